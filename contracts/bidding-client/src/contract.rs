@@ -0,0 +1,291 @@
+use bidding::msg::{BidItemInput, BidItemPricesResp, BidderBidResp, CrankStatusResp, ExecuteMsg, PagedResp, QueryMsg, VerifyCursor, VerifyStateResp};
+use bidding::state::{Auction, AuctionId, AuctionStatus, Bid, BidId, BidItem, BidItemId, BidItemKey, BidItemStatus, BidKey, Bidder, BidderAccess, BidderStats, Config, PauseScope, PayoutMode, RemoteSettlement};
+use cosmwasm_std::{to_json_binary, Addr, Coin, CosmosMsg, QuerierWrapper, QueryRequest, StdResult, Timestamp, Uint128, WasmMsg, WasmQuery};
+use serde::de::DeserializeOwned;
+
+/// An auction to create, the fields left out get the contract's defaults.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewAuction {
+    pub name: String,
+    pub description: Option<String>,
+    pub terms: Option<String>,
+    pub ends_at: Option<Timestamp>,
+    pub payout_mode: Option<PayoutMode>,
+    /// The seller the admin lists the auction for.
+    pub owner: Option<String>,
+    pub bid_items: Vec<BidItemInput>,
+}
+
+impl NewAuction {
+    pub fn new(name: impl Into<String>, bid_items: Vec<BidItemInput>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            terms: None,
+            ends_at: None,
+            payout_mode: None,
+            owner: None,
+            bid_items,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_terms(mut self, terms: impl Into<String>) -> Self {
+        self.terms = Some(terms.into());
+        self
+    }
+
+    pub fn with_ends_at(mut self, ends_at: Timestamp) -> Self {
+        self.ends_at = Some(ends_at);
+        self
+    }
+
+    pub fn with_payout_mode(mut self, payout_mode: PayoutMode) -> Self {
+        self.payout_mode = Some(payout_mode);
+        self
+    }
+
+    pub fn with_owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+}
+
+/// Builds messages and runs queries for a deployed bidding contract.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BiddingContract(pub Addr);
+
+impl BiddingContract {
+    pub fn new(addr: Addr) -> Self {
+        Self(addr)
+    }
+
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+
+    /// Any execute message, the typed helpers below cover the common ones.
+    pub fn call(&self, msg: &ExecuteMsg, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(msg)?,
+            funds,
+        }
+        .into())
+    }
+
+    /// Any query message, e.g. to send it from another contract's `QueryRequest`. The typed
+    /// `query_*` helpers below run the common ones.
+    pub fn query_msg(&self, msg: &QueryMsg) -> StdResult<WasmQuery> {
+        Ok(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(msg)?,
+        })
+    }
+
+    fn query<T: DeserializeOwned>(&self, querier: &QuerierWrapper, msg: &QueryMsg) -> StdResult<T> {
+        querier.query(&QueryRequest::Wasm(self.query_msg(msg)?))
+    }
+
+    pub fn create_auction(&self, auction: NewAuction) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::CreateAuction {
+            name: auction.name,
+            description: auction.description,
+            terms: auction.terms,
+            ends_at: auction.ends_at,
+            payout_mode: auction.payout_mode,
+            owner: auction.owner,
+            bid_items: auction.bid_items,
+        }, vec![])
+    }
+
+    pub fn add_bid_items(&self, auction_id: AuctionId, bid_items: Vec<BidItemInput>) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::AddBidItems { auction_id, bid_items }, vec![])
+    }
+
+    pub fn set_auction_state(&self, id: AuctionId, status: AuctionStatus) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::SetAuctionState { id, status }, vec![])
+    }
+
+    pub fn cancel_auction(&self, id: AuctionId) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::CancelAuction { id }, vec![])
+    }
+
+    pub fn place_bid(&self, bid_item_id: BidItemId, coin: Coin) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::PlaceBid { bid_item_id, amount: None }, vec![coin])
+    }
+
+    pub fn place_bid_from_deposit(&self, bid_item_id: BidItemId, amount: Uint128) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::PlaceBid { bid_item_id, amount: Some(amount) }, vec![])
+    }
+
+    pub fn advance_crank(&self, max_items: Option<u32>) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::AdvanceCrank { max_items }, vec![])
+    }
+
+    pub fn deposit(&self, coin: Coin) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::Deposit {}, vec![coin])
+    }
+
+    pub fn withdraw(&self, amount: Option<Uint128>) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::Withdraw { amount }, vec![])
+    }
+
+    pub fn claim(&self) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::Claim {}, vec![])
+    }
+
+    pub fn set_bidder_access(&self, auction_id: AuctionId, access: BidderAccess) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::SetBidderAccess { auction_id, access }, vec![])
+    }
+
+    pub fn update_allowlist(&self, auction_id: AuctionId, add: Vec<Addr>, remove: Vec<Addr>) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::UpdateAllowlist { auction_id, add, remove }, vec![])
+    }
+
+    pub fn deny_addresses(&self, addresses: Vec<String>) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::DenyAddresses { addresses }, vec![])
+    }
+
+    pub fn allow_addresses(&self, addresses: Vec<String>) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::AllowAddresses { addresses }, vec![])
+    }
+
+    pub fn resolve_escrow(&self, address: String, recipient: Option<String>) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::ResolveEscrow { address, recipient }, vec![])
+    }
+
+    pub fn add_hook(&self, contract: Addr) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::AddHook { contract }, vec![])
+    }
+
+    pub fn remove_hook(&self, contract: Addr) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::RemoveHook { contract }, vec![])
+    }
+
+    pub fn transfer(&self, channel_id: String, remote_address: String, coin: Coin) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::Transfer { channel_id, remote_address }, vec![coin])
+    }
+
+    pub fn add_transfer_channel(&self, channel_id: String) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::AddTransferChannel { channel_id }, vec![])
+    }
+
+    pub fn remove_transfer_channel(&self, channel_id: String) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::RemoveTransferChannel { channel_id }, vec![])
+    }
+
+    pub fn retry_settlement(&self, bid_id: BidId, channel_id: Option<String>) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::RetrySettlement { bid_id, channel_id }, vec![])
+    }
+
+    pub fn reindex(&self, limit: Option<u32>) -> StdResult<CosmosMsg> {
+        self.call(&ExecuteMsg::Reindex { limit }, vec![])
+    }
+
+    pub fn query_admin(&self, querier: &QuerierWrapper) -> StdResult<Addr> {
+        self.query(querier, &QueryMsg::Admin {})
+    }
+
+    pub fn query_config(&self, querier: &QuerierWrapper) -> StdResult<Config> {
+        self.query(querier, &QueryMsg::Config {})
+    }
+
+    pub fn query_auction(&self, querier: &QuerierWrapper, id: AuctionId) -> StdResult<Auction> {
+        self.query(querier, &QueryMsg::Auction { id })
+    }
+
+    /// Pass the page's `next` as `start_after` for the next page.
+    pub fn query_auctions_paged(&self, querier: &QuerierWrapper, status: Option<AuctionStatus>, start_after: Option<AuctionId>, limit: Option<u32>) -> StdResult<PagedResp<AuctionId, Auction>> {
+        self.query(querier, &QueryMsg::Auctions { status, start_after, start_before: None, limit, order: None })
+    }
+
+    pub fn query_bid_item(&self, querier: &QuerierWrapper, id: BidItemId) -> StdResult<BidItem> {
+        self.query(querier, &QueryMsg::BidItem { id })
+    }
+
+    pub fn query_bid_items_paged(&self, querier: &QuerierWrapper, status: Option<BidItemStatus>, start_after: Option<BidItemKey>, limit: Option<u32>) -> StdResult<PagedResp<BidItemKey, BidItem>> {
+        self.query(querier, &QueryMsg::BidItems { status, start_after, start_before: None, limit, order: None })
+    }
+
+    pub fn query_bid_items_by_auction_paged(&self, querier: &QuerierWrapper, auction_id: AuctionId, start_after: Option<BidItemId>, limit: Option<u32>) -> StdResult<PagedResp<BidItemId, BidItem>> {
+        self.query(querier, &QueryMsg::BidItemsByAuctionId { auction_id, start_after, start_before: None, limit, order: None })
+    }
+
+    /// The page only moves through `bid_items_ids`, an unknown id fails the whole query.
+    pub fn query_bid_items_by_id_paged(&self, querier: &QuerierWrapper, bid_items_ids: Vec<BidItemId>, start_after: Option<BidItemId>, limit: Option<u32>) -> StdResult<PagedResp<BidItemId, BidItem>> {
+        self.query(querier, &QueryMsg::BidItemsById { bid_items_ids, start_after, limit })
+    }
+
+    pub fn query_bids_by_bidder_paged(&self, querier: &QuerierWrapper, bidder: Bidder, start_after: Option<BidKey>, limit: Option<u32>) -> StdResult<PagedResp<BidKey, BidderBidResp>> {
+        self.query(querier, &QueryMsg::BidsByBidder { bidder, start_after, start_before: None, limit, order: None })
+    }
+
+    pub fn query_bids_by_bid_item_paged(&self, querier: &QuerierWrapper, bid_item_id: BidItemId, start_after: Option<BidId>, limit: Option<u32>) -> StdResult<PagedResp<BidId, Bid>> {
+        self.query(querier, &QueryMsg::BidsByBidItem { bid_item_id, start_after, start_before: None, limit, order: None })
+    }
+
+    pub fn query_winning_bid(&self, querier: &QuerierWrapper, bid_item_id: BidItemId) -> StdResult<Option<(BidId, Bid)>> {
+        self.query(querier, &QueryMsg::WinningBid { bid_item_id })
+    }
+
+    pub fn query_bid_item_prices(&self, querier: &QuerierWrapper, bid_item_id: BidItemId) -> StdResult<BidItemPricesResp> {
+        self.query(querier, &QueryMsg::BidItemPrices { bid_item_id })
+    }
+
+    pub fn query_crank_status(&self, querier: &QuerierWrapper, start_after: Option<AuctionId>, limit: Option<u32>) -> StdResult<CrankStatusResp> {
+        self.query(querier, &QueryMsg::CrankStatus { start_after, limit })
+    }
+
+    pub fn query_paused(&self, querier: &QuerierWrapper) -> StdResult<Vec<PauseScope>> {
+        self.query(querier, &QueryMsg::Paused {})
+    }
+
+    pub fn query_claimable(&self, querier: &QuerierWrapper, address: Addr) -> StdResult<Vec<Coin>> {
+        self.query(querier, &QueryMsg::Claimable { address })
+    }
+
+    pub fn query_deposit_balance(&self, querier: &QuerierWrapper, address: Addr) -> StdResult<Uint128> {
+        self.query(querier, &QueryMsg::DepositBalance { address })
+    }
+
+    pub fn query_is_bidder_allowed(&self, querier: &QuerierWrapper, auction_id: AuctionId, bidder: Addr) -> StdResult<bool> {
+        self.query(querier, &QueryMsg::IsBidderAllowed { auction_id, bidder })
+    }
+
+    pub fn query_bidder_stats(&self, querier: &QuerierWrapper, address: Addr) -> StdResult<BidderStats> {
+        self.query(querier, &QueryMsg::BidderStats { address })
+    }
+
+    /// The denylisted addresses and when they were denied.
+    pub fn query_denylist_paged(&self, querier: &QuerierWrapper, start_after: Option<Addr>, limit: Option<u32>) -> StdResult<PagedResp<Addr, Timestamp>> {
+        self.query(querier, &QueryMsg::Denylist { start_after, start_before: None, limit, order: None })
+    }
+
+    /// The hook contracts and when they were added.
+    pub fn query_hooks_paged(&self, querier: &QuerierWrapper, start_after: Option<Addr>, limit: Option<u32>) -> StdResult<PagedResp<Addr, Timestamp>> {
+        self.query(querier, &QueryMsg::Hooks { start_after, start_before: None, limit, order: None })
+    }
+
+    pub fn query_denied_escrow(&self, querier: &QuerierWrapper, address: Addr) -> StdResult<Uint128> {
+        self.query(querier, &QueryMsg::DeniedEscrow { address })
+    }
+
+    /// The channels remote bids are taken from and when they were added.
+    pub fn query_transfer_channels_paged(&self, querier: &QuerierWrapper, start_after: Option<String>, limit: Option<u32>) -> StdResult<PagedResp<String, Timestamp>> {
+        self.query(querier, &QueryMsg::TransferChannels { start_after, start_before: None, limit, order: None })
+    }
+
+    /// The refunds waiting to be retried.
+    pub fn query_undelivered_settlements_paged(&self, querier: &QuerierWrapper, start_after: Option<BidId>, limit: Option<u32>) -> StdResult<PagedResp<BidId, RemoteSettlement>> {
+        self.query(querier, &QueryMsg::UndeliveredSettlements { start_after, start_before: None, limit, order: None })
+    }
+
+    pub fn query_verify_state(&self, querier: &QuerierWrapper, auction_id: Option<AuctionId>, start_after: Option<VerifyCursor>, limit: Option<u32>) -> StdResult<VerifyStateResp> {
+        self.query(querier, &QueryMsg::VerifyState { auction_id, start_after, limit })
+    }
+}
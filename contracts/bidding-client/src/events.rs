@@ -0,0 +1,325 @@
+use std::str::FromStr;
+
+use bidding::events::{AuctionCreated, AuctionStateChanged, BidItemAdded, BidItemCancelled, BidItemRemoved, BidItemUpdated, BidOutbid, BidPlaced, HookFailed, ItemSettled, PayoutEscrowed, RefundDestination, RefundIssued, ReserveNotMet, SettlementDeferred, SettlementUndelivered, TransferRejected};
+use bidding::msg::BidItemInput;
+use bidding::state::{AuctionId, AuctionStatus, BidId, BidItemId, BidItemStatus, PayoutMode};
+use cosmwasm_std::{from_json, Addr, Event, StdError, StdResult, Timestamp, Uint128};
+use serde::de::DeserializeOwned;
+
+/// Typed events that can be read back from the events of a transaction or a submessage reply.
+pub trait FromEvent: Sized {
+    const TYPE: &'static str;
+
+    fn from_event(event: &Event) -> StdResult<Self>;
+}
+
+/// Parses every event of the given type, with or without the `wasm-` prefix added on chain.
+pub fn parse_events<T: FromEvent>(events: &[Event]) -> StdResult<Vec<T>> {
+    events
+        .iter()
+        .filter(|event| event.ty.strip_prefix("wasm-").unwrap_or(&event.ty) == T::TYPE)
+        .map(T::from_event)
+        .collect()
+}
+
+/// Parses the first event of the given type.
+pub fn find_event<T: FromEvent>(events: &[Event]) -> StdResult<Option<T>> {
+    Ok(parse_events(events)?.into_iter().next())
+}
+
+fn get_opt<'a>(event: &'a Event, key: &str) -> Option<&'a str> {
+    event.attributes
+        .iter()
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.as_str())
+}
+
+fn get<'a>(event: &'a Event, key: &str) -> StdResult<&'a str> {
+    get_opt(event, key).ok_or_else(|| StdError::generic_err(format!("{} event is missing the {key} attribute", event.ty)))
+}
+
+fn parse<T: FromStr>(event: &Event, key: &str) -> StdResult<T> {
+    get(event, key)?
+        .parse()
+        .map_err(|_| StdError::generic_err(format!("Invalid {key} in {} event", event.ty)))
+}
+
+fn parse_json<T: DeserializeOwned>(event: &Event, key: &str) -> StdResult<T> {
+    from_json(get(event, key)?)
+}
+
+fn auction_status(value: &str) -> StdResult<AuctionStatus> {
+    [AuctionStatus::Active, AuctionStatus::Suspended, AuctionStatus::PendingCompletion, AuctionStatus::Completed, AuctionStatus::Cancelled]
+        .into_iter()
+        .find(|status| status.as_str() == value)
+        .ok_or_else(|| StdError::generic_err(format!("Unknown auction status {value}")))
+}
+
+fn bid_item_status(value: &str) -> StdResult<BidItemStatus> {
+    [BidItemStatus::Active, BidItemStatus::Completed, BidItemStatus::Cancelled]
+        .into_iter()
+        .find(|status| status.as_str() == value)
+        .ok_or_else(|| StdError::generic_err(format!("Unknown bid item status {value}")))
+}
+
+fn payout_mode(value: &str) -> StdResult<PayoutMode> {
+    [PayoutMode::Push, PayoutMode::Pull]
+        .into_iter()
+        .find(|mode| mode.as_str() == value)
+        .ok_or_else(|| StdError::generic_err(format!("Unknown payout mode {value}")))
+}
+
+fn bid_item_input(event: &Event) -> StdResult<BidItemInput> {
+    Ok(BidItemInput {
+        name: get(event, "name")?.to_string(),
+        description: get_opt(event, "description").map(str::to_string),
+        media_uris: parse_json(event, "media_uris")?,
+        category: get_opt(event, "category").map(str::to_string),
+        attributes: parse_json(event, "attributes")?,
+        starting_price: get_opt(event, "starting_price").map(|_| parse_json(event, "starting_price")).transpose()?,
+        reserve_price: get_opt(event, "reserve_price").map(|_| parse_json(event, "reserve_price")).transpose()?,
+    })
+}
+
+impl FromEvent for AuctionCreated {
+    const TYPE: &'static str = "auction_created";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(AuctionCreated {
+            auction_id: AuctionId(parse(event, "auction_id")?),
+            name: get(event, "name")?.to_string(),
+            description: get_opt(event, "description").map(str::to_string),
+            terms: get_opt(event, "terms").map(str::to_string),
+            owner: Addr::unchecked(get(event, "owner")?),
+            bid_items: parse(event, "bid_items")?,
+            ends_at: get_opt(event, "ends_at").map(|_| parse(event, "ends_at").map(Timestamp::from_seconds)).transpose()?,
+            payout_mode: payout_mode(get(event, "payout_mode")?)?,
+        })
+    }
+}
+
+impl FromEvent for BidItemAdded {
+    const TYPE: &'static str = "bid_item_added";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(BidItemAdded {
+            auction_id: AuctionId(parse(event, "auction_id")?),
+            bid_item_id: BidItemId(parse(event, "bid_item_id")?),
+            item: bid_item_input(event)?,
+        })
+    }
+}
+
+impl FromEvent for BidItemUpdated {
+    const TYPE: &'static str = "bid_item_updated";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(BidItemUpdated {
+            auction_id: AuctionId(parse(event, "auction_id")?),
+            bid_item_id: BidItemId(parse(event, "bid_item_id")?),
+            item: bid_item_input(event)?,
+        })
+    }
+}
+
+impl FromEvent for BidItemRemoved {
+    const TYPE: &'static str = "bid_item_removed";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(BidItemRemoved {
+            auction_id: AuctionId(parse(event, "auction_id")?),
+            bid_item_id: BidItemId(parse(event, "bid_item_id")?),
+        })
+    }
+}
+
+impl FromEvent for BidItemCancelled {
+    const TYPE: &'static str = "bid_item_cancelled";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(BidItemCancelled {
+            auction_id: AuctionId(parse(event, "auction_id")?),
+            bid_item_id: BidItemId(parse(event, "bid_item_id")?),
+        })
+    }
+}
+
+impl FromEvent for BidPlaced {
+    const TYPE: &'static str = "bid_placed";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(BidPlaced {
+            auction_id: AuctionId(parse(event, "auction_id")?),
+            bid_item_id: BidItemId(parse(event, "bid_item_id")?),
+            bid_id: BidId(parse(event, "bid_id")?),
+            bidder: get(event, "bidder")?.parse()?,
+            amount: Uint128::new(parse(event, "amount")?),
+            denom: get(event, "denom")?.to_string(),
+            from_deposit: parse(event, "from_deposit")?,
+        })
+    }
+}
+
+impl FromEvent for BidOutbid {
+    const TYPE: &'static str = "bid_outbid";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(BidOutbid {
+            bid_item_id: BidItemId(parse(event, "bid_item_id")?),
+            bid_id: BidId(parse(event, "bid_id")?),
+            bidder: get(event, "bidder")?.parse()?,
+            amount: Uint128::new(parse(event, "amount")?),
+            outbid_by: BidId(parse(event, "outbid_by")?),
+        })
+    }
+}
+
+impl FromEvent for AuctionStateChanged {
+    const TYPE: &'static str = "auction_state_changed";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(AuctionStateChanged {
+            auction_id: AuctionId(parse(event, "auction_id")?),
+            previous: auction_status(get(event, "previous")?)?,
+            next: auction_status(get(event, "next")?)?,
+        })
+    }
+}
+
+impl FromEvent for ItemSettled {
+    const TYPE: &'static str = "item_settled";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(ItemSettled {
+            auction_id: AuctionId(parse(event, "auction_id")?),
+            bid_item_id: BidItemId(parse(event, "bid_item_id")?),
+            status: bid_item_status(get(event, "status")?)?,
+            winner: get_opt(event, "winner").map(str::parse).transpose()?,
+            amount: get_opt(event, "amount").map(|_| parse(event, "amount").map(Uint128::new)).transpose()?,
+            denom: get_opt(event, "denom").unwrap_or_default().to_string(),
+        })
+    }
+}
+
+impl FromEvent for RefundIssued {
+    const TYPE: &'static str = "refund_issued";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        let destination = match get(event, "destination")? {
+            "wallet" => RefundDestination::Wallet,
+            "claimable" => RefundDestination::Claimable,
+            "deposit" => RefundDestination::Deposit,
+            "escrow" => RefundDestination::Escrow,
+            "remote" => RefundDestination::Remote,
+            other => return Err(StdError::generic_err(format!("Unknown refund destination {other}"))),
+        };
+
+        Ok(RefundIssued {
+            bid_item_id: BidItemId(parse(event, "bid_item_id")?),
+            bid_id: BidId(parse(event, "bid_id")?),
+            bidder: get(event, "bidder")?.parse()?,
+            amount: Uint128::new(parse(event, "amount")?),
+            denom: get(event, "denom")?.to_string(),
+            destination,
+        })
+    }
+}
+
+impl FromEvent for PayoutEscrowed {
+    const TYPE: &'static str = "payout_escrowed";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(PayoutEscrowed {
+            bid_item_id: BidItemId(parse(event, "bid_item_id")?),
+            bid_id: BidId(parse(event, "bid_id")?),
+            bidder: Addr::unchecked(get(event, "bidder")?),
+            amount: Uint128::new(parse(event, "amount")?),
+            denom: get(event, "denom")?.to_string(),
+        })
+    }
+}
+
+impl FromEvent for HookFailed {
+    const TYPE: &'static str = "hook_failed";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(HookFailed {
+            contract: Addr::unchecked(get(event, "contract")?),
+            error: get(event, "error")?.to_string(),
+        })
+    }
+}
+
+impl FromEvent for SettlementUndelivered {
+    const TYPE: &'static str = "settlement_undelivered";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(SettlementUndelivered {
+            bid_id: BidId(parse(event, "bid_id")?),
+            channel_id: get(event, "channel_id")?.to_string(),
+            reason: get(event, "reason")?.to_string(),
+        })
+    }
+}
+
+impl FromEvent for TransferRejected {
+    const TYPE: &'static str = "transfer_rejected";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(TransferRejected {
+            channel_id: get(event, "channel_id")?.to_string(),
+            sequence: parse(event, "sequence")?,
+            sender: get(event, "sender")?.to_string(),
+            amount: Uint128::new(parse(event, "amount")?),
+            reason: get(event, "reason")?.to_string(),
+        })
+    }
+}
+
+impl FromEvent for ReserveNotMet {
+    const TYPE: &'static str = "reserve_not_met";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(ReserveNotMet {
+            auction_id: AuctionId(parse(event, "auction_id")?),
+            bid_item_id: BidItemId(parse(event, "bid_item_id")?),
+            highest_bid: parse(event, "highest_bid")?,
+            reserve_price: parse(event, "reserve_price")?,
+        })
+    }
+}
+
+impl FromEvent for SettlementDeferred {
+    const TYPE: &'static str = "settlement_deferred";
+
+    fn from_event(event: &Event) -> StdResult<Self> {
+        Ok(SettlementDeferred {
+            auction_id: AuctionId(parse(event, "auction_id")?),
+            bid_item_id: BidItemId(parse(event, "bid_item_id")?),
+            reason: get(event, "reason")?.to_string(),
+        })
+    }
+}
+
+/// The `wasm` attributes of an `advance_crank` response.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrankResult {
+    pub processed_bid_items: u32,
+    pub processed_bids: u64,
+    pub crank_reward: Uint128,
+}
+
+impl CrankResult {
+    /// Reads the first `wasm` event with `action=advance_crank`.
+    pub fn from_events(events: &[Event]) -> StdResult<Option<Self>> {
+        let Some(event) = events.iter().find(|event| event.ty == "wasm" && get_opt(event, "action") == Some("advance_crank")) else {
+            return Ok(None);
+        };
+
+        Ok(Some(CrankResult {
+            processed_bid_items: parse(event, "processed_bid_items")?,
+            processed_bids: parse(event, "processed_bids")?,
+            crank_reward: Uint128::new(parse(event, "crank_reward")?),
+        }))
+    }
+}
@@ -0,0 +1,8 @@
+pub mod contract;
+pub mod events;
+
+#[cfg(test)]
+mod tests;
+
+pub use contract::{BiddingContract, NewAuction};
+pub use events::{find_event, parse_events, CrankResult, FromEvent};
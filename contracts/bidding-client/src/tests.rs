@@ -0,0 +1,125 @@
+const _INITIAL_BALANCE: u128 = 200_000;
+const _DENOM: &str = "eth";
+
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod tests {
+    use bidding::contract::{execute, instantiate, query};
+    use bidding::events::{AuctionCreated, BidItemAdded, BidOutbid, BidPlaced, ItemSettled, RefundDestination, RefundIssued};
+    use bidding::msg::{BidItemInput, InstantiateMsg};
+    use bidding::state::{AuctionStatus, BidItemId, BidItemStatus, Bidder};
+    use cosmwasm_std::{coin, Addr, Coin, Uint128};
+    use cw_multi_test::{App, ContractWrapper, Executor};
+
+    use crate::tests::{_DENOM, _INITIAL_BALANCE};
+    use crate::{find_event, parse_events, BiddingContract, CrankResult, NewAuction};
+
+    #[test]
+    fn typed_messages_and_events() {
+        let mut app = App::default();
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let owner = app.api().addr_make("owner");
+        let users: Vec<Addr> = (0..2).map(|num| app.api().addr_make(&format!("user{num}"))).collect();
+
+        let addr = app
+            .instantiate_contract(
+                code_id,
+                owner.clone(),
+                &InstantiateMsg {
+                    admin: owner.clone(),
+                },
+                &[],
+                "Contract",
+                None,
+            )
+            .unwrap();
+
+        app.init_modules(|router, _, storage| {
+            for account in &users {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        account,
+                        vec![Coin::new(_INITIAL_BALANCE, _DENOM)],
+                    )
+                    .unwrap();
+            }
+        });
+
+        let contract = BiddingContract::new(addr.clone());
+
+        let resp = app
+            .execute(owner.clone(), contract.create_auction(NewAuction::new("TestAuction #1", vec![ BidItemInput::new("My first bid item") ]).with_description("Paintings")).unwrap())
+            .unwrap();
+
+        let created: AuctionCreated = find_event(&resp.events).unwrap().unwrap();
+        assert_eq!(created.owner, owner);
+        assert_eq!(created.name, "TestAuction #1");
+        assert_eq!(created.description.as_deref(), Some("Paintings"));
+        assert_eq!(created.bid_items, 1);
+
+        let added: Vec<BidItemAdded> = parse_events(&resp.events).unwrap();
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].auction_id, created.auction_id);
+        assert_eq!(added[0].item, BidItemInput::new("My first bid item"));
+
+        let bid_item_id = added[0].bid_item_id;
+        let mut placed: Vec<BidPlaced> = vec![];
+
+        for (num, user) in users.iter().enumerate() {
+            let resp = app
+                .execute(user.clone(), contract.place_bid(bid_item_id, coin(10 * (num as u128 + 1), _DENOM)).unwrap())
+                .unwrap();
+
+            placed.push(find_event(&resp.events).unwrap().unwrap());
+
+            if num == 1 {
+                let outbid: BidOutbid = find_event(&resp.events).unwrap().unwrap();
+                assert_eq!(outbid.bid_id, placed[0].bid_id);
+                assert_eq!(outbid.outbid_by, placed[1].bid_id);
+            }
+        }
+
+        assert_eq!(placed[1].bidder, Bidder::Local(users[1].clone()));
+        assert_eq!(placed[1].amount, Uint128::new(20));
+
+        let winning = contract.query_winning_bid(&app.wrap(), bid_item_id).unwrap();
+
+        assert_eq!(winning.unwrap().0, placed[1].bid_id);
+
+        app.execute(owner.clone(), contract.set_auction_state(created.auction_id, AuctionStatus::PendingCompletion).unwrap())
+            .unwrap();
+
+        let resp = app
+            .execute(owner.clone(), contract.advance_crank(None).unwrap())
+            .unwrap();
+
+        let crank = CrankResult::from_events(&resp.events).unwrap().unwrap();
+        assert_eq!(crank, CrankResult { processed_bid_items: 1, processed_bids: 2, crank_reward: Uint128::zero() });
+
+        let settled: ItemSettled = find_event(&resp.events).unwrap().unwrap();
+        assert_eq!(settled.status, BidItemStatus::Completed);
+        assert_eq!(settled.winner, Some(Bidder::Local(users[1].clone())));
+
+        let refund: RefundIssued = find_event(&resp.events).unwrap().unwrap();
+        assert_eq!(refund.bidder, Bidder::Local(users[0].clone()));
+        assert_eq!(refund.destination, RefundDestination::Wallet);
+
+        let page = contract.query_auctions_paged(&app.wrap(), Some(AuctionStatus::Completed), None, Some(10)).unwrap();
+
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].0, created.auction_id);
+        assert_eq!(page.next, None);
+
+        // Known ids are returned in the given order, an unknown one fails the query
+        let page = contract.query_bid_items_by_id_paged(&app.wrap(), vec![bid_item_id], None, None).unwrap();
+        assert_eq!(page.items.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![bid_item_id]);
+
+        let unknown = BidItemId(bid_item_id.0 + 1);
+        contract.query_bid_items_by_id_paged(&app.wrap(), vec![bid_item_id, unknown], None, None).unwrap_err();
+    }
+}
@@ -0,0 +1,361 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use bidding::events::{AuctionCreated, AuctionStateChanged, BidItemAdded, BidItemCancelled, BidItemRemoved, BidItemUpdated, BidOutbid, BidPlaced, HookFailed, ItemSettled, PayoutEscrowed, RefundIssued, ReserveNotMet, SettlementUndelivered, TransferRejected};
+use bidding::msg::BidItemInput;
+use bidding::state::Bidder;
+
+use crate::events::{EventPosition, IndexedEvent};
+
+// Mirrors the auctions, bid items and bids of `bidding::state`, with their totals, plus the
+// settlement of every item, the refunds of the losing bids and what went wrong on the way.
+// Amounts are stored as text since they are u128s, lists and prices as the JSON of the events.
+// Events are applied once per position, so feeds can be ingested again.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS auctions (
+    auction_id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT,
+    terms TEXT,
+    owner TEXT NOT NULL,
+    status TEXT NOT NULL,
+    ends_at INTEGER,
+    payout_mode TEXT NOT NULL,
+    available_bid_items INTEGER NOT NULL DEFAULT 0,
+    total_bids INTEGER NOT NULL DEFAULT 0,
+    total_coins TEXT NOT NULL DEFAULT '0'
+);
+
+CREATE TABLE IF NOT EXISTS bid_items (
+    bid_item_id INTEGER PRIMARY KEY,
+    auction_id INTEGER NOT NULL REFERENCES auctions (auction_id),
+    name TEXT NOT NULL,
+    description TEXT,
+    category TEXT,
+    media_uris TEXT NOT NULL,
+    attributes TEXT NOT NULL,
+    starting_price TEXT,
+    reserve_price TEXT,
+    status TEXT NOT NULL,
+    winning_bid_id INTEGER,
+    winner TEXT,
+    total_bids INTEGER NOT NULL DEFAULT 0,
+    total_coins TEXT NOT NULL DEFAULT '0'
+);
+
+CREATE TABLE IF NOT EXISTS bids (
+    bid_id INTEGER PRIMARY KEY,
+    bid_item_id INTEGER NOT NULL REFERENCES bid_items (bid_item_id),
+    bidder TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    denom TEXT NOT NULL,
+    from_deposit INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS settlements (
+    bid_item_id INTEGER PRIMARY KEY REFERENCES bid_items (bid_item_id),
+    auction_id INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    winner TEXT,
+    amount TEXT,
+    denom TEXT
+);
+
+CREATE TABLE IF NOT EXISTS refunds (
+    bid_id INTEGER PRIMARY KEY,
+    bid_item_id INTEGER NOT NULL,
+    bidder TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    denom TEXT NOT NULL,
+    destination TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS escrowed_payouts (
+    bid_id INTEGER PRIMARY KEY,
+    bid_item_id INTEGER NOT NULL,
+    bidder TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    denom TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS unmet_reserves (
+    bid_item_id INTEGER PRIMARY KEY,
+    auction_id INTEGER NOT NULL,
+    highest_bid TEXT NOT NULL,
+    reserve_price TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS undelivered_settlements (
+    id INTEGER PRIMARY KEY,
+    bid_id INTEGER NOT NULL,
+    channel_id TEXT NOT NULL,
+    reason TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS rejected_transfers (
+    channel_id TEXT NOT NULL,
+    sequence INTEGER NOT NULL,
+    sender TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    PRIMARY KEY (channel_id, sequence)
+);
+
+CREATE TABLE IF NOT EXISTS hook_failures (
+    id INTEGER PRIMARY KEY,
+    contract TEXT NOT NULL,
+    error TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS applied_events (
+    height INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    PRIMARY KEY (height, tx_hash, event_index)
+);
+";
+
+pub struct Indexer {
+    conn: Connection,
+}
+
+impl Indexer {
+    /// Opens the database, creating the tables if they don't exist yet.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Deletes the database and starts a new one.
+    pub fn rebuild(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+
+        Self::open(path)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Applies the events by height, all of them or none, and returns how many were applied.
+    /// Events of the same height keep their order, so inputs can come in any file order. Every
+    /// event needs its position: the ones already applied there are skipped, and new ones can't
+    /// be older than what the database already indexed.
+    pub fn apply(&mut self, events: &[(Option<EventPosition>, IndexedEvent)]) -> Result<usize> {
+        let mut ordered = vec![];
+
+        for (position, event) in events {
+            let Some(position) = position else {
+                bail!("Events need their position, only transaction results with a height and txhash can be indexed");
+            };
+
+            ordered.push((position, event));
+        }
+
+        ordered.sort_by_key(|(position, _)| position.height);
+
+        let tx = self.conn.transaction()?;
+        let indexed_height: Option<u64> = tx.query_row("SELECT MAX(height) FROM applied_events", [], |row| row.get(0))?;
+        let mut applied = 0;
+
+        for (position, event) in ordered {
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO applied_events (height, tx_hash, event_index) VALUES (?1, ?2, ?3)",
+                params![position.height, position.tx_hash, position.index],
+            )?;
+
+            if inserted == 0 {
+                continue;
+            }
+
+            // Applying it after newer events would leave the tables in a state the contract never had
+            if let Some(indexed_height) = indexed_height.filter(|indexed_height| position.height < *indexed_height) {
+                bail!("Event at height {} is older than the indexed height {indexed_height}, the database needs a rebuild", position.height);
+            }
+
+            apply_event(&tx, event)?;
+            applied += 1;
+        }
+
+        tx.commit()?;
+
+        Ok(applied)
+    }
+}
+
+fn apply_event(conn: &Connection, event: &IndexedEvent) -> Result<()> {
+    match event {
+        IndexedEvent::AuctionCreated(AuctionCreated { auction_id, name, description, terms, owner, ends_at, payout_mode, .. }) => {
+            conn.execute(
+                "INSERT INTO auctions (auction_id, name, description, terms, owner, status, ends_at, payout_mode) VALUES (?1, ?2, ?3, ?4, ?5, 'active', ?6, ?7)",
+                params![auction_id.0, name, description, terms, owner.as_str(), ends_at.map(|ends_at| ends_at.seconds()), payout_mode.as_str()],
+            )?;
+        },
+        IndexedEvent::BidItemAdded(BidItemAdded { auction_id, bid_item_id, item }) => {
+            let details = BidItemDetails::new(item)?;
+
+            conn.execute(
+                "INSERT INTO bid_items (bid_item_id, auction_id, name, description, category, media_uris, attributes, starting_price, reserve_price, status) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 'active')",
+                params![bid_item_id.0, auction_id.0, item.name, item.description, item.category, details.media_uris, details.attributes, details.starting_price, details.reserve_price],
+            )?;
+            conn.execute("UPDATE auctions SET available_bid_items = available_bid_items + 1 WHERE auction_id = ?1", params![auction_id.0])?;
+        },
+        IndexedEvent::BidItemUpdated(BidItemUpdated { bid_item_id, item, .. }) => {
+            let details = BidItemDetails::new(item)?;
+
+            conn.execute(
+                "UPDATE bid_items SET name = ?1, description = ?2, category = ?3, media_uris = ?4, attributes = ?5, starting_price = ?6, reserve_price = ?7 WHERE bid_item_id = ?8",
+                params![item.name, item.description, item.category, details.media_uris, details.attributes, details.starting_price, details.reserve_price, bid_item_id.0],
+            )?;
+        },
+        IndexedEvent::BidItemCancelled(BidItemCancelled { bid_item_id, .. }) => {
+            // The item is deleted with a `bid_item_removed` once the crank refunded its bids
+            conn.execute("UPDATE bid_items SET status = 'cancelled' WHERE bid_item_id = ?1", params![bid_item_id.0])?;
+        },
+        IndexedEvent::BidItemRemoved(BidItemRemoved { auction_id, bid_item_id }) => {
+            // The auction loses the totals of the item, like in the contract
+            let (item_bids, item_coins) = totals(conn, "bid_items", "bid_item_id", bid_item_id.0)?;
+            update_totals(conn, "auctions", "auction_id", auction_id.0, |bids, coins| {
+                (bids.saturating_sub(item_bids), coins.saturating_sub(item_coins))
+            })?;
+            conn.execute("UPDATE auctions SET available_bid_items = MAX(available_bid_items - 1, 0) WHERE auction_id = ?1", params![auction_id.0])?;
+
+            // The contract drops the bids of removed items too, their refunds are kept
+            conn.execute("DELETE FROM bids WHERE bid_item_id = ?1", params![bid_item_id.0])?;
+            conn.execute("DELETE FROM bid_items WHERE bid_item_id = ?1", params![bid_item_id.0])?;
+        },
+        IndexedEvent::BidPlaced(BidPlaced { auction_id, bid_item_id, bid_id, bidder, amount, denom, from_deposit }) => {
+            conn.execute(
+                "INSERT INTO bids (bid_id, bid_item_id, bidder, amount, denom, from_deposit) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![bid_id.0, bid_item_id.0, bidder.to_string(), amount.to_string(), denom, from_deposit],
+            )?;
+
+            let amount = amount.u128();
+            update_totals(conn, "bid_items", "bid_item_id", bid_item_id.0, |bids, coins| (bids + 1, coins + amount))?;
+            update_totals(conn, "auctions", "auction_id", auction_id.0, |bids, coins| (bids + 1, coins + amount))?;
+
+            // The first bid of an item is the winning one until it's outbid
+            let winning_bid_id: Option<u32> = conn
+                .query_row("SELECT winning_bid_id FROM bid_items WHERE bid_item_id = ?1", params![bid_item_id.0], |row| row.get(0))
+                .optional()?
+                .flatten();
+
+            if winning_bid_id.is_none() {
+                conn.execute("UPDATE bid_items SET winning_bid_id = ?1 WHERE bid_item_id = ?2", params![bid_id.0, bid_item_id.0])?;
+            }
+        },
+        IndexedEvent::BidOutbid(BidOutbid { bid_item_id, outbid_by, .. }) => {
+            conn.execute("UPDATE bid_items SET winning_bid_id = ?1 WHERE bid_item_id = ?2", params![outbid_by.0, bid_item_id.0])?;
+        },
+        IndexedEvent::AuctionStateChanged(AuctionStateChanged { auction_id, next, .. }) => {
+            let updated = conn.execute("UPDATE auctions SET status = ?1 WHERE auction_id = ?2", params![next.as_str(), auction_id.0])?;
+
+            if updated == 0 {
+                bail!("State change for unknown auction {auction_id}");
+            }
+        },
+        IndexedEvent::ItemSettled(ItemSettled { auction_id, bid_item_id, status, winner, amount, denom }) => {
+            let winner = winner.as_ref().map(Bidder::to_string);
+
+            conn.execute(
+                "UPDATE bid_items SET status = ?1, winner = ?2 WHERE bid_item_id = ?3",
+                params![status.as_str(), winner, bid_item_id.0],
+            )?;
+            conn.execute(
+                "INSERT INTO settlements (bid_item_id, auction_id, status, winner, amount, denom) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![bid_item_id.0, auction_id.0, status.as_str(), winner, amount.map(|amount| amount.to_string()), denom],
+            )?;
+        },
+        IndexedEvent::RefundIssued(RefundIssued { bid_item_id, bid_id, bidder, amount, denom, destination }) => {
+            conn.execute(
+                "INSERT INTO refunds (bid_id, bid_item_id, bidder, amount, denom, destination) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![bid_id.0, bid_item_id.0, bidder.to_string(), amount.to_string(), denom, destination.as_str()],
+            )?;
+        },
+        IndexedEvent::PayoutEscrowed(PayoutEscrowed { bid_item_id, bid_id, bidder, amount, denom }) => {
+            conn.execute(
+                "INSERT INTO escrowed_payouts (bid_id, bid_item_id, bidder, amount, denom) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![bid_id.0, bid_item_id.0, bidder.as_str(), amount.to_string(), denom],
+            )?;
+        },
+        IndexedEvent::ReserveNotMet(ReserveNotMet { auction_id, bid_item_id, highest_bid, reserve_price }) => {
+            conn.execute(
+                "INSERT INTO unmet_reserves (bid_item_id, auction_id, highest_bid, reserve_price) VALUES (?1, ?2, ?3, ?4)",
+                params![bid_item_id.0, auction_id.0, highest_bid.to_string(), reserve_price.to_string()],
+            )?;
+        },
+        IndexedEvent::SettlementUndelivered(SettlementUndelivered { bid_id, channel_id, reason }) => {
+            // A settlement can fail several times before it's delivered, every failure is kept
+            conn.execute(
+                "INSERT INTO undelivered_settlements (bid_id, channel_id, reason) VALUES (?1, ?2, ?3)",
+                params![bid_id.0, channel_id, reason],
+            )?;
+        },
+        IndexedEvent::TransferRejected(TransferRejected { channel_id, sequence, sender, amount, reason }) => {
+            conn.execute(
+                "INSERT INTO rejected_transfers (channel_id, sequence, sender, amount, reason) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![channel_id, sequence, sender, amount.to_string(), reason],
+            )?;
+        },
+        IndexedEvent::HookFailed(HookFailed { contract, error }) => {
+            conn.execute("INSERT INTO hook_failures (contract, error) VALUES (?1, ?2)", params![contract.as_str(), error])?;
+        },
+    }
+
+    Ok(())
+}
+
+/// The lists and prices of a bid item, kept as JSON.
+struct BidItemDetails {
+    media_uris: String,
+    attributes: String,
+    starting_price: Option<String>,
+    reserve_price: Option<String>,
+}
+
+impl BidItemDetails {
+    fn new(item: &BidItemInput) -> Result<Self> {
+        Ok(BidItemDetails {
+            media_uris: serde_json::to_string(&item.media_uris)?,
+            attributes: serde_json::to_string(&item.attributes)?,
+            starting_price: item.starting_price.map(|price| serde_json::to_string(&price)).transpose()?,
+            reserve_price: item.reserve_price.map(|price| serde_json::to_string(&price)).transpose()?,
+        })
+    }
+}
+
+/// Bid and coin totals of an auction or bid item.
+fn totals(conn: &Connection, table: &str, key: &str, id: u32) -> Result<(u64, u128)> {
+    let (bids, coins): (u64, String) = conn
+        .query_row(&format!("SELECT total_bids, total_coins FROM {table} WHERE {key} = ?1"), params![id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .optional()?
+        .with_context(|| format!("Unknown {key} {id}"))?;
+
+    Ok((bids, coins.parse()?))
+}
+
+fn update_totals(conn: &Connection, table: &str, key: &str, id: u32, update: impl FnOnce(u64, u128) -> (u64, u128)) -> Result<()> {
+    let (bids, coins) = totals(conn, table, key, id)?;
+    let (bids, coins) = update(bids, coins);
+
+    conn.execute(
+        &format!("UPDATE {table} SET total_bids = ?1, total_coins = ?2 WHERE {key} = ?3"),
+        params![bids, coins.to_string(), id],
+    )?;
+
+    Ok(())
+}
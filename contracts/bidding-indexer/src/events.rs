@@ -0,0 +1,181 @@
+use anyhow::{Context, Result};
+use bidding::events::{AuctionCreated, AuctionStateChanged, BidItemAdded, BidItemCancelled, BidItemRemoved, BidItemUpdated, BidOutbid, BidPlaced, HookFailed, ItemSettled, PayoutEscrowed, RefundIssued, ReserveNotMet, SettlementUndelivered, TransferRejected};
+use bidding_client::FromEvent;
+use cosmwasm_std::Event;
+use serde_json::Value;
+
+/// A contract event as found in a transaction result, with the `wasm-` prefix stripped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawEvent {
+    pub ty: String,
+    pub attributes: Vec<(String, String)>,
+    /// Only known when the transaction result has its height and hash.
+    pub position: Option<EventPosition>,
+}
+
+/// Where an event was emitted, the index counts every event of the transaction so it doesn't
+/// depend on the contract filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventPosition {
+    pub height: u64,
+    pub tx_hash: String,
+    pub index: u32,
+}
+
+impl RawEvent {
+    fn get_opt(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The event as the contract emitted it, for the parsers of `bidding_client`. Attributes the
+    /// chain added, like `_contract_address`, are left out.
+    fn to_event(&self) -> Event {
+        let attributes = self.attributes.iter().filter(|(key, _)| !key.starts_with('_')).cloned();
+        Event::new(&self.ty).add_attributes(attributes)
+    }
+}
+
+/// Collects the wasm events of a transaction result, a list of them or anything else that has
+/// an `events` array, e.g. `{"tx_response": {"events": [...]}}`. When a contract address is
+/// given, events of other contracts are skipped.
+pub fn collect_events(value: &Value, contract: Option<&str>, out: &mut Vec<RawEvent>) {
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                collect_events(value, contract, out);
+            }
+        },
+        Value::Object(object) => {
+            if let Some(tx_response) = object.get("tx_response") {
+                collect_events(tx_response, contract, out);
+                return;
+            }
+
+            let Some(Value::Array(events)) = object.get("events") else {
+                return;
+            };
+
+            let tx = tx_id(object);
+
+            for (index, event) in events.iter().enumerate() {
+                let Some(ty) = event.get("type").and_then(Value::as_str) else {
+                    continue;
+                };
+
+                let Some(ty) = ty.strip_prefix("wasm-") else {
+                    continue;
+                };
+
+                let attributes: Vec<(String, String)> = event
+                    .get("attributes")
+                    .and_then(Value::as_array)
+                    .map(|attributes| {
+                        attributes
+                            .iter()
+                            .filter_map(|attr| {
+                                let key = attr.get("key")?.as_str()?;
+                                let value = attr.get("value")?.as_str()?;
+                                Some((key.to_string(), value.to_string()))
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                let position = tx.as_ref().map(|(height, tx_hash)| EventPosition {
+                    height: *height,
+                    tx_hash: tx_hash.clone(),
+                    index: index as u32,
+                });
+
+                let event = RawEvent { ty: ty.to_string(), attributes, position };
+
+                if contract.is_some_and(|contract| event.get_opt("_contract_address") != Some(contract)) {
+                    continue;
+                }
+
+                out.push(event);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// The height and hash of a transaction result, nodes return the height as a string.
+fn tx_id(object: &serde_json::Map<String, Value>) -> Option<(u64, String)> {
+    let height = match object.get("height")? {
+        Value::String(height) => height.parse().ok()?,
+        height => height.as_u64()?,
+    };
+
+    let tx_hash = object.get("txhash")?.as_str()?;
+
+    Some((height, tx_hash.to_string()))
+}
+
+/// Parses the events the indexer knows about, along with their position.
+pub fn index_events(raw: &[RawEvent]) -> Result<Vec<(Option<EventPosition>, IndexedEvent)>> {
+    let mut events = vec![];
+
+    for event in raw {
+        if let Some(indexed) = IndexedEvent::parse(event)? {
+            events.push((event.position.clone(), indexed));
+        }
+    }
+
+    Ok(events)
+}
+
+/// The contract events the indexer knows about, parsed by `bidding_client`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndexedEvent {
+    AuctionCreated(AuctionCreated),
+    BidItemAdded(BidItemAdded),
+    BidItemUpdated(BidItemUpdated),
+    BidItemCancelled(BidItemCancelled),
+    BidItemRemoved(BidItemRemoved),
+    BidPlaced(BidPlaced),
+    BidOutbid(BidOutbid),
+    AuctionStateChanged(AuctionStateChanged),
+    ItemSettled(ItemSettled),
+    RefundIssued(RefundIssued),
+    PayoutEscrowed(PayoutEscrowed),
+    ReserveNotMet(ReserveNotMet),
+    SettlementUndelivered(SettlementUndelivered),
+    TransferRejected(TransferRejected),
+    HookFailed(HookFailed),
+}
+
+impl IndexedEvent {
+    /// Parses a raw event, events of unknown types are ignored.
+    pub fn parse(raw: &RawEvent) -> Result<Option<Self>> {
+        let event = raw.to_event();
+
+        let indexed = match raw.ty.as_str() {
+            AuctionCreated::TYPE => IndexedEvent::AuctionCreated(from_event(&event)?),
+            BidItemAdded::TYPE => IndexedEvent::BidItemAdded(from_event(&event)?),
+            BidItemUpdated::TYPE => IndexedEvent::BidItemUpdated(from_event(&event)?),
+            BidItemCancelled::TYPE => IndexedEvent::BidItemCancelled(from_event(&event)?),
+            BidItemRemoved::TYPE => IndexedEvent::BidItemRemoved(from_event(&event)?),
+            BidPlaced::TYPE => IndexedEvent::BidPlaced(from_event(&event)?),
+            BidOutbid::TYPE => IndexedEvent::BidOutbid(from_event(&event)?),
+            AuctionStateChanged::TYPE => IndexedEvent::AuctionStateChanged(from_event(&event)?),
+            ItemSettled::TYPE => IndexedEvent::ItemSettled(from_event(&event)?),
+            RefundIssued::TYPE => IndexedEvent::RefundIssued(from_event(&event)?),
+            PayoutEscrowed::TYPE => IndexedEvent::PayoutEscrowed(from_event(&event)?),
+            ReserveNotMet::TYPE => IndexedEvent::ReserveNotMet(from_event(&event)?),
+            SettlementUndelivered::TYPE => IndexedEvent::SettlementUndelivered(from_event(&event)?),
+            TransferRejected::TYPE => IndexedEvent::TransferRejected(from_event(&event)?),
+            HookFailed::TYPE => IndexedEvent::HookFailed(from_event(&event)?),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(indexed))
+    }
+}
+
+fn from_event<T: FromEvent>(event: &Event) -> Result<T> {
+    T::from_event(event).with_context(|| format!("Invalid {} event", event.ty))
+}
@@ -0,0 +1,54 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+/// Reads transaction results from a JSON file, a JSON lines feed, a directory of those (in file
+/// name order) or `-` for stdin.
+pub fn read_input(path: &str) -> Result<Vec<Value>> {
+    if path == "-" {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        return parse_content(&content).context("Failed to parse stdin");
+    }
+
+    let path = Path::new(path);
+
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort();
+
+        let mut values = vec![];
+
+        for entry in entries {
+            if matches!(entry.extension().and_then(|ext| ext.to_str()), Some("json" | "jsonl")) {
+                values.extend(read_file(&entry)?);
+            }
+        }
+
+        return Ok(values);
+    }
+
+    read_file(path)
+}
+
+fn read_file(path: &Path) -> Result<Vec<Value>> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_content(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn parse_content(content: &str) -> Result<Vec<Value>> {
+    if let Ok(value) = serde_json::from_str::<Value>(content) {
+        return Ok(vec![value]);
+    }
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
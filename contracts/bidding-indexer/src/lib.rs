@@ -0,0 +1,7 @@
+pub mod db;
+pub mod events;
+pub mod input;
+pub mod verify;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,94 @@
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
+use bidding_indexer::db::Indexer;
+use bidding_indexer::events::{collect_events, index_events};
+use bidding_indexer::input::read_input;
+use bidding_indexer::verify::{verify, StateDump};
+use clap::{Parser, Subcommand};
+
+/// Replays the bidding contract's events into a SQLite database.
+#[derive(Parser)]
+struct Cli {
+    /// Path of the SQLite database.
+    #[arg(long, default_value = "bidding.db")]
+    db: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Deletes the database and replays every input into a new one.
+    Rebuild {
+        /// Only index events of this contract.
+        #[arg(long)]
+        contract: Option<String>,
+        /// Transaction result files, JSON lines feeds, directories of those or `-` for stdin.
+        #[arg(required = true)]
+        inputs: Vec<String>,
+    },
+    /// Applies new inputs on top of the existing database, events already indexed are skipped.
+    Ingest {
+        #[arg(long)]
+        contract: Option<String>,
+        #[arg(required = true)]
+        inputs: Vec<String>,
+    },
+    /// Compares the database against a JSON dump of the contract state.
+    Verify {
+        #[arg(long)]
+        state: String,
+    },
+}
+
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Rebuild { contract, inputs } => {
+            let mut indexer = Indexer::rebuild(&cli.db)?;
+            ingest(&mut indexer, contract.as_deref(), &inputs)?;
+        },
+        Command::Ingest { contract, inputs } => {
+            let mut indexer = Indexer::open(&cli.db)?;
+            ingest(&mut indexer, contract.as_deref(), &inputs)?;
+        },
+        Command::Verify { state } => {
+            let indexer = Indexer::open(&cli.db)?;
+            let dump: StateDump = serde_json::from_str(&std::fs::read_to_string(&state)?)
+                .with_context(|| format!("Failed to parse {state}"))?;
+
+            let mismatches = verify(indexer.connection(), &dump)?;
+
+            for mismatch in &mismatches {
+                eprintln!("{mismatch}");
+            }
+
+            if !mismatches.is_empty() {
+                eprintln!("{} mismatches found", mismatches.len());
+                return Ok(ExitCode::FAILURE);
+            }
+
+            println!("Database matches the contract state");
+        },
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn ingest(indexer: &mut Indexer, contract: Option<&str>, inputs: &[String]) -> Result<()> {
+    let mut raw = vec![];
+
+    for input in inputs {
+        for value in read_input(input)? {
+            collect_events(&value, contract, &mut raw);
+        }
+    }
+
+    let events = index_events(&raw)?;
+    let applied = indexer.apply(&events)?;
+    println!("Indexed {applied} events, {} were already indexed", events.len() - applied);
+
+    Ok(())
+}
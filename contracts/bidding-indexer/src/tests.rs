@@ -0,0 +1,354 @@
+const _INITIAL_BALANCE: u128 = 200_000;
+const _DENOM: &str = "eth";
+
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod tests {
+    use bidding::contract::{execute, instantiate, query};
+    use bidding::events::{AuctionCreated, BidItemAdded};
+    use bidding::msg::{BidItemInput, ExecuteMsg, InstantiateMsg, PagedResp, QueryMsg};
+    use bidding::state::{Auction, AuctionId, AuctionStatus, Bid, BidId, BidItem, BidItemAttribute, BidItemId, BidItemKey, BidKey, Price};
+    use cosmwasm_std::{coins, Addr, Coin};
+    use bidding_client::find_event;
+    use cw_multi_test::{App, AppResponse, ContractWrapper, Executor};
+    use rusqlite::Connection;
+    use serde_json::{json, Value};
+
+    use crate::db::Indexer;
+    use crate::events::{collect_events, index_events, EventPosition, IndexedEvent};
+    use crate::tests::{_DENOM, _INITIAL_BALANCE};
+    use crate::verify::{verify, StateDump};
+
+    #[test]
+    fn replay_and_verify() {
+        let mut app = App::default();
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let owner = app.api().addr_make("owner");
+        let users: Vec<Addr> = (0..2).map(|num| app.api().addr_make(&format!("user{num}"))).collect();
+
+        let addr = app
+            .instantiate_contract(
+                code_id,
+                owner.clone(),
+                &InstantiateMsg {
+                    admin: owner.clone(),
+                },
+                &[],
+                "Contract",
+                None,
+            )
+            .unwrap();
+
+        app.init_modules(|router, _, storage| {
+            for account in &users {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        account,
+                        vec![Coin::new(_INITIAL_BALANCE, _DENOM)],
+                    )
+                    .unwrap();
+            }
+        });
+
+        // Transaction results, the way a node would return them
+        let mut txs: Vec<AppResponse> = vec![];
+
+        let bid_items= vec![ BidItemInput::new("My first bid item"), BidItemInput::new("My second bid item"), BidItemInput::new("My third bid item") ];
+
+        let resp = app.execute_contract(
+            owner.clone(),
+            addr.clone(),
+            &ExecuteMsg::CreateAuction { name: "TestAuction #1".to_string(), description: Some("Paintings".to_string()), terms: None, ends_at: None, payout_mode: None, owner: None, bid_items },
+            &[],
+        )
+        .unwrap();
+
+        let wasm = resp.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+
+        let auction_id = &wasm.attributes
+                .iter()
+                .find(|attr| attr.key == "auction_id")
+                .unwrap()
+                .value;
+
+        let auction_id = AuctionId(auction_id.parse::<u32>().unwrap());
+
+        txs.push(resp);
+
+        let bid_items: Vec<(BidItemId, BidItem)> = app
+            .wrap()
+            .query_wasm_smart::<PagedResp<BidItemId, BidItem>>(&addr, &QueryMsg::BidItemsByAuctionId { auction_id, start_after: None, start_before: None, limit: None, order: None })
+            .unwrap()
+            .items;
+
+        txs.push(app.execute_contract(
+            owner.clone(),
+            addr.clone(),
+            &ExecuteMsg::RemoveBidItem { bid_item_id: bid_items[2].0 },
+            &[],
+        )
+        .unwrap());
+
+        let mut update = BidItemInput::new("My first bid item, framed");
+        update.category = Some("art".to_string());
+        update.media_uris = vec!["ipfs://first".to_string()];
+        update.attributes = vec![BidItemAttribute { key: "frame".to_string(), value: "oak".to_string() }];
+
+        txs.push(app.execute_contract(
+            owner.clone(),
+            addr.clone(),
+            &ExecuteMsg::UpdateBidItem { bid_item_id: bid_items[0].0, bid_item: update },
+            &[],
+        )
+        .unwrap());
+
+        let mut update = BidItemInput::new("My second bid item");
+        update.reserve_price = Some(Price::Native(100u128.into()));
+
+        txs.push(app.execute_contract(
+            owner.clone(),
+            addr.clone(),
+            &ExecuteMsg::UpdateBidItem { bid_item_id: bid_items[1].0, bid_item: update },
+            &[],
+        )
+        .unwrap());
+
+        for (num, user) in users.iter().enumerate() {
+            txs.push(app.execute_contract(
+                user.clone(),
+                addr.clone(),
+                &ExecuteMsg::PlaceBid { bid_item_id: bid_items[0].0, amount: None },
+                &coins(10 * (num as u128 + 1), _DENOM),
+            )
+            .unwrap());
+        }
+
+        // Below the reserve, the item isn't sold
+        txs.push(app.execute_contract(
+            users[0].clone(),
+            addr.clone(),
+            &ExecuteMsg::PlaceBid { bid_item_id: bid_items[1].0, amount: None },
+            &coins(30, _DENOM),
+        )
+        .unwrap());
+
+        txs.push(app.execute_contract(
+            owner.clone(),
+            addr.clone(),
+            &ExecuteMsg::SetAuctionState { id: auction_id, status: AuctionStatus::PendingCompletion },
+            &[],
+        )
+        .unwrap());
+
+        txs.push(app.execute_contract(
+            owner.clone(),
+            addr.clone(),
+            &ExecuteMsg::AdvanceCrank { max_items: None },
+            &[],
+        )
+        .unwrap());
+
+        let feed = json!(tx_results(&txs));
+        let events = events_of(&feed, &addr);
+
+        let mut indexer = Indexer::open_in_memory().unwrap();
+        assert_eq!(indexer.apply(&events).unwrap(), events.len());
+
+        // Feeds can be ingested again, events already applied are skipped
+        assert_eq!(indexer.apply(&events).unwrap(), 0);
+
+        // Other contracts' events are skipped
+        let mut other = vec![];
+        collect_events(&feed, Some("other"), &mut other);
+        assert!(other.is_empty());
+
+        let auctions: Vec<(AuctionId, Auction)> = app
+            .wrap()
+            .query_wasm_smart::<PagedResp<AuctionId, Auction>>(&addr, &QueryMsg::Auctions { status: None, start_after: None, start_before: None, limit: None, order: None })
+            .unwrap()
+            .items;
+
+        let bid_items: Vec<(BidItemKey, BidItem)> = app
+            .wrap()
+            .query_wasm_smart::<PagedResp<BidItemKey, BidItem>>(&addr, &QueryMsg::BidItems { status: None, start_after: None, start_before: None, limit: None, order: None })
+            .unwrap()
+            .items;
+
+        let mut bids: Vec<(BidKey, Bid)> = vec![];
+
+        for (key, _) in &bid_items {
+            let item_bids: Vec<(BidId, Bid)> = app
+                .wrap()
+                .query_wasm_smart::<PagedResp<BidId, Bid>>(&addr, &QueryMsg::BidsByBidItem { bid_item_id: key.bid_item_id, start_after: None, start_before: None, limit: None, order: None })
+                .unwrap()
+                .items;
+
+            bids.extend(item_bids.into_iter().map(|(bid_id, bid)| (BidKey { bid_item_id: key.bid_item_id, bid_id }, bid)));
+        }
+
+        let winning_bids: Vec<(BidItemId, BidId)> = bid_items
+            .iter()
+            .filter_map(|(key, _)| {
+                app.wrap()
+                    .query_wasm_smart::<Option<(BidId, Bid)>>(&addr, &QueryMsg::WinningBid { bid_item_id: key.bid_item_id })
+                    .unwrap()
+                    .map(|(bid_id, _)| (key.bid_item_id, bid_id))
+            })
+            .collect();
+
+        let mut dump = StateDump { auctions, bid_items, bids, winning_bids };
+
+        assert_eq!(verify(indexer.connection(), &dump).unwrap(), Vec::<String>::new());
+
+        let (status, winner, amount): (String, String, String) = indexer
+            .connection()
+            .query_row("SELECT status, winner, amount FROM settlements WHERE bid_item_id = ?1", [dump.bid_items[0].0.bid_item_id.0], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap();
+
+        assert_eq!((status.as_str(), winner, amount.as_str()), ("completed", users[1].to_string(), "20"));
+
+        let (bidder, destination): (String, String) = indexer
+            .connection()
+            .query_row("SELECT bidder, destination FROM refunds", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+
+        assert_eq!((bidder, destination.as_str()), (users[0].to_string(), "wallet"));
+
+        let (highest_bid, reserve_price): (String, String) = indexer
+            .connection()
+            .query_row("SELECT highest_bid, reserve_price FROM unmet_reserves WHERE bid_item_id = ?1", [dump.bid_items[1].0.bid_item_id.0], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+
+        assert_eq!((highest_bid.as_str(), reserve_price.as_str()), ("30", "100"));
+
+        // Differences with the contract state are reported
+        dump.bids[0].1.amount = 11u128.into();
+        dump.auctions.clear();
+        dump.winning_bids.remove(0);
+
+        let mismatches = verify(indexer.connection(), &dump).unwrap();
+        assert_eq!(mismatches.len(), 3);
+        assert!(mismatches[0].starts_with(&format!("auction {auction_id} isn't in the contract state")));
+        assert!(mismatches[1].starts_with(&format!("bid item {}:", dump.bid_items[0].0.bid_item_id)));
+        assert!(mismatches[2].starts_with(&format!("bid {}:", dump.bids[0].0.bid_id)));
+    }
+
+    /// Transaction results the way a node would return them, one block per transaction.
+    fn tx_results(txs: &[AppResponse]) -> Vec<Value> {
+        txs.iter()
+            .enumerate()
+            .map(|(num, tx)| json!({ "tx_response": { "height": (num + 1).to_string(), "txhash": format!("TX{num}"), "events": tx.events } }))
+            .collect()
+    }
+
+    fn events_of(feed: &Value, contract: &Addr) -> Vec<(Option<EventPosition>, IndexedEvent)> {
+        let mut raw = vec![];
+        collect_events(feed, Some(contract.as_str()), &mut raw);
+
+        index_events(&raw).unwrap()
+    }
+
+    /// Every row of the tables that mirror the contract state.
+    fn snapshot(conn: &Connection) -> Vec<String> {
+        let mut rows = vec![];
+
+        for table in ["auctions", "bid_items", "bids", "settlements", "refunds"] {
+            let mut stmt = conn.prepare(&format!("SELECT * FROM {table}")).unwrap();
+            let columns = stmt.column_count();
+
+            let table_rows = stmt
+                .query_map([], |row| {
+                    let values = (0..columns)
+                        .map(|column| row.get::<_, rusqlite::types::Value>(column).map(|value| format!("{value:?}")))
+                        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+                    Ok(format!("{table}: {}", values.join(", ")))
+                })
+                .unwrap();
+
+            rows.extend(table_rows.map(Result::unwrap));
+        }
+
+        rows
+    }
+
+    #[test]
+    fn reingest_and_out_of_order_input() {
+        let mut app = App::default();
+
+        let code = ContractWrapper::new(execute, instantiate, query);
+        let code_id = app.store_code(Box::new(code));
+
+        let owner = app.api().addr_make("owner");
+        let users: Vec<Addr> = (0..2).map(|num| app.api().addr_make(&format!("user{num}"))).collect();
+
+        let addr = app
+            .instantiate_contract(code_id, owner.clone(), &InstantiateMsg { admin: owner.clone() }, &[], "Contract", None)
+            .unwrap();
+
+        app.init_modules(|router, _, storage| {
+            for account in &users {
+                router.bank.init_balance(storage, account, coins(_INITIAL_BALANCE, _DENOM)).unwrap();
+            }
+        });
+
+        let mut txs: Vec<AppResponse> = vec![];
+
+        let msg = ExecuteMsg::CreateAuction { name: "TestAuction #1".to_string(), description: None, terms: None, ends_at: None, payout_mode: None, owner: None, bid_items: vec![ BidItemInput::new("My first bid item") ] };
+        let resp = app.execute_contract(owner.clone(), addr.clone(), &msg, &[]).unwrap();
+        let auction_id = find_event::<AuctionCreated>(&resp.events).unwrap().unwrap().auction_id;
+        let bid_item_id = find_event::<BidItemAdded>(&resp.events).unwrap().unwrap().bid_item_id;
+        txs.push(resp);
+
+        for (num, user) in users.iter().enumerate() {
+            txs.push(app.execute_contract(user.clone(), addr.clone(), &ExecuteMsg::PlaceBid { bid_item_id, amount: None }, &coins(10 * (num as u128 + 1), _DENOM)).unwrap());
+        }
+
+        txs.push(app.execute_contract(owner.clone(), addr.clone(), &ExecuteMsg::SetAuctionState { id: auction_id, status: AuctionStatus::PendingCompletion }, &[]).unwrap());
+        txs.push(app.execute_contract(owner.clone(), addr.clone(), &ExecuteMsg::AdvanceCrank { max_items: None }, &[]).unwrap());
+
+        let results = tx_results(&txs);
+        let events = events_of(&json!(results), &addr);
+
+        let mut expected = Indexer::open_in_memory().unwrap();
+        expected.apply(&events).unwrap();
+        let expected = snapshot(expected.connection());
+
+        // The same feed ingested twice, the second time nothing is applied
+        let mut indexer = Indexer::open_in_memory().unwrap();
+        assert_eq!(indexer.apply(&events).unwrap(), events.len());
+        assert_eq!(indexer.apply(&events).unwrap(), 0);
+        assert_eq!(snapshot(indexer.connection()), expected);
+
+        // A feed that overlaps what was ingested only applies the new events
+        let first_half = events_of(&json!(results[..2]), &addr);
+
+        let mut indexer = Indexer::open_in_memory().unwrap();
+        indexer.apply(&first_half).unwrap();
+        assert_eq!(indexer.apply(&events).unwrap(), events.len() - first_half.len());
+        assert_eq!(snapshot(indexer.connection()), expected);
+
+        // Transactions are applied by height, whatever the order of the feed
+        let reversed: Vec<Value> = results.iter().rev().cloned().collect();
+
+        let mut indexer = Indexer::open_in_memory().unwrap();
+        indexer.apply(&events_of(&json!(reversed), &addr)).unwrap();
+        assert_eq!(snapshot(indexer.connection()), expected);
+
+        // Events older than what's indexed can't be applied anymore
+        let mut indexer = Indexer::open_in_memory().unwrap();
+        indexer.apply(&events_of(&json!([results[0].clone(), results[2].clone()]), &addr)).unwrap();
+
+        let err = indexer.apply(&events_of(&json!(results[1]), &addr)).unwrap_err();
+        assert!(err.to_string().starts_with("Event at height 2 is older than the indexed height 3"));
+
+        // Without a position an event can't be deduplicated, so it isn't applied
+        let unpositioned: Vec<(Option<EventPosition>, IndexedEvent)> = events.into_iter().map(|(_, event)| (None, event)).collect();
+        Indexer::open_in_memory().unwrap().apply(&unpositioned).unwrap_err();
+    }
+}
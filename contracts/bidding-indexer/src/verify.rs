@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use bidding::state::{Auction, AuctionId, Bid, BidId, BidItem, BidItemAttribute, BidItemId, BidItemKey, BidKey, Price};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// Contract state dumped through the `Auctions`, `BidItems`, `BidsByBidItem` and `WinningBid`
+/// queries.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct StateDump {
+    pub auctions: Vec<(AuctionId, Auction)>,
+    pub bid_items: Vec<(BidItemKey, BidItem)>,
+    pub bids: Vec<(BidKey, Bid)>,
+    #[serde(default)]
+    pub winning_bids: Vec<(BidItemId, BidId)>,
+}
+
+#[derive(Debug, PartialEq)]
+struct IndexedAuction {
+    name: String,
+    description: Option<String>,
+    terms: Option<String>,
+    owner: String,
+    status: String,
+    ends_at: Option<u64>,
+    payout_mode: String,
+    available_bid_items: u64,
+    total_bids: u64,
+    total_coins: String,
+}
+
+impl IndexedAuction {
+    fn from_state(auction: &Auction) -> Self {
+        IndexedAuction {
+            name: auction.name.clone(),
+            description: auction.description.clone(),
+            terms: auction.terms.clone(),
+            owner: auction.owner.to_string(),
+            status: auction.current_state.as_str().to_string(),
+            ends_at: auction.ends_at.map(|ends_at| ends_at.seconds()),
+            payout_mode: auction.payout_mode.as_str().to_string(),
+            available_bid_items: auction.available_bid_items.u64(),
+            total_bids: auction.total_bids.u64(),
+            total_coins: auction.total_coins.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct IndexedBidItem {
+    auction_id: u32,
+    name: String,
+    description: Option<String>,
+    category: Option<String>,
+    media_uris: Vec<String>,
+    attributes: Vec<BidItemAttribute>,
+    starting_price: Option<Price>,
+    reserve_price: Option<Price>,
+    status: String,
+    winning_bid_id: Option<u32>,
+    winner: Option<String>,
+    total_bids: u64,
+    total_coins: String,
+}
+
+impl IndexedBidItem {
+    fn from_state(key: &BidItemKey, bid_item: &BidItem, winning_bid_id: Option<BidId>) -> Self {
+        IndexedBidItem {
+            auction_id: key.auction_id.0,
+            name: bid_item.name.clone(),
+            description: bid_item.description.clone(),
+            category: bid_item.category.clone(),
+            media_uris: bid_item.media_uris.clone(),
+            attributes: bid_item.attributes.clone(),
+            starting_price: bid_item.starting_price,
+            reserve_price: bid_item.reserve_price,
+            status: bid_item.current_state.as_str().to_string(),
+            winning_bid_id: winning_bid_id.map(|bid_id| bid_id.0),
+            winner: bid_item.winner.as_ref().map(|winner| winner.to_string()),
+            total_bids: bid_item.total_bids.u64(),
+            total_coins: bid_item.total_coins.to_string(),
+        }
+    }
+}
+
+/// Compares the database against dumped contract state, returns every difference found.
+pub fn verify(conn: &Connection, dump: &StateDump) -> Result<Vec<String>> {
+    let mut mismatches = vec![];
+
+    let mut auctions: BTreeMap<u32, IndexedAuction> = BTreeMap::new();
+    let mut stmt = conn.prepare("SELECT auction_id, name, description, terms, owner, status, ends_at, payout_mode, available_bid_items, total_bids, total_coins FROM auctions")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get(0)?, IndexedAuction {
+            name: row.get(1)?,
+            description: row.get(2)?,
+            terms: row.get(3)?,
+            owner: row.get(4)?,
+            status: row.get(5)?,
+            ends_at: row.get(6)?,
+            payout_mode: row.get(7)?,
+            available_bid_items: row.get(8)?,
+            total_bids: row.get(9)?,
+            total_coins: row.get(10)?,
+        }))
+    })?;
+
+    for row in rows {
+        let (id, auction) = row?;
+        auctions.insert(id, auction);
+    }
+
+    for (auction_id, auction) in &dump.auctions {
+        let expected = IndexedAuction::from_state(auction);
+
+        match auctions.remove(&auction_id.0) {
+            Some(indexed) if indexed == expected => {},
+            Some(indexed) => mismatches.push(format!("auction {auction_id}: indexed {indexed:?}, contract has {expected:?}")),
+            None => mismatches.push(format!("auction {auction_id} is missing")),
+        }
+    }
+
+    for auction_id in auctions.keys() {
+        mismatches.push(format!("auction {auction_id} isn't in the contract state"));
+    }
+
+    // Lists and prices are stored as the JSON of the events, they're compared once parsed
+    let mut bid_items: BTreeMap<u32, IndexedBidItem> = BTreeMap::new();
+    let mut stmt = conn.prepare("SELECT bid_item_id, auction_id, name, description, category, media_uris, attributes, starting_price, reserve_price, status, winning_bid_id, winner, total_bids, total_coins FROM bid_items")?;
+    let rows = stmt.query_map([], |row| {
+        let media_uris: String = row.get(5)?;
+        let attributes: String = row.get(6)?;
+        let starting_price: Option<String> = row.get(7)?;
+        let reserve_price: Option<String> = row.get(8)?;
+
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, (media_uris, attributes, starting_price, reserve_price), row.get(9)?, row.get(10)?, row.get(11)?, row.get(12)?, row.get(13)?))
+    })?;
+
+    for row in rows {
+        let (id, auction_id, name, description, category, (media_uris, attributes, starting_price, reserve_price), status, winning_bid_id, winner, total_bids, total_coins) = row?;
+
+        bid_items.insert(id, IndexedBidItem {
+            auction_id,
+            name,
+            description,
+            category,
+            media_uris: serde_json::from_str(&media_uris)?,
+            attributes: serde_json::from_str(&attributes)?,
+            starting_price: starting_price.map(|price| serde_json::from_str(&price)).transpose()?,
+            reserve_price: reserve_price.map(|price| serde_json::from_str(&price)).transpose()?,
+            status,
+            winning_bid_id,
+            winner,
+            total_bids,
+            total_coins,
+        });
+    }
+
+    let winning_bids: BTreeMap<BidItemId, BidId> = dump.winning_bids.iter().copied().collect();
+
+    for (key, bid_item) in &dump.bid_items {
+        let expected = IndexedBidItem::from_state(key, bid_item, winning_bids.get(&key.bid_item_id).copied());
+
+        match bid_items.remove(&key.bid_item_id.0) {
+            Some(indexed) if indexed == expected => {},
+            Some(indexed) => mismatches.push(format!("bid item {}: indexed {indexed:?}, contract has {expected:?}", key.bid_item_id)),
+            None => mismatches.push(format!("bid item {} is missing", key.bid_item_id)),
+        }
+    }
+
+    for bid_item_id in bid_items.keys() {
+        mismatches.push(format!("bid item {bid_item_id} isn't in the contract state"));
+    }
+
+    let mut bids: BTreeMap<u32, (u32, String, String, bool)> = BTreeMap::new();
+    let mut stmt = conn.prepare("SELECT bid_id, bid_item_id, bidder, amount, from_deposit FROM bids")?;
+    for row in stmt.query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))))? {
+        let (id, bid) = row?;
+        bids.insert(id, bid);
+    }
+
+    for (key, bid) in &dump.bids {
+        let expected = (key.bid_item_id.0, bid.bidder.to_string(), bid.amount.to_string(), bid.from_deposit);
+
+        match bids.remove(&key.bid_id.0) {
+            Some(indexed) if indexed == expected => {},
+            Some(indexed) => mismatches.push(format!("bid {}: indexed {indexed:?}, contract has {expected:?}", key.bid_id)),
+            None => mismatches.push(format!("bid {} is missing", key.bid_id)),
+        }
+    }
+
+    for bid_id in bids.keys() {
+        mismatches.push(format!("bid {bid_id} isn't in the contract state"));
+    }
+
+    Ok(mismatches)
+}
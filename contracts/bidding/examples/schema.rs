@@ -14,5 +14,12 @@ fn main() {
     export_schema_with_title(&schema_for!(InstantiateMsg), &out_dir, "InstantiateMsg");
     export_schema_with_title(&schema_for!(ExecuteMsg), &out_dir, "ExecuteMsg");
     export_schema_with_title(&schema_for!(QueryMsg), &out_dir, "QueryMsg");
+    export_schema_with_title(&schema_for!(MigrateMsg), &out_dir, "MigrateMsg");
+    export_schema_with_title(&schema_for!(SudoMsg), &out_dir, "SudoMsg");
+    export_schema_with_title(&schema_for!(VerifierQueryMsg), &out_dir, "VerifierQueryMsg");
+    export_schema_with_title(&schema_for!(HookExecuteMsg), &out_dir, "HookExecuteMsg");
+    export_schema_with_title(&schema_for!(BidMemo), &out_dir, "BidMemo");
+    export_schema_with_title(&schema_for!(RefundMemo), &out_dir, "RefundMemo");
+    export_schema_with_title(&schema_for!(OracleQueryMsg), &out_dir, "OracleQueryMsg");
     export_schema(&schema_for!(AdminsListResp), &out_dir);
 }
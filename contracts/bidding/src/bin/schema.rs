@@ -1,10 +1,12 @@
-use bidding::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use bidding::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 use cosmwasm_schema::write_api;
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
-        query: QueryMsg
+        query: QueryMsg,
+        migrate: MigrateMsg,
+        sudo: SudoMsg
     }
 }
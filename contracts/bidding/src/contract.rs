@@ -1,16 +1,42 @@
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{ADMIN, AUCTIONS, AUCTIONS_CRANK_QUEUE};
+use crate::events::{AuctionCreated, AuctionStateChanged, BidItemAdded, BidItemCancelled, BidItemRemoved, BidItemUpdated, BidOutbid, BidPlaced, HookFailed, ItemSettled, PayoutEscrowed, RefundDestination, RefundIssued, ReserveNotMet, SettlementDeferred};
+use crate::ibc;
+use crate::msg::{BidItemInput, ConfigUpdate, ExecuteMsg, HookExecuteMsg, HookMsg, Ics20Packet, InstantiateMsg, MigrateMsg, OraclePriceResp, OracleQueryMsg, QueryMsg, SudoMsg, VerifierQueryMsg};
+use crate::state::{AuctionId, BidId, Bidder, BidderAccess, Config, PauseScope, Price, ReindexStep, RemoteSettlement, ADMIN, ALLOWLISTS, CONTRACT_VERSION, AUCTIONS, AUCTIONS_CRANK_QUEUE, BIDDER_STATS, CONFIG, DENIED_ESCROW, DENYLIST, HOOKS, IBC_CHANNELS, PAUSED, REINDEX_CURSOR, REMOVED_BID_ITEMS, TRANSFER_CHANNELS, UNDELIVERED_SETTLEMENTS, UNMET_RESERVES};
 use cosmwasm_std::{
-    coins, to_json_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
-    StdResult, Uint128,
+    coins, from_json, to_json_binary, Addr, BankMsg, Binary, Coin, Decimal, Event, Deps, DepsMut, Env, MessageInfo, Order, Reply, Response,
+    StdResult, Storage, SubMsg, SubMsgResult, Timestamp, Uint128, WasmMsg,
 };
 
 pub type Result<T> = std::result::Result<T, ContractError>;
 
-const CRANK_MAX_BID_ITEMS: u32 = 3;
-const DENOM: &'static str = "eth";
-const PAGINATION_LIMIT: u32 = 10;
+const DEFAULT_CRANK_BATCH_SIZE: u32 = 3;
+const MAX_CRANK_BATCH_SIZE: u32 = 30;
+const DEFAULT_CRANK_MAX_BIDS: u32 = 500;
+pub(crate) const DENOM: &str = "eth";
+const MAX_BPS: u16 = 10_000;
+const DEFAULT_PAGE_LIMIT: u32 = 10;
+const MAX_PAGE_LIMIT: u32 = 100;
+const MAX_HOOKS: usize = 10;
+const HOOK_REPLY_ID: u64 = 1;
+pub(crate) const REFUND_REPLY_ID: u64 = 2;
+const DEFAULT_HOOK_GAS_LIMIT: u64 = 300_000;
+
+const MAX_NAME_LENGTH: usize = 128;
+const MAX_DESCRIPTION_LENGTH: usize = 2_048;
+const MAX_TERMS_LENGTH: usize = 4_096;
+const MAX_CATEGORY_LENGTH: usize = 64;
+const MAX_URI_LENGTH: usize = 512;
+const MAX_MEDIA_URIS: usize = 10;
+const MAX_ATTRIBUTES: usize = 32;
+const MAX_ATTRIBUTE_LENGTH: usize = 256;
+
+/// Where the funds of a bid come from.
+pub(crate) enum BidSource {
+    Funds,
+    Deposit,
+    Remote,
+}
 
 pub fn instantiate(
     deps: DepsMut,
@@ -19,14 +45,59 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
     ADMIN.save(deps.storage, &msg.admin)?;
+    CONFIG.save(deps.storage, &default_config())?;
+    CONTRACT_VERSION.save(deps.storage, &env!("CARGO_PKG_VERSION").to_string())?;
+
     Ok(Response::new())
 }
 
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary> {
+/// Fills in what contracts instantiated by older code are missing. Their records read fine with the
+/// serde defaults of the newer fields, the first version's records are also saved again in batches
+/// through `ExecuteMsg::Reindex`, since its plain maps didn't write the index entries.
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response> {
+    if !CONFIG.exists(deps.storage) {
+        CONFIG.save(deps.storage, &default_config())?;
+    }
+
+    // The first version didn't store its version
+    let previous = CONTRACT_VERSION.may_load(deps.storage)?;
+
+    if previous.is_none() {
+        REINDEX_CURSOR.save(deps.storage, &ReindexStep::Auctions { start_after: None })?;
+    }
+
+    CONTRACT_VERSION.save(deps.storage, &env!("CARGO_PKG_VERSION").to_string())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("previous_version", previous.unwrap_or_default())
+        .add_attribute("version", env!("CARGO_PKG_VERSION")))
+}
+
+fn default_config() -> Config {
+    Config {
+        default_page_limit: DEFAULT_PAGE_LIMIT,
+        max_page_limit: MAX_PAGE_LIMIT,
+        crank_reward: Uint128::zero(),
+        crank_reward_fee_bps: 0,
+        crank_batch_size: DEFAULT_CRANK_BATCH_SIZE,
+        max_crank_batch_size: MAX_CRANK_BATCH_SIZE,
+        crank_max_bids: DEFAULT_CRANK_MAX_BIDS,
+        guardian: None,
+        max_active_bids_per_bidder: None,
+        max_escrow_per_bidder: None,
+        oracle: None,
+        hook_gas_limit: DEFAULT_HOOK_GAS_LIMIT,
+    }
+}
+
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary> {
     use QueryMsg::*;
+    use query::Page;
 
     match msg {
         Admin {} => Ok(to_json_binary(&query::admin(deps)?)?),
+        Config {} => Ok(to_json_binary(&query::config(deps)?)?),
         Auction {
             id
         } => {
@@ -35,8 +106,13 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary> {
         },
         BidItemsByAuctionId {
             auction_id,
+            start_after,
+            start_before,
+            limit,
+            order,
         } => {
-            let response = query::get_bid_items_by_auction_id(deps, auction_id)?;
+            let page = Page::new(deps, start_after, start_before, limit, order)?;
+            let response = query::get_bid_items_by_auction_id(deps, auction_id, page)?;
             Ok(to_json_binary(&response)?)
         },
         BidItem {
@@ -46,23 +122,133 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary> {
             Ok(to_json_binary(&response)?)
         },
         BidItems {
+            status,
             start_after,
+            start_before,
+            limit,
+            order,
         } => {
-            let response = query::get_bid_items(deps, start_after, PAGINATION_LIMIT)?;
+            let page = Page::new(deps, start_after, start_before, limit, order)?;
+            let response = query::get_bid_items(deps, status, page)?;
             Ok(to_json_binary(&response)?)
         },
         Auctions {
+            status,
             start_after,
+            start_before,
+            limit,
+            order,
         } => {
-            let response = query::get_auctions(deps, start_after, PAGINATION_LIMIT)?;
+            let page = Page::new(deps, start_after, start_before, limit, order)?;
+            let response = query::get_auctions(deps, status, page)?;
             Ok(to_json_binary(&response)?)
         },
         BidItemsById { 
-            bid_items_ids
+            bid_items_ids,
+            start_after,
+            limit,
+        } => {
+            let page = Page::new(deps, start_after, None, limit, None)?;
+            let response = query::get_bid_items_by_id(deps, bid_items_ids, page)?;
+            Ok(to_json_binary(&response)?)
+        },
+        BidsByBidder {
+            bidder,
+            start_after,
+            start_before,
+            limit,
+            order,
+        } => {
+            let page = Page::new(deps, start_after, start_before, limit, order)?;
+            let response = query::get_bids_by_bidder(deps, bidder, page)?;
+            Ok(to_json_binary(&response)?)
+        },
+        BidsByBidItem {
+            bid_item_id,
+            start_after,
+            start_before,
+            limit,
+            order,
+        } => {
+            let page = Page::new(deps, start_after, start_before, limit, order)?;
+            let response = query::get_bids_by_bid_item(deps, bid_item_id, page)?;
+            Ok(to_json_binary(&response)?)
+        },
+        WinningBid {
+            bid_item_id,
+        } => {
+            let response = query::get_winning_bid(deps, bid_item_id)?;
+            Ok(to_json_binary(&response)?)
+        },
+        BidItemPrices {
+            bid_item_id,
+        } => Ok(to_json_binary(&query::bid_item_prices(deps, env, bid_item_id)?)?),
+        CrankStatus { start_after, limit } => Ok(to_json_binary(&query::crank_status(deps, start_after, limit)?)?),
+        Paused {} => Ok(to_json_binary(&PAUSED.may_load(deps.storage)?.unwrap_or_default())?),
+        Claimable {
+            address,
+        } => Ok(to_json_binary(&query::claimable(deps, address)?)?),
+        DepositBalance {
+            address,
+        } => Ok(to_json_binary(&query::deposit_balance(deps, address)?)?),
+        IsBidderAllowed {
+            auction_id,
+            bidder,
+        } => {
+            let auction = query::get_auction(deps, auction_id)?;
+            Ok(to_json_binary(&is_bidder_allowed(deps, auction_id, &auction.bidder_access, &bidder)?)?)
+        },
+        BidderStats {
+            address,
+        } => Ok(to_json_binary(&BIDDER_STATS.may_load(deps.storage, address.as_str())?.unwrap_or_default())?),
+        Denylist {
+            start_after,
+            start_before,
+            limit,
+            order,
+        } => {
+            let page = Page::new(deps, start_after, start_before, limit, order)?;
+            let response = query::get_denylist(deps, page)?;
+            Ok(to_json_binary(&response)?)
+        },
+        Hooks {
+            start_after,
+            start_before,
+            limit,
+            order,
+        } => {
+            let page = Page::new(deps, start_after, start_before, limit, order)?;
+            let response = query::get_hooks(deps, page)?;
+            Ok(to_json_binary(&response)?)
+        },
+        DeniedEscrow {
+            address,
+        } => Ok(to_json_binary(&DENIED_ESCROW.may_load(deps.storage, &address)?.unwrap_or_default())?),
+        TransferChannels {
+            start_after,
+            start_before,
+            limit,
+            order,
+        } => {
+            let page = Page::new(deps, start_after, start_before, limit, order)?;
+            let response = query::get_transfer_channels(deps, page)?;
+            Ok(to_json_binary(&response)?)
+        },
+        UndeliveredSettlements {
+            start_after,
+            start_before,
+            limit,
+            order,
         } => {
-            let response = query::get_bid_items_by_id(deps, bid_items_ids)?;
+            let page = Page::new(deps, start_after, start_before, limit, order)?;
+            let response = query::get_undelivered_settlements(deps, page)?;
             Ok(to_json_binary(&response)?)
         },
+        VerifyState {
+            auction_id,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query::verify_state(deps, env, auction_id, start_after, limit)?)?),
     }
 }
 
@@ -74,66 +260,256 @@ pub fn execute(
 ) -> Result<Response> {
     use ExecuteMsg::*;
 
+    let scope = match msg {
+        CreateAuction { .. } | AddBidItems { .. } | UpdateBidItem { .. } | RemoveBidItem { .. } | SetBidderAccess { .. } | UpdateAllowlist { .. } => Some(PauseScope::Listing),
+        PlaceBid { .. } | Deposit {} | Transfer { .. } => Some(PauseScope::Bidding),
+        AdvanceCrank { .. } | RetrySettlement { .. } => Some(PauseScope::Settlement),
+        _ => None,
+    };
+
+    if let Some(scope) = scope {
+        assert_not_paused(deps.as_ref(), scope)?;
+    }
+
     match msg {
         CreateAuction {
             name,
+            description,
+            terms,
+            ends_at,
+            payout_mode,
+            owner,
             bid_items,
-        } => exec::create_auction(deps, info, name, bid_items).map_err(Into::into),
+        } => exec::create_auction(deps, info, env, name, description, terms, ends_at, payout_mode.unwrap_or_default(), owner, bid_items),
         SetAuctionState{
             id,
             status,
-        }  => exec::set_auction_state(deps, info, id, status).map_err(Into::into),
+        }  => exec::set_auction_state(deps, info, id, status),
         AddBidItems {
             auction_id,
             bid_items,
-        } => exec::add_bid_items(deps, info, auction_id, bid_items).map_err(Into::into),
+        } => exec::add_bid_items(deps, info, auction_id, bid_items),
         PlaceBid {
             bid_item_id,
+            amount,
+        } => {
+            // Bids without attached funds are drawn from the bidder's deposit
+            match (get_sent_amount(&info)?, amount) {
+                (Some(coins_to_bid), None) => exec::place_bid(deps, env, Bidder::Local(info.sender), bid_item_id, coins_to_bid, BidSource::Funds),
+                (None, Some(amount)) if !amount.is_zero() => exec::place_bid(deps, env, Bidder::Local(info.sender), bid_item_id, amount, BidSource::Deposit),
+                (Some(_), Some(_)) => Err(ContractError::UnexpectedAssetsReceived { msg: "Either attach funds or bid from the deposit, not both".to_string() }),
+                _ => Err(ContractError::NoFundsReceived { denom: DENOM.to_string() }),
+            }
+        },
+        AdvanceCrank {
+            max_items,
+        } => exec::advance_crank(deps, info, env, max_items),
+        CancelAuction {
+            id,
+        } => exec::cancel_auction(deps, info, id),
+        UpdateBidItem {
+            bid_item_id,
+            bid_item,
+        } => exec::update_bid_item(deps, info, bid_item_id, bid_item),
+        RemoveBidItem {
+            bid_item_id,
+        } => exec::remove_bid_item(deps, info, bid_item_id),
+        UpdateConfig(update) => exec::update_config(deps, info, update),
+        Pause {
+            scope,
+        } => exec::set_paused(deps, info, scope, true),
+        Unpause {
+            scope,
+        } => exec::set_paused(deps, info, scope, false),
+        FundCrankRewards {} => {
+            let amount = assert_sent_amount(&info)?;
+            exec::fund_crank_rewards(deps, info, amount)
+        },
+        Deposit {} => {
+            let amount = assert_sent_amount(&info)?;
+            assert_not_denied(deps.as_ref(), &info.sender)?;
+            exec::deposit(deps, info, amount)
+        },
+        Withdraw {
+            amount,
+        } => exec::withdraw(deps, info, amount),
+        Claim {} => exec::claim(deps, info, None),
+        ClaimFor {
+            denoms,
+        } => exec::claim(deps, info, Some(denoms)),
+        SetBidderAccess {
+            auction_id,
+            access,
+        } => exec::set_bidder_access(deps, info, auction_id, access),
+        UpdateAllowlist {
+            auction_id,
+            add,
+            remove,
+        } => exec::update_allowlist(deps, info, auction_id, add, remove),
+        DenyAddresses {
+            addresses,
+        } => exec::set_denied(deps, info, env, addresses, true),
+        AllowAddresses {
+            addresses,
+        } => exec::set_denied(deps, info, env, addresses, false),
+        ResolveEscrow {
+            address,
+            recipient,
+        } => exec::resolve_escrow(deps, info, address, recipient),
+        AddHook {
+            contract,
+        } => exec::add_hook(deps, info, env, contract),
+        RemoveHook {
+            contract,
+        } => exec::remove_hook(deps, info, contract),
+        Transfer {
+            channel_id,
+            remote_address,
         } => {
-            let coins_to_bid = assert_sent_amount(&info)?;
-            exec::place_bid(deps, info, env, bid_item_id, coins_to_bid).map_err(Into::into)
+            let amount = assert_sent_amount(&info)?;
+            assert_not_denied(deps.as_ref(), &info.sender)?;
+            exec::transfer(deps, info, env, channel_id, remote_address, amount)
+        },
+        AddTransferChannel {
+            channel_id,
+        } => exec::add_transfer_channel(deps, info, env, channel_id),
+        RemoveTransferChannel {
+            channel_id,
+        } => exec::remove_transfer_channel(deps, info, channel_id),
+        RetrySettlement {
+            bid_id,
+            channel_id,
+        } => exec::retry_settlement(deps, info, env, bid_id, channel_id),
+        Reindex {
+            limit,
+        } => exec::reindex(deps, info, limit),
+    }
+}
+
+/// Only failed hooks and refund packets reply, their changes are already reverted and the call
+/// that sent them goes on.
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response> {
+    match msg.id {
+        HOOK_REPLY_ID => {
+            let SubMsgResult::Err(error) = msg.result else {
+                return Ok(Response::new());
+            };
+
+            let contract: Addr = from_json(&msg.payload)?;
+
+            Ok(Response::new().add_event(HookFailed { contract, error }))
+        },
+        REFUND_REPLY_ID => {
+            let SubMsgResult::Err(error) = msg.result else {
+                return Ok(Response::new());
+            };
+
+            let bid_id: BidId = from_json(&msg.payload)?;
+
+            Ok(Response::new().add_events(ibc::hold_refund(deps.storage, bid_id, error)?))
         },
-        AdvanceCrank {} => exec::advance_crank(deps, info, env).map_err(Into::into),
+        id => Err(ContractError::UnknownReplyId { id }),
+    }
+}
+
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response> {
+    use SudoMsg::*;
+
+    assert_not_paused(deps.as_ref(), PauseScope::Settlement)?;
+
+    match msg {
+        AdvanceCrank {
+            max_items,
+        } => exec::sudo_advance_crank(deps, env, max_items),
+        CloseExpiredAuctions {} => exec::close_expired_auctions(deps, env),
     }
 }
 
-mod exec {
+pub(crate) mod exec {
     use std::vec;
 
     use cosmwasm_std::{Uint128, Uint64};
+    use cw_storage_plus::Bound;
 
-    use crate::state::{Auction, AuctionId, AuctionStatus, Bid, BidId, BidItem, BidItemId, BidItemKey, BidItemStatus, BidKey, AUCTIONS_CRANK_QUEUE_COUNT, BIDS, BID_ITEMS, BID_ITEMS_TO_AUCTIONS, WINNING_BIDS};
+    use crate::state::{Auction, AuctionId, AuctionStatus, Bid, BidId, BidItem, BidItemId, BidItemKey, BidItemStatus, BidKey, BidderAccess, PayoutMode, ALLOWLISTS, AUCTIONS_CRANK_QUEUE_COUNT, AUCTION_EXPIRIES, BIDDER_STATS, BIDS, BID_ITEMS, BID_ITEMS_TO_AUCTIONS, CLAIMABLE, CRANK_BID_CURSORS, DEPOSITS, CRANK_CURSORS, CRANK_REWARD_POOL, WINNING_BIDS};
 
     use super::*;
 
-    pub fn create_auction(deps: DepsMut, info: MessageInfo, name: String, bid_items: Vec<String>) -> Result<Response> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_auction(deps: DepsMut, info: MessageInfo, env: Env, name: String, description: Option<String>, terms: Option<String>, ends_at: Option<Timestamp>, payout_mode: PayoutMode, owner: Option<String>, bid_items: Vec<BidItemInput>) -> Result<Response> {
         let curr_admin = ADMIN.load(deps.storage)?;
 
-        if curr_admin != &info.sender {
+        if curr_admin != info.sender {
             return Err(ContractError::Unauthorized { sender: info.sender });
         }
 
+        let owner = match owner {
+            Some(owner) => deps.api.addr_validate(&owner)?,
+            None => info.sender,
+        };
+
+        validate_length("auction name", &name, MAX_NAME_LENGTH)?;
+        validate_optional_length("auction description", &description, MAX_DESCRIPTION_LENGTH)?;
+        validate_optional_length("auction terms", &terms, MAX_TERMS_LENGTH)?;
+
+        if ends_at.is_some_and(|ends_at| ends_at <= env.block.time) {
+            return Err(ContractError::InvalidEndTime);
+        }
+
         let auction_id = AuctionId::next(deps.storage)?;
 
         let auction = Auction {
-            name: name,
-            total_bids: Uint64::from(0 as u64),
-            total_coins: Uint128::from(0 as u128),
-            available_bid_items: Uint64::from(bid_items.len() as u64),
+            name,
+            description,
+            terms,
+            owner,
+            total_bids: Uint64::from(0_u64),
+            total_coins: Uint128::from(0_u128),
+            available_bid_items: Uint64::zero(),
             current_state: AuctionStatus::Active,
+            ends_at,
+            payout_mode,
+            bidder_access: BidderAccess::Open,
         };
 
         AUCTIONS.save(deps.storage, auction_id, &auction)?;
 
-        add_bid_items_to_auction(bid_items, auction_id, deps)?;
+        if let Some(ends_at) = ends_at {
+            AUCTION_EXPIRIES.save(deps.storage, (ends_at.seconds(), auction_id), &())?;
+        }
+
+        let event = AuctionCreated {
+            auction_id,
+            name: auction.name,
+            description: auction.description,
+            terms: auction.terms,
+            owner: auction.owner,
+            bid_items: bid_items.len() as u64,
+            ends_at,
+            payout_mode,
+        };
+
+        let events = add_bid_items_to_auction(bid_items, auction_id, deps)?;
 
         let attributes = Some(vec![("auction_id".to_string(), auction_id.to_string())]);
-        let response = response("create_auction", "Successfully created auction.", attributes);
+        let response = response("create_auction", "Successfully created auction.", attributes)
+            .add_event(event)
+            .add_events(events);
 
         Ok(response)
     }
 
-    fn add_bid_items_to_auction(bid_items: Vec<String>, auction_id: AuctionId, deps: DepsMut<'_>) -> Result<()> {
+    fn add_bid_items_to_auction(bid_items: Vec<BidItemInput>, auction_id: AuctionId, deps: DepsMut<'_>) -> Result<Vec<Event>> {
+        for bid_item in &bid_items {
+            validate_bid_item(bid_item)?;
+            validate_prices(deps.as_ref(), bid_item)?;
+        }
+
+        let mut auction = AUCTIONS.load(deps.storage, auction_id)?;
+        auction.available_bid_items += Uint64::from(bid_items.len() as u64);
+        AUCTIONS.save(deps.storage, auction_id, &auction)?;
+
+        let mut events: Vec<Event> = vec![];
 
         for bid_item in bid_items {
             let bid_item_id = BidItemId::next(deps.storage)?;
@@ -144,31 +520,45 @@ mod exec {
             };
 
             let item = BidItem {
-                name: bid_item,
-                total_bids: Uint64::from(0 as u64),
-                total_coins: Uint128::from(0 as u128),
+                name: bid_item.name.clone(),
+                description: bid_item.description.clone(),
+                media_uris: bid_item.media_uris.clone(),
+                category: bid_item.category.clone(),
+                attributes: bid_item.attributes.clone(),
+                total_bids: Uint64::from(0_u64),
+                total_coins: Uint128::from(0_u128),
                 winner: None,
-                current_state: BidItemStatus::Active
+                current_state: BidItemStatus::Active,
+                starting_price: bid_item.starting_price,
+                reserve_price: bid_item.reserve_price,
             };
         
             BID_ITEMS.save(deps.storage, key, &item)?;
             BID_ITEMS_TO_AUCTIONS.save(deps.storage, bid_item_id, &auction_id)?;
+
+            events.push(BidItemAdded {
+                auction_id,
+                bid_item_id,
+                item: bid_item,
+            }.into());
         };
 
-        Ok(())
+        Ok(events)
     }
     
     pub fn set_auction_state(deps: DepsMut, info: MessageInfo, id: AuctionId, auction_status: AuctionStatus) -> Result<Response> {
         let curr_admin: Addr = ADMIN.load(deps.storage)?;
 
-        if curr_admin != &info.sender {
-            return Err(ContractError::Unauthorized { sender: info.sender });
-        }
-
         let mut auction = AUCTIONS
             .may_load(deps.storage, id)?
             .ok_or(ContractError::InvalidAuctionId)?;
 
+        if curr_admin != info.sender && auction.owner != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender });
+        }
+
+        let previous = auction.current_state;
+
         let response = match auction.current_state {
             AuctionStatus::PendingCompletion => {
                 return Err(ContractError::AuctionInvalidStateUpdate { msg: "Can't revert an auction that's already in pending completion.".to_string() });
@@ -176,6 +566,9 @@ mod exec {
             AuctionStatus::Completed => {
                 return Err(ContractError::AuctionInvalidStateUpdate { msg: "Can't revert an auction that's already completed.".to_string() });
             },
+            AuctionStatus::Cancelled => {
+                return Err(ContractError::AuctionInvalidStateUpdate { msg: "Can't revert an auction that's already cancelled.".to_string() });
+            },
             AuctionStatus::Suspended =>  {
                 match auction_status {
                     AuctionStatus::PendingCompletion | AuctionStatus::Active  => {
@@ -183,11 +576,7 @@ mod exec {
                         AUCTIONS.save(deps.storage, id, &auction)?;
 
                         if auction_status == AuctionStatus::PendingCompletion {
-                            AUCTIONS_CRANK_QUEUE.save(deps.storage, id, &())?;
-                            let mut count = AUCTIONS_CRANK_QUEUE_COUNT.load(deps.storage).unwrap_or(0);
-                            count += 1;
-
-                            AUCTIONS_CRANK_QUEUE_COUNT.save(deps.storage, &count)?;
+                            let count = enqueue_for_crank(deps, id)?;
 
                             let attributes = Some(vec![("auctions_crank_queue_count".to_string(), count.to_string())]);
                             response("set_auction_state", "Auction has been transitioned to the desired state.", attributes)
@@ -203,6 +592,9 @@ mod exec {
                     AuctionStatus::Completed => {
                         return Err(ContractError::AuctionInvalidStateUpdate { msg: "Only the crank can set an auction to a complete state.".to_string() });
                     },
+                    AuctionStatus::Cancelled => {
+                        return Err(ContractError::AuctionInvalidStateUpdate { msg: "Use CancelAuction to cancel an auction.".to_string() });
+                    },
                 }
             },
             AuctionStatus::Active =>  {
@@ -210,11 +602,7 @@ mod exec {
                 AUCTIONS.save(deps.storage, id, &auction)?;
 
                 if auction_status == AuctionStatus::PendingCompletion {
-                    AUCTIONS_CRANK_QUEUE.save(deps.storage, id, &())?;
-                    let mut count = AUCTIONS_CRANK_QUEUE_COUNT.load(deps.storage).unwrap_or(0);
-                    count += 1;
-
-                    AUCTIONS_CRANK_QUEUE_COUNT.save(deps.storage, &count)?;
+                    let count = enqueue_for_crank(deps, id)?;
 
                     let attributes = Some(vec![("auctions_crank_queue_count".to_string(), count.to_string())]);
                     response("set_auction_state", "Auction has been transitioned to the desired state.", attributes)
@@ -222,241 +610,1583 @@ mod exec {
                 else if auction_status == AuctionStatus::Completed {
                     return Err(ContractError::AuctionInvalidStateUpdate { msg: "Only the crank can set an auction to a complete state.".to_string() });
                 }
+                else if auction_status == AuctionStatus::Cancelled {
+                    return Err(ContractError::AuctionInvalidStateUpdate { msg: "Use CancelAuction to cancel an auction.".to_string() });
+                }
                 else {
                     response("set_auction_state", "Auction has been transitioned to the desired state.", None)
                 }
             },
         };
 
-        Ok(response)
-    }
-    
-    fn response(action: &str, msg: &str, attributes: Option<Vec<(String, String)>>) -> Response {
-        let mut response = Response::new()
-            .add_attribute("action", action)
-            .add_attribute("response", msg);
-
-        match attributes {
-            Some(attributes) => {
-                for attribute in attributes {
-                    response = response.add_attribute(attribute.0, attribute.1);
-                }
-            },
-            None => {},
-        }
+        let response = response.add_event(AuctionStateChanged {
+            auction_id: id,
+            previous,
+            next: auction_status,
+        });
 
-        response
+        Ok(response)
     }
     
-    pub fn add_bid_items(deps: DepsMut, info: MessageInfo, auction_id: AuctionId, bid_items: Vec<String>) -> Result<Response> {
+    pub fn cancel_auction(deps: DepsMut, info: MessageInfo, id: AuctionId) -> Result<Response> {
         let curr_admin: Addr = ADMIN.load(deps.storage)?;
 
-        if curr_admin != &info.sender {
+        let mut auction = AUCTIONS
+            .may_load(deps.storage, id)?
+            .ok_or(ContractError::InvalidAuctionId)?;
+
+        if curr_admin != info.sender && auction.owner != info.sender {
             return Err(ContractError::Unauthorized { sender: info.sender });
         }
 
-        let auction = AUCTIONS
-            .may_load(deps.storage, auction_id)?
-            .ok_or(ContractError::InvalidAuctionId)?;
-
         match auction.current_state {
-            AuctionStatus::PendingCompletion | AuctionStatus::Completed => {
-                return Err(ContractError::AuctionNonUpdateable);
-
+            AuctionStatus::Active | AuctionStatus::Suspended => {},
+            AuctionStatus::Cancelled => {
+                return Err(ContractError::AuctionInvalidStateUpdate { msg: "Auction is already cancelled.".to_string() });
+            },
+            _ => {
+                return Err(ContractError::AuctionInvalidStateUpdate { msg: "Only active or suspended auctions can be cancelled.".to_string() });
             },
-            _ => {},
         }
 
-        add_bid_items_to_auction(bid_items, auction_id, deps)?;
+        let previous = auction.current_state;
+
+        auction.current_state = AuctionStatus::Cancelled;
+        AUCTIONS.save(deps.storage, id, &auction)?;
+
+        // The crank refunds every bid of a cancelled auction
+        let count = enqueue_for_crank(deps, id)?;
 
-        let response = response("add_bid_items", "Successfully added bid items to auction.", None);
+        let attributes = Some(vec![("auctions_crank_queue_count".to_string(), count.to_string())]);
+        let response = response("cancel_auction", "Auction has been cancelled.", attributes)
+            .add_event(AuctionStateChanged {
+                auction_id: id,
+                previous,
+                next: AuctionStatus::Cancelled,
+            });
 
         Ok(response)
     }
 
-    pub fn place_bid(deps: DepsMut, info: MessageInfo, env: Env, bid_item_id: BidItemId, coins_to_bid: Uint128) -> Result<Response> {
+    pub fn update_bid_item(deps: DepsMut, info: MessageInfo, bid_item_id: BidItemId, input: BidItemInput) -> Result<Response> {
+        let (key, _) = load_updateable_bid_item(&deps, &info, bid_item_id)?;
 
-        let auction_id = BID_ITEMS_TO_AUCTIONS
-            .may_load(deps.storage, bid_item_id)?
-            .ok_or(ContractError::InvalidBidItemId)?;
+        validate_bid_item(&input)?;
+        validate_prices(deps.as_ref(), &input)?;
 
-        let auction = AUCTIONS
-            .may_load(deps.storage, auction_id)?
-            .ok_or(ContractError::InvalidAuctionId)?;
+        let mut bid_item = BID_ITEMS.load(deps.storage, key)?;
 
-        if auction.current_state != AuctionStatus::Active {
-            return Err(ContractError::AuctionCompleted);
+        // Bidders bid against the prices they saw, a suspended auction can't move them
+        if !bid_item.total_bids.is_zero() && (input.starting_price != bid_item.starting_price || input.reserve_price != bid_item.reserve_price) {
+            return Err(ContractError::BidItemPricesLocked);
         }
 
-        let bid_id = BidId::next(deps.storage)?;
+        bid_item.name = input.name.clone();
+        bid_item.description = input.description.clone();
+        bid_item.media_uris = input.media_uris.clone();
+        bid_item.category = input.category.clone();
+        bid_item.attributes = input.attributes.clone();
+        bid_item.starting_price = input.starting_price;
+        bid_item.reserve_price = input.reserve_price;
+        BID_ITEMS.save(deps.storage, key, &bid_item)?;
+
+        let attributes = Some(vec![("bid_item_id".to_string(), bid_item_id.to_string())]);
+        let response = response("update_bid_item", "Successfully updated bid item.", attributes)
+            .add_event(BidItemUpdated {
+                auction_id: key.auction_id,
+                bid_item_id,
+                item: input,
+            });
 
-        let item = Bid {
-            amount: coins_to_bid,
-            bidder: info.sender, 
-            placed: env.block.time,
-        };
+        Ok(response)
+    }
 
-        let key = BidKey {
-            bid_id,
-            bid_item_id, 
-        };
+    pub fn remove_bid_item(deps: DepsMut, info: MessageInfo, bid_item_id: BidItemId) -> Result<Response> {
+        let (key, _) = load_updateable_bid_item(&deps, &info, bid_item_id)?;
+        let mut bid_item = BID_ITEMS.load(deps.storage, key)?;
 
-        BIDS.save(deps.storage, key, &item)?;
+        let attributes = Some(vec![("bid_item_id".to_string(), bid_item_id.to_string())]);
+
+        // Only suspended auctions can get here with bids, the crank refunds those in chunks and
+        // deletes the item after the last one
+        if !bid_item.total_bids.is_zero() {
+            bid_item.current_state = BidItemStatus::Cancelled;
+            BID_ITEMS.save(deps.storage, key, &bid_item)?;
+            REMOVED_BID_ITEMS.save(deps.storage, bid_item_id, &key.auction_id)?;
 
-        check_winning_bid(deps, bid_item_id, item, key)?;
+            let response = response("remove_bid_item", "Successfully cancelled bid item, its bids are refunded by the crank.", attributes)
+                .add_event(BidItemCancelled {
+                    auction_id: key.auction_id,
+                    bid_item_id,
+                });
+
+            return Ok(response);
+        }
 
-        let response: Response = response("place_bid", "Successfully placed bid.", None);
+        let response = response("remove_bid_item", "Successfully removed bid item.", attributes)
+            .add_event(delete_bid_item(deps.storage, key)?);
 
         Ok(response)
     }
 
-    fn check_winning_bid(deps: DepsMut<'_>, bid_item_id: BidItemId, item: Bid, key: BidKey) -> Result<()> {
-        Ok(match WINNING_BIDS.may_load(deps.storage, bid_item_id)? {
-            Some(bid) => {  // There's an existing winning bid.
-                let current_winning_bid = BIDS.load(deps.storage, bid)?;
-    
-                if item.amount > current_winning_bid.amount {
-                    WINNING_BIDS.save(deps.storage, bid_item_id, &key)?;
-                }
-            }
-            None => {   // No winning bid exists, set current one as winning bid.
-                WINNING_BIDS.save(deps.storage, bid_item_id, &key)?;
-            }
-        })
+    /// Deletes a bid item whose bids are all gone, it no longer counts as one of its auction's.
+    fn delete_bid_item(storage: &mut dyn Storage, key: BidItemKey) -> Result<Event> {
+        WINNING_BIDS.remove(storage, key.bid_item_id);
+        BID_ITEMS.remove(storage, key)?;
+        BID_ITEMS_TO_AUCTIONS.remove(storage, key.bid_item_id);
+        REMOVED_BID_ITEMS.remove(storage, key.bid_item_id);
+
+        let mut auction = AUCTIONS.load(storage, key.auction_id)?;
+        auction.available_bid_items = auction.available_bid_items.saturating_sub(Uint64::one());
+        AUCTIONS.save(storage, key.auction_id, &auction)?;
+
+        Ok(BidItemRemoved {
+            auction_id: key.auction_id,
+            bid_item_id: key.bid_item_id,
+        }.into())
     }
-    
-    pub fn advance_crank(deps: DepsMut, _info: MessageInfo, _env: Env) -> Result<Response> {
-        let auction_ids_to_process = extract_auction_ids_to_process(&deps);
-
-        let mut processed_bid_items = 0;
-        let mut auctions_completed: Vec<AuctionId> = vec![];
-
-        for auction_id in auction_ids_to_process {
 
-            let mut bid_items = get_pending_bid_items_by_auction_id(&deps, auction_id)?;
+    /// Deletes refunded bids of a removed bid item, taking them out of the item and auction totals.
+    fn delete_bids(storage: &mut dyn Storage, key: BidItemKey, bids: &[(BidId, Bid)]) -> Result<()> {
+        let mut bid_item = BID_ITEMS.load(storage, key)?;
+        let mut auction = AUCTIONS.load(storage, key.auction_id)?;
 
-            while processed_bid_items < CRANK_MAX_BID_ITEMS && bid_items.len() > 0 {
+        for (bid_id, bid) in bids {
+            BIDS.remove(storage, BidKey { bid_item_id: key.bid_item_id, bid_id: *bid_id })?;
 
-                let mut bid_item = bid_items.pop().unwrap();
+            bid_item.total_bids = bid_item.total_bids.saturating_sub(Uint64::one());
+            bid_item.total_coins = bid_item.total_coins.saturating_sub(bid.amount);
+            auction.total_bids = auction.total_bids.saturating_sub(Uint64::one());
+            auction.total_coins = auction.total_coins.saturating_sub(bid.amount);
+        }
 
-                if bid_item.1.winner == None {  // If there's no winner then it means this bid item is still pending
-                    let bids = get_bids_from_bid_item_id(&deps, bid_item.0)?;
+        BID_ITEMS.save(storage, key, &bid_item)?;
+        AUCTIONS.save(storage, key.auction_id, &auction)?;
 
-                    let key = BidItemKey {
-                        auction_id: auction_id,
-                        bid_item_id: bid_item.0,
-                    };
+        Ok(())
+    }
 
-                    if bids.len() > 0 {
-                        let winning_bid = WINNING_BIDS
-                        .load(deps.storage, bid_item.0)?;
+    /// Loads the auction of a bid item that the sender wants to edit or remove. Bid items can only
+    /// be changed while they have no bids, unless the auction is suspended.
+    fn load_updateable_bid_item(deps: &DepsMut, info: &MessageInfo, bid_item_id: BidItemId) -> Result<(BidItemKey, Auction)> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
 
-                        // Refund other bids & process Winning bid
-                        process_bids(&deps, winning_bid.bid_id, &bids)?;
+        let auction_id = BID_ITEMS_TO_AUCTIONS
+            .may_load(deps.storage, bid_item_id)?
+            .ok_or(ContractError::InvalidBidItemId)?;
 
-                        let bid = BIDS.load(deps.storage, winning_bid)?;
+        let auction = AUCTIONS
+            .may_load(deps.storage, auction_id)?
+            .ok_or(ContractError::InvalidAuctionId)?;
 
-                        // Update Bid Item
-                        bid_item.1.winner = Some(bid.bidder);
-                    }
+        if curr_admin != info.sender && auction.owner != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender.clone() });
+        }
 
-                    bid_item.1.current_state = BidItemStatus::Completed;
-                    
-                    BID_ITEMS.save(deps.storage, key, &bid_item.1)?;
-    
-                    processed_bid_items += 1;
+        match auction.current_state {
+            AuctionStatus::Active => {
+                if WINNING_BIDS.has(deps.storage, bid_item_id) {
+                    return Err(ContractError::BidItemNonUpdateable);
                 }
-            }
+            },
+            AuctionStatus::Suspended => {},
+            _ => {
+                return Err(ContractError::AuctionNonUpdateable);
+            },
+        }
 
-            if bid_items.len() == 0 {    // This means that the auction has been completed, so marking it as completed to remove it below
-                auctions_completed.push(auction_id);
-            }
+        // Removed items wait for the crank to refund their bids
+        if REMOVED_BID_ITEMS.has(deps.storage, bid_item_id) {
+            return Err(ContractError::InvalidBidItemId);
         }
 
-        // Removing Auctions from Crank queue
-        for auction_completed in auctions_completed {
-            AUCTIONS_CRANK_QUEUE.remove(deps.storage, auction_completed);
-            let mut count = AUCTIONS_CRANK_QUEUE_COUNT.load(deps.storage).unwrap_or(0);
-            if count > 0 {
-                count -= 1;
-            }
+        let key = BidItemKey {
+            auction_id,
+            bid_item_id,
+        };
 
-            AUCTIONS_CRANK_QUEUE_COUNT.save(deps.storage, &count)?;
+        Ok((key, auction))
+    }
 
-            // Update Auction status as Complete
-            let mut auction = AUCTIONS.load(deps.storage, auction_completed)?;
-            auction.current_state = AuctionStatus::Completed;
-            AUCTIONS.save(deps.storage, auction_completed, &auction)?;
+    pub fn update_config(deps: DepsMut, info: MessageInfo, update: ConfigUpdate) -> Result<Response> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+
+        if curr_admin != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender });
         }
 
-        let response = response("place_bid", "Successfully advanced crank.", None);
-                
-        Ok(response)
-    }
+        let mut config = CONFIG.load(deps.storage)?;
 
-    fn extract_auction_ids_to_process (deps: &DepsMut<'_>) -> Vec<AuctionId> {
-        let mut auction_ids_to_process: Vec<AuctionId> = vec![];
-        let auctions_crank_queue = AUCTIONS_CRANK_QUEUE.range(deps.storage, None, None, Order::Ascending);
-    
-        for auction_id in auctions_crank_queue {
-            auction_ids_to_process.push(auction_id.unwrap().0);
+        if let Some(default_page_limit) = update.default_page_limit {
+            config.default_page_limit = default_page_limit;
         }
 
-        auction_ids_to_process
-    }
-    
-    pub fn get_pending_bid_items_by_auction_id(deps: &DepsMut, auction_id: AuctionId) -> Result<Vec<(BidItemId, BidItem)>> {
-        let iter = BID_ITEMS
-            .prefix(auction_id)
-            .range(deps.storage, None, None, Order::Ascending);
+        if let Some(max_page_limit) = update.max_page_limit {
+            config.max_page_limit = max_page_limit;
+        }
 
-        let mut results: Vec<(BidItemId, BidItem)> = Vec::new();
+        if let Some(crank_reward) = update.crank_reward {
+            config.crank_reward = crank_reward;
+        }
 
-        for bid_item in iter {
-            let (key, value) = bid_item?;
-            if value.current_state == BidItemStatus::Active {
-                results.push((key, value));
-            }
+        if let Some(crank_reward_fee_bps) = update.crank_reward_fee_bps {
+            config.crank_reward_fee_bps = crank_reward_fee_bps;
         }
 
-        Ok(results)
-    }
+        if let Some(crank_batch_size) = update.crank_batch_size {
+            config.crank_batch_size = crank_batch_size;
+        }
 
-    pub fn get_bids_from_bid_item_id(deps: &DepsMut, bid_item_id: BidItemId) -> Result<Vec<(BidId, Bid)>> {
-        let iter = BIDS
-            .prefix(bid_item_id)
-            .range(deps.storage, None, None, Order::Ascending);
+        if let Some(max_crank_batch_size) = update.max_crank_batch_size {
+            config.max_crank_batch_size = max_crank_batch_size;
+        }
 
-        let mut results: Vec<(BidId, Bid)> = Vec::new();
+        if let Some(crank_max_bids) = update.crank_max_bids {
+            config.crank_max_bids = crank_max_bids;
+        }
 
-        for bid in iter {
-            let (key, value) = bid?;
-            results.push((key, value));
+        match (update.guardian, update.clear_guardian) {
+            (Some(_), true) => {
+                return Err(ContractError::InvalidConfig { msg: "guardian can't be set and cleared at once".to_string() });
+            },
+            (Some(guardian), false) => config.guardian = Some(deps.api.addr_validate(&guardian)?),
+            (None, true) => config.guardian = None,
+            (None, false) => {},
         }
 
-        Ok(results)
-    }
+        if let Some(max_active_bids) = update.max_active_bids_per_bidder {
+            config.max_active_bids_per_bidder = (max_active_bids != 0).then_some(max_active_bids);
+        }
 
-    pub fn process_bids(deps: &DepsMut, winning_bid_id: BidId, bids: &Vec<(BidId, Bid)>) -> Result<()> {
-        let curr_admin: Addr = ADMIN.load(deps.storage)?;
-        let curr_admin_str = curr_admin.to_string();
+        if let Some(max_escrow) = update.max_escrow_per_bidder {
+            config.max_escrow_per_bidder = (!max_escrow.is_zero()).then_some(max_escrow);
+        }
+
+        if let Some(oracle) = update.oracle {
+            if oracle.max_deviation_bps > MAX_BPS {
+                return Err(ContractError::InvalidConfig { msg: format!("max_deviation_bps can't be greater than {MAX_BPS}") });
+            }
+
+            config.oracle = Some(oracle);
+        }
+
+        if let Some(hook_gas_limit) = update.hook_gas_limit {
+            config.hook_gas_limit = hook_gas_limit;
+        }
+
+        if config.default_page_limit == 0 || config.default_page_limit > config.max_page_limit {
+            return Err(ContractError::InvalidConfig { msg: "default_page_limit must be between 1 and max_page_limit".to_string() });
+        }
+
+        if config.crank_batch_size == 0 || config.crank_batch_size > config.max_crank_batch_size {
+            return Err(ContractError::InvalidConfig { msg: "crank_batch_size must be between 1 and max_crank_batch_size".to_string() });
+        }
+
+        if config.crank_max_bids == 0 {
+            return Err(ContractError::InvalidConfig { msg: "crank_max_bids can't be zero".to_string() });
+        }
+
+        if config.hook_gas_limit == 0 {
+            return Err(ContractError::InvalidConfig { msg: "hook_gas_limit can't be zero".to_string() });
+        }
+
+        if config.crank_reward_fee_bps > MAX_BPS {
+            return Err(ContractError::InvalidConfig { msg: format!("crank_reward_fee_bps can't be greater than {MAX_BPS}") });
+        }
+
+        CONFIG.save(deps.storage, &config)?;
+
+        let response = response("update_config", "Successfully updated config.", None);
+
+        Ok(response)
+    }
+
+    /// Pauses or unpauses a scope, or all of them, can be done by the admin or the guardian.
+    pub fn set_paused(deps: DepsMut, info: MessageInfo, scope: Option<PauseScope>, paused: bool) -> Result<Response> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+        let config = CONFIG.load(deps.storage)?;
+
+        if curr_admin != info.sender && config.guardian.as_ref() != Some(&info.sender) {
+            return Err(ContractError::Unauthorized { sender: info.sender });
+        }
+
+        let scopes = match scope {
+            Some(scope) => vec![scope],
+            None => vec![PauseScope::Bidding, PauseScope::Listing, PauseScope::Settlement],
+        };
+
+        let mut paused_scopes = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+        paused_scopes.retain(|scope| !scopes.contains(scope));
+
+        if paused {
+            paused_scopes.extend(scopes);
+        }
+
+        PAUSED.save(deps.storage, &paused_scopes)?;
+
+        let paused_list = paused_scopes.iter().map(PauseScope::as_str).collect::<Vec<_>>().join(",");
+        let attributes = Some(vec![("paused".to_string(), paused_list)]);
+        let response = response("set_paused", "Successfully updated paused scopes.", attributes);
+
+        Ok(response)
+    }
+
+    pub fn set_bidder_access(deps: DepsMut, info: MessageInfo, auction_id: AuctionId, access: BidderAccess) -> Result<Response> {
+        let mut auction = load_access_managed_auction(&deps, &info, auction_id)?;
+
+        auction.bidder_access = access;
+        AUCTIONS.save(deps.storage, auction_id, &auction)?;
+
+        let attributes = Some(vec![("auction_id".to_string(), auction_id.to_string())]);
+        let response = response("set_bidder_access", "Successfully updated the bidder access.", attributes);
+
+        Ok(response)
+    }
+
+    /// Allowlist entries are kept whatever the access mode, they only apply to `BidderAccess::Allowlist`.
+    pub fn update_allowlist(deps: DepsMut, info: MessageInfo, auction_id: AuctionId, add: Vec<Addr>, remove: Vec<Addr>) -> Result<Response> {
+        load_access_managed_auction(&deps, &info, auction_id)?;
+
+        for bidder in &add {
+            ALLOWLISTS.save(deps.storage, (auction_id, bidder), &())?;
+        }
+
+        for bidder in &remove {
+            ALLOWLISTS.remove(deps.storage, (auction_id, bidder));
+        }
+
+        let attributes = Some(vec![
+            ("auction_id".to_string(), auction_id.to_string()),
+            ("added".to_string(), add.len().to_string()),
+            ("removed".to_string(), remove.len().to_string()),
+        ]);
+        let response = response("update_allowlist", "Successfully updated the allowlist.", attributes);
+
+        Ok(response)
+    }
+
+    /// Loads an auction whose bidder access can be managed by the sender, the admin or the auction owner.
+    fn load_access_managed_auction(deps: &DepsMut, info: &MessageInfo, auction_id: AuctionId) -> Result<Auction> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+
+        let auction = AUCTIONS
+            .may_load(deps.storage, auction_id)?
+            .ok_or(ContractError::InvalidAuctionId)?;
+
+        if curr_admin != info.sender && auction.owner != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender.clone() });
+        }
+
+        if matches!(auction.current_state, AuctionStatus::Completed | AuctionStatus::Cancelled) {
+            return Err(ContractError::AuctionNonUpdateable);
+        }
+
+        Ok(auction)
+    }
+
+    pub fn add_hook(deps: DepsMut, info: MessageInfo, env: Env, contract: Addr) -> Result<Response> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+
+        if curr_admin != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender });
+        }
+
+        if HOOKS.has(deps.storage, &contract) {
+            return Err(ContractError::HookAlreadyRegistered { contract });
+        }
+
+        let hooks = HOOKS.keys(deps.storage, None, None, Order::Ascending).count();
+
+        if hooks >= MAX_HOOKS {
+            return Err(ContractError::TooManyHooks { max: MAX_HOOKS as u32 });
+        }
+
+        HOOKS.save(deps.storage, &contract, &env.block.time)?;
+
+        let attributes = Some(vec![("contract".to_string(), contract.to_string())]);
+        let response = response("add_hook", "Successfully added hook.", attributes);
+
+        Ok(response)
+    }
+
+    /// Saves the records of a migrated first version contract again, up to `limit` of them per call.
+    pub fn reindex(deps: DepsMut, info: MessageInfo, limit: Option<u32>) -> Result<Response> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+
+        if curr_admin != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender });
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+        let limit = limit.unwrap_or(config.default_page_limit).min(config.max_page_limit) as usize;
+
+        let mut step = REINDEX_CURSOR
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NothingToReindex)?;
+        let mut reindexed = 0;
+
+        // A step that runs out of records before the limit hands over to the next one
+        let done = loop {
+            let remaining = limit - reindexed;
+
+            if remaining == 0 {
+                break false;
+            }
+
+            step = match step {
+                ReindexStep::Auctions { start_after } => {
+                    let auctions = AUCTIONS
+                        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+                        .take(remaining)
+                        .collect::<StdResult<Vec<_>>>()?;
+
+                    reindexed += auctions.len();
+                    let last = auctions.last().map(|(auction_id, _)| *auction_id);
+                    let exhausted = auctions.len() < remaining;
+
+                    for (auction_id, mut auction) in auctions {
+                        if auction.owner.as_str().is_empty() {
+                            auction.owner = curr_admin.clone();
+                        }
+
+                        AUCTIONS.save(deps.storage, auction_id, &auction)?;
+                    }
+
+                    match exhausted {
+                        true => ReindexStep::BidItems { start_after: None },
+                        false => ReindexStep::Auctions { start_after: last },
+                    }
+                },
+                ReindexStep::BidItems { start_after } => {
+                    let bid_items = BID_ITEMS
+                        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+                        .take(remaining)
+                        .collect::<StdResult<Vec<_>>>()?;
+
+                    reindexed += bid_items.len();
+                    let last = bid_items.last().map(|(key, _)| *key);
+                    let exhausted = bid_items.len() < remaining;
+
+                    for (key, bid_item) in bid_items {
+                        BID_ITEMS.save(deps.storage, key, &bid_item)?;
+                    }
+
+                    match exhausted {
+                        true => ReindexStep::Bids { start_after: None },
+                        false => ReindexStep::BidItems { start_after: last },
+                    }
+                },
+                ReindexStep::Bids { start_after } => {
+                    let bids = BIDS
+                        .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+                        .take(remaining)
+                        .collect::<StdResult<Vec<_>>>()?;
+
+                    reindexed += bids.len();
+                    let last = bids.last().map(|(key, _)| *key);
+                    let exhausted = bids.len() < remaining;
+
+                    for (key, bid) in bids {
+                        BIDS.save(deps.storage, key, &bid)?;
+                    }
+
+                    if exhausted {
+                        break true;
+                    }
+
+                    ReindexStep::Bids { start_after: last }
+                },
+            };
+        };
+
+        if done {
+            REINDEX_CURSOR.remove(deps.storage);
+        } else {
+            REINDEX_CURSOR.save(deps.storage, &step)?;
+        }
+
+        let attributes = Some(vec![
+            ("reindexed".to_string(), reindexed.to_string()),
+            ("done".to_string(), done.to_string()),
+        ]);
+        let response = response("reindex", "Successfully reindexed records.", attributes);
+
+        Ok(response)
+    }
+
+    pub fn remove_hook(deps: DepsMut, info: MessageInfo, contract: Addr) -> Result<Response> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+
+        if curr_admin != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender });
+        }
+
+        if !HOOKS.has(deps.storage, &contract) {
+            return Err(ContractError::HookNotRegistered { contract });
+        }
+
+        HOOKS.remove(deps.storage, &contract);
+
+        let attributes = Some(vec![("contract".to_string(), contract.to_string())]);
+        let response = response("remove_hook", "Successfully removed hook.", attributes);
+
+        Ok(response)
+    }
+
+    pub fn add_transfer_channel(deps: DepsMut, info: MessageInfo, env: Env, channel_id: String) -> Result<Response> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+
+        if curr_admin != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender });
+        }
+
+        if TRANSFER_CHANNELS.has(deps.storage, &channel_id) {
+            return Err(ContractError::TransferChannelExists { channel_id });
+        }
+
+        if !IBC_CHANNELS.may_load(deps.storage, &channel_id)?.is_some_and(|channel| channel.open) {
+            return Err(ContractError::ChannelNotOpen { channel_id });
+        }
+
+        TRANSFER_CHANNELS.save(deps.storage, &channel_id, &env.block.time)?;
+
+        let attributes = Some(vec![("channel_id".to_string(), channel_id)]);
+        let response = response("add_transfer_channel", "Successfully added transfer channel.", attributes);
+
+        Ok(response)
+    }
+
+    /// Packets received over the channel from now on are acknowledged with an error instead of
+    /// bidding, the other chain refunds them.
+    pub fn remove_transfer_channel(deps: DepsMut, info: MessageInfo, channel_id: String) -> Result<Response> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+
+        if curr_admin != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender });
+        }
+
+        if !TRANSFER_CHANNELS.has(deps.storage, &channel_id) {
+            return Err(ContractError::UnknownTransferChannel { channel_id });
+        }
+
+        TRANSFER_CHANNELS.remove(deps.storage, &channel_id);
+
+        let attributes = Some(vec![("channel_id".to_string(), channel_id)]);
+        let response = response("remove_transfer_channel", "Successfully removed transfer channel.", attributes);
+
+        Ok(response)
+    }
+
+    /// Sends the attached funds to `remote_address` over a channel remote bids are taken from, so
+    /// they can come back as bids.
+    pub fn transfer(deps: DepsMut, info: MessageInfo, env: Env, channel_id: String, remote_address: String, amount: Uint128) -> Result<Response> {
+        if !TRANSFER_CHANNELS.has(deps.storage, &channel_id) {
+            return Err(ContractError::UnknownTransferChannel { channel_id });
+        }
+
+        let transfer = Ics20Packet {
+            denom: DENOM.to_string(),
+            amount,
+            sender: info.sender.to_string(),
+            receiver: remote_address.clone(),
+            memo: String::new(),
+        };
+
+        let attributes = Some(vec![
+            ("channel_id".to_string(), channel_id.clone()),
+            ("remote_address".to_string(), remote_address),
+            ("amount".to_string(), amount.to_string()),
+        ]);
+        let msg = ibc::send_transfer(deps.storage, &env, &channel_id, transfer)?;
+        let response = response("transfer", "Successfully sent transfer.", attributes)
+            .add_message(msg);
+
+        Ok(response)
+    }
+
+    pub fn retry_settlement(deps: DepsMut, info: MessageInfo, env: Env, bid_id: BidId, channel_id: Option<String>) -> Result<Response> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+
+        if curr_admin != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender });
+        }
+
+        let Some(mut settlement) = UNDELIVERED_SETTLEMENTS.may_load(deps.storage, bid_id)? else {
+            return Err(ContractError::NoUndeliveredSettlement { bid_id });
+        };
+
+        if let Some(channel_id) = channel_id {
+            settlement.channel_id = channel_id;
+        }
+
+        UNDELIVERED_SETTLEMENTS.remove(deps.storage, bid_id);
+
+        let attributes = Some(vec![
+            ("bid_id".to_string(), bid_id.to_string()),
+            ("channel_id".to_string(), settlement.channel_id.clone()),
+        ]);
+        let msg = ibc::refund_msg(deps.storage, &env, bid_id, settlement)?;
+        let response = response("retry_settlement", "Successfully resent settlement.", attributes)
+            .add_submessage(msg);
+
+        Ok(response)
+    }
+
+    pub fn set_denied(deps: DepsMut, info: MessageInfo, env: Env, addresses: Vec<String>, denied: bool) -> Result<Response> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+
+        if curr_admin != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender });
+        }
+
+        let addresses = addresses
+            .iter()
+            .map(|address| deps.api.addr_validate(address))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for address in &addresses {
+            if denied {
+                DENYLIST.save(deps.storage, address, &env.block.time)?;
+            } else {
+                DENYLIST.remove(deps.storage, address);
+            }
+        }
+
+        let (action, msg) = if denied {
+            ("deny_addresses", "Successfully denied addresses.")
+        } else {
+            ("allow_addresses", "Successfully allowed addresses.")
+        };
+
+        let attributes = Some(vec![("addresses".to_string(), addresses.len().to_string())]);
+        let response = response(action, msg, attributes);
+
+        Ok(response)
+    }
+
+    /// Sends what's held for a denylisted address to the recipient, the address itself by default.
+    /// That's the denied escrow, plus the deposits and claimable balances the address can't take
+    /// out itself while it's denied.
+    pub fn resolve_escrow(deps: DepsMut, info: MessageInfo, address: String, recipient: Option<String>) -> Result<Response> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+
+        if curr_admin != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender });
+        }
+
+        let address = deps.api.addr_validate(&address)?;
+        let recipient = match recipient {
+            Some(recipient) => deps.api.addr_validate(&recipient)?,
+            None => address.clone(),
+        };
+
+        let mut amount = DENIED_ESCROW.may_load(deps.storage, &address)?.unwrap_or_default();
+        DENIED_ESCROW.remove(deps.storage, &address);
+
+        let mut released: Vec<Coin> = vec![];
+
+        // Allowed addresses withdraw and claim on their own
+        if DENYLIST.has(deps.storage, &address) {
+            amount += DEPOSITS.may_load(deps.storage, &address)?.unwrap_or_default();
+            DEPOSITS.remove(deps.storage, &address);
+
+            let claimable: Vec<(String, Uint128)> = CLAIMABLE
+                .prefix(&address)
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()?;
+
+            for (denom, claimable) in claimable {
+                CLAIMABLE.remove(deps.storage, (&address, &denom));
+
+                match denom == DENOM {
+                    true => amount += claimable,
+                    false => released.push(Coin { denom, amount: claimable }),
+                }
+            }
+        }
+
+        if !amount.is_zero() {
+            released.push(Coin { denom: DENOM.to_string(), amount });
+        }
+
+        if released.is_empty() {
+            return Err(ContractError::NothingEscrowed { address });
+        }
+
+        released.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+        let attributes = Some(vec![
+            ("recipient".to_string(), recipient.to_string()),
+            ("amount".to_string(), released.iter().map(Coin::to_string).collect::<Vec<_>>().join(",")),
+        ]);
+        let response = response("resolve_escrow", "Successfully resolved escrowed funds.", attributes)
+            .add_message(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: released,
+            });
+
+        Ok(response)
+    }
+
+    pub fn fund_crank_rewards(deps: DepsMut, info: MessageInfo, amount: Uint128) -> Result<Response> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+
+        if curr_admin != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender });
+        }
+
+        let pool = CRANK_REWARD_POOL.may_load(deps.storage)?.unwrap_or_default() + amount;
+        CRANK_REWARD_POOL.save(deps.storage, &pool)?;
+
+        let attributes = Some(vec![("crank_reward_pool".to_string(), pool.to_string())]);
+        let response = response("fund_crank_rewards", "Successfully funded crank rewards.", attributes);
+
+        Ok(response)
+    }
+
+    pub fn deposit(deps: DepsMut, info: MessageInfo, amount: Uint128) -> Result<Response> {
+        let balance = DEPOSITS.may_load(deps.storage, &info.sender)?.unwrap_or_default() + amount;
+        DEPOSITS.save(deps.storage, &info.sender, &balance)?;
+
+        let attributes = Some(vec![("deposit_balance".to_string(), balance.to_string())]);
+        let response = response("deposit", "Successfully deposited funds.", attributes);
+
+        Ok(response)
+    }
+
+    /// Withdraws the given amount from the sender's deposit, or all of it. Denylisted addresses
+    /// can't withdraw until they're allowed again.
+    pub fn withdraw(deps: DepsMut, info: MessageInfo, amount: Option<Uint128>) -> Result<Response> {
+        assert_not_denied(deps.as_ref(), &info.sender)?;
+
+        let available = DEPOSITS.may_load(deps.storage, &info.sender)?.unwrap_or_default();
+        let amount = amount.unwrap_or(available);
+
+        if amount.is_zero() || available < amount {
+            return Err(ContractError::InsufficientDeposit { available });
+        }
+
+        DEPOSITS.save(deps.storage, &info.sender, &(available - amount))?;
+
+        let attributes = Some(vec![("deposit_balance".to_string(), (available - amount).to_string())]);
+        let response = response("withdraw", "Successfully withdrew funds.", attributes)
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(amount.u128(), DENOM),
+            });
+
+        Ok(response)
+    }
+
+    /// Sends the sender's claimable balances, or only the given denoms. Denylisted addresses can't
+    /// claim until they're allowed again.
+    pub fn claim(deps: DepsMut, info: MessageInfo, denoms: Option<Vec<String>>) -> Result<Response> {
+        assert_not_denied(deps.as_ref(), &info.sender)?;
+
+        let claimable: Vec<(String, Uint128)> = CLAIMABLE
+            .prefix(&info.sender)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()?;
+
+        let mut claimed: Vec<Coin> = vec![];
+
+        for (denom, amount) in claimable {
+            if denoms.as_ref().is_some_and(|denoms| !denoms.contains(&denom)) {
+                continue;
+            }
+
+            CLAIMABLE.remove(deps.storage, (&info.sender, &denom));
+            claimed.push(Coin { denom, amount });
+        }
+
+        if claimed.is_empty() {
+            return Err(ContractError::NothingToClaim);
+        }
+
+        let attributes = Some(vec![("claimed".to_string(), claimed.iter().map(Coin::to_string).collect::<Vec<_>>().join(","))]);
+        let response = response("claim", "Successfully claimed funds.", attributes)
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: claimed,
+            });
+
+        Ok(response)
+    }
+
+    fn enqueue_for_crank(deps: DepsMut, id: AuctionId) -> Result<u64> {
+        AUCTIONS_CRANK_QUEUE.save(deps.storage, id, &())?;
+        let mut count = AUCTIONS_CRANK_QUEUE_COUNT.load(deps.storage).unwrap_or(0);
+        count += 1;
+
+        AUCTIONS_CRANK_QUEUE_COUNT.save(deps.storage, &count)?;
+
+        Ok(count)
+    }
+
+    fn response(action: &str, msg: &str, attributes: Option<Vec<(String, String)>>) -> Response {
+        let mut response = Response::new()
+            .add_attribute("action", action)
+            .add_attribute("response", msg);
+
+        if let Some(attributes) = attributes {
+            for attribute in attributes {
+                response = response.add_attribute(attribute.0, attribute.1);
+            }
+        }
+
+        response
+    }
+    
+    pub fn add_bid_items(deps: DepsMut, info: MessageInfo, auction_id: AuctionId, bid_items: Vec<BidItemInput>) -> Result<Response> {
+        let curr_admin: Addr = ADMIN.load(deps.storage)?;
+
+        let auction = AUCTIONS
+            .may_load(deps.storage, auction_id)?
+            .ok_or(ContractError::InvalidAuctionId)?;
+
+        if curr_admin != info.sender && auction.owner != info.sender {
+            return Err(ContractError::Unauthorized { sender: info.sender });
+        }
+
+        match auction.current_state {
+            AuctionStatus::PendingCompletion | AuctionStatus::Completed | AuctionStatus::Cancelled => {
+                return Err(ContractError::AuctionNonUpdateable);
+
+            },
+            _ => {},
+        }
+
+        let events = add_bid_items_to_auction(bid_items, auction_id, deps)?;
+
+        let response = response("add_bid_items", "Successfully added bid items to auction.", None)
+            .add_events(events);
+
+        Ok(response)
+    }
+
+    /// Every check comes before the first write, so a transfer received over IBC that fails to bid
+    /// leaves nothing behind when it's refunded.
+    pub(crate) fn place_bid(deps: DepsMut, env: Env, bidder: Bidder, bid_item_id: BidItemId, coins_to_bid: Uint128, source: BidSource) -> Result<Response> {
+
+        let auction_id = BID_ITEMS_TO_AUCTIONS
+            .may_load(deps.storage, bid_item_id)?
+            .ok_or(ContractError::InvalidBidItemId)?;
+
+        let mut auction = AUCTIONS
+            .may_load(deps.storage, auction_id)?
+            .ok_or(ContractError::InvalidAuctionId)?;
+
+        if auction.current_state != AuctionStatus::Active {
+            return Err(ContractError::AuctionCompleted);
+        }
+
+        if auction.ends_at.is_some_and(|ends_at| ends_at <= env.block.time) {
+            return Err(ContractError::AuctionExpired);
+        }
+
+        let bid_item_key = BidItemKey {
+            auction_id,
+            bid_item_id,
+        };
+
+        let mut bid_item = BID_ITEMS.load(deps.storage, bid_item_key)?;
+
+        // Removed items stay around until the crank refunded their bids
+        if bid_item.current_state != BidItemStatus::Active {
+            return Err(ContractError::InvalidBidItemId);
+        }
+
+        if let Some(starting_price) = bid_item.starting_price {
+            let starting_price = native_price(deps.as_ref(), &env, starting_price, &mut None)?;
+
+            if coins_to_bid < starting_price {
+                return Err(ContractError::BidBelowStartingPrice { starting_price });
+            }
+        }
+
+        let allowed = match &bidder {
+            Bidder::Local(address) => {
+                assert_not_denied(deps.as_ref(), address)?;
+                is_bidder_allowed(deps.as_ref(), auction_id, &auction.bidder_access, address)?
+            },
+            // Allowlists and verifiers only know local accounts
+            Bidder::Remote(_) => matches!(auction.bidder_access, BidderAccess::Open),
+        };
+
+        if !allowed {
+            return Err(ContractError::BidderNotAllowed { bidder });
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+
+        let stats_key = bidder.to_string();
+        let mut stats = BIDDER_STATS.may_load(deps.storage, &stats_key)?.unwrap_or_default();
+        stats.active_bids += 1;
+        stats.escrowed += coins_to_bid;
+
+        if let Some(max) = config.max_active_bids_per_bidder.filter(|max| stats.active_bids > *max) {
+            return Err(ContractError::TooManyActiveBids { max });
+        }
+
+        if let Some(max) = config.max_escrow_per_bidder.filter(|max| stats.escrowed > *max) {
+            return Err(ContractError::EscrowCapExceeded { max });
+        }
+
+        let from_deposit = matches!(source, BidSource::Deposit);
+
+        if from_deposit {
+            // Only local bidders have a deposit
+            let depositor = bidder.local().ok_or(ContractError::InsufficientDeposit { available: Uint128::zero() })?;
+            let available = DEPOSITS.may_load(deps.storage, depositor)?.unwrap_or_default();
+
+            if available < coins_to_bid {
+                return Err(ContractError::InsufficientDeposit { available });
+            }
+
+            DEPOSITS.save(deps.storage, depositor, &(available - coins_to_bid))?;
+        }
+
+        BIDDER_STATS.save(deps.storage, &stats_key, &stats)?;
+
+        let bid_id = BidId::next(deps.storage)?;
+
+        let item = Bid {
+            amount: coins_to_bid,
+            bidder,
+            placed: env.block.time,
+            from_deposit,
+        };
+
+        let key = BidKey {
+            bid_id,
+            bid_item_id, 
+        };
+
+        BIDS.save(deps.storage, key, &item)?;
+
+        bid_item.total_bids += Uint64::one();
+        bid_item.total_coins += coins_to_bid;
+        BID_ITEMS.save(deps.storage, bid_item_key, &bid_item)?;
+
+        auction.total_bids += Uint64::one();
+        auction.total_coins += coins_to_bid;
+        AUCTIONS.save(deps.storage, auction_id, &auction)?;
+
+        let placed = BidPlaced {
+            auction_id,
+            bid_item_id,
+            bid_id,
+            bidder: item.bidder.clone(),
+            amount: item.amount,
+            denom: DENOM.to_string(),
+            from_deposit,
+        };
+
+        let notification = HookMsg::BidPlaced {
+            auction_id,
+            bid_item_id,
+            bid_id,
+            bidder: placed.bidder.clone(),
+            amount: placed.amount,
+        };
+
+        let hooks = hook_messages(deps.as_ref(), &[notification])?;
+        let outbid = check_winning_bid(deps, bid_item_id, item, key)?;
+
+        let mut response: Response = response("place_bid", "Successfully placed bid.", None)
+            .set_data(to_json_binary(&bid_id)?)
+            .add_submessages(hooks)
+            .add_event(placed);
+
+        if let Some((outbid_id, outbid)) = outbid {
+            response = response.add_event(BidOutbid {
+                bid_item_id,
+                bid_id: outbid_id,
+                bidder: outbid.bidder,
+                amount: outbid.amount,
+                outbid_by: bid_id,
+            });
+        }
+
+        Ok(response)
+    }
+
+    /// Returns the previous winning bid when the new one beats it.
+    fn check_winning_bid(deps: DepsMut<'_>, bid_item_id: BidItemId, item: Bid, key: BidKey) -> Result<Option<(BidId, Bid)>> {
+        match WINNING_BIDS.may_load(deps.storage, bid_item_id)? {
+            Some(bid) => {  // There's an existing winning bid.
+                let current_winning_bid = BIDS.load(deps.storage, bid)?;
+    
+                if item.amount > current_winning_bid.amount {
+                    WINNING_BIDS.save(deps.storage, bid_item_id, &key)?;
+                    return Ok(Some((bid.bid_id, current_winning_bid)));
+                }
+            }
+            None => {   // No winning bid exists, set current one as winning bid.
+                WINNING_BIDS.save(deps.storage, bid_item_id, &key)?;
+            }
+        }
+
+        Ok(None)
+    }
+    
+    pub fn advance_crank(mut deps: DepsMut, info: MessageInfo, env: Env, max_items: Option<u32>) -> Result<Response> {
+        let config = CONFIG.load(deps.storage)?;
+        let mut outcome = run_crank(deps.branch(), &env, max_items)?;
+
+        // Rejecting empty calls so nobody can farm crank rewards
+        if outcome.is_empty() {
+            return Err(ContractError::NothingToCrank);
+        }
+
+        // Paying the crank caller from the reward pool, as far as it goes
+        let pool = CRANK_REWARD_POOL.may_load(deps.storage)?.unwrap_or_default();
+        let reward = config.crank_reward
+            .checked_mul(Uint128::from(outcome.rewarded_bid_items))
+            .unwrap_or(Uint128::MAX)
+            .min(pool);
+
+        CRANK_REWARD_POOL.save(deps.storage, &(pool - reward))?;
+
+        if !reward.is_zero() {
+            outcome.payouts.push(SubMsg::new(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(reward.u128(), DENOM),
+            }));
+        }
+
+        let attributes = Some(vec![
+            ("processed_bid_items".to_string(), outcome.processed_bid_items.to_string()),
+            ("processed_bids".to_string(), outcome.processed_bids.to_string()),
+            ("crank_reward".to_string(), reward.to_string()),
+        ]);
+        let response = response("advance_crank", "Successfully advanced crank.", attributes)
+            .add_submessages(outcome.payouts)
+            .add_submessages(outcome.hooks)
+            .add_events(outcome.events);
+                
+        Ok(response)
+    }
+
+    /// Called by the chain, so there's no caller to reward and an empty queue isn't an error.
+    pub fn sudo_advance_crank(deps: DepsMut, env: Env, max_items: Option<u32>) -> Result<Response> {
+        let outcome = run_crank(deps, &env, max_items)?;
+
+        let attributes = Some(vec![
+            ("processed_bid_items".to_string(), outcome.processed_bid_items.to_string()),
+            ("processed_bids".to_string(), outcome.processed_bids.to_string()),
+        ]);
+        let response = response("sudo_advance_crank", "Successfully advanced crank.", attributes)
+            .add_submessages(outcome.payouts)
+            .add_submessages(outcome.hooks)
+            .add_events(outcome.events);
+
+        Ok(response)
+    }
+
+    /// Moves auctions past their end time to pending completion and then advances the crank.
+    pub fn close_expired_auctions(mut deps: DepsMut, env: Env) -> Result<Response> {
+        let config = CONFIG.load(deps.storage)?;
+
+        let expired: Vec<(u64, AuctionId)> = AUCTION_EXPIRIES
+            .keys(deps.storage, None, Some(Bound::inclusive((env.block.time.seconds(), AuctionId(u32::MAX)))), Order::Ascending)
+            .take(config.max_crank_batch_size as usize)
+            .collect::<StdResult<_>>()?;
+
+        let mut events: Vec<Event> = vec![];
+
+        for (ends_at, auction_id) in expired {
+            let mut auction = AUCTIONS.load(deps.storage, auction_id)?;
+
+            match auction.current_state {
+                AuctionStatus::Active => AUCTION_EXPIRIES.remove(deps.storage, (ends_at, auction_id)),
+                // Suspended auctions keep their entry, they're closed once they're active again
+                AuctionStatus::Suspended => continue,
+                // Cancelled or manually closed auctions can't become active again
+                AuctionStatus::PendingCompletion | AuctionStatus::Completed | AuctionStatus::Cancelled => {
+                    AUCTION_EXPIRIES.remove(deps.storage, (ends_at, auction_id));
+                    continue;
+                },
+            }
+
+            auction.current_state = AuctionStatus::PendingCompletion;
+            AUCTIONS.save(deps.storage, auction_id, &auction)?;
+            enqueue_for_crank(deps.branch(), auction_id)?;
+
+            events.push(AuctionStateChanged {
+                auction_id,
+                previous: AuctionStatus::Active,
+                next: AuctionStatus::PendingCompletion,
+            }.into());
+        }
+
+        let closed_auctions = events.len();
+        let outcome = run_crank(deps, &env, None)?;
+        events.extend(outcome.events);
+
+        let attributes = Some(vec![
+            ("closed_auctions".to_string(), closed_auctions.to_string()),
+            ("processed_bid_items".to_string(), outcome.processed_bid_items.to_string()),
+            ("processed_bids".to_string(), outcome.processed_bids.to_string()),
+        ]);
+        let response = response("close_expired_auctions", "Successfully closed expired auctions.", attributes)
+            .add_submessages(outcome.payouts)
+            .add_submessages(outcome.hooks)
+            .add_events(events);
+
+        Ok(response)
+    }
+
+    /// What a single run of the crank settled, the payouts still have to be sent.
+    struct CrankOutcome {
+        processed_bid_items: u32,
+        /// Processed items that had bids to settle or refund, only those earn crank rewards.
+        rewarded_bid_items: u32,
+        processed_bids: usize,
+        completed_auctions: usize,
+        payouts: Vec<SubMsg>,
+        events: Vec<Event>,
+        hooks: Vec<SubMsg>,
+    }
+
+    impl CrankOutcome {
+        fn is_empty(&self) -> bool {
+            self.processed_bid_items == 0 && self.processed_bids == 0 && self.completed_auctions == 0
+        }
+    }
+
+    /// Settles queued auctions up to the batch limits, crank fees go straight to the reward pool.
+    fn run_crank(mut deps: DepsMut, env: &Env, max_items: Option<u32>) -> Result<CrankOutcome> {
+        let config = CONFIG.load(deps.storage)?;
+        let max_items = max_items
+            .unwrap_or(config.crank_batch_size)
+            .min(config.max_crank_batch_size);
+        let mut bids_budget = config.crank_max_bids as usize;
+
+        let removed_bid_items = REMOVED_BID_ITEMS
+            .range(deps.storage, None, None, Order::Ascending)
+            .take(max_items as usize)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut processed_bid_items = 0;
+        let mut rewarded_bid_items = 0;
+        let mut processed_bids = 0;
+        let mut auctions_completed: Vec<AuctionId> = vec![];
+        let mut payouts: Vec<SubMsg> = vec![];
+        let mut events: Vec<Event> = vec![];
+        let mut crank_reward_fees = Uint128::zero();
+        let mut notifications: Vec<HookMsg> = vec![];
+        // The oracle is queried once per call, for the first item with a quote reserve price. When
+        // it fails every item that needs it is deferred with the same reason
+        let mut rate = None;
+        let mut oracle_failure: Option<String> = None;
+
+        // Bid items removed with bids go first, their bids are refunded and deleted chunk by chunk
+        let mut removals_done = true;
+
+        for (bid_item_id, auction_id) in removed_bid_items {
+            if processed_bid_items >= max_items {
+                removals_done = false;
+                break;
+            }
+
+            let auction = AUCTIONS.load(deps.storage, auction_id)?;
+            let key = BidItemKey { auction_id, bid_item_id };
+
+            let mut bids = get_bids_from_bid_item_id(&deps, bid_item_id, None, bids_budget + 1)?;
+            let all_bids_processed = bids.len() <= bids_budget;
+            bids.truncate(bids_budget);
+
+            let (refunds, refund_events, _) = process_bids(&mut deps, env, &auction, bid_item_id, None, &bids)?;
+            payouts.extend(refunds);
+            events.extend(refund_events);
+            delete_bids(deps.storage, key, &bids)?;
+
+            processed_bids += bids.len();
+            bids_budget -= bids.len();
+
+            if !all_bids_processed {
+                removals_done = false;
+                break;
+            }
+
+            events.push(delete_bid_item(deps.storage, key)?);
+            processed_bid_items += 1;
+            rewarded_bid_items += 1;
+        }
+
+        let auction_ids_to_process = match removals_done {
+            true => extract_auction_ids_to_process(&deps),
+            false => vec![],
+        };
+
+        'auctions: for auction_id in auction_ids_to_process {
+
+            let auction = AUCTIONS.load(deps.storage, auction_id)?;
+            let cancelled = auction.current_state == AuctionStatus::Cancelled;
+
+            // Bid items up to the cursor are already settled
+            let mut cursor = CRANK_CURSORS.may_load(deps.storage, auction_id)?;
+
+            loop {
+                let Some((bid_item_id, mut bid_item)) = next_pending_bid_item(&deps, auction_id, cursor)? else {
+                    // No pending items left, so the auction has been completed and it's removed below
+                    auctions_completed.push(auction_id);
+                    break;
+                };
+
+                if processed_bid_items >= max_items {
+                    break 'auctions;
+                }
+
+                // Bids are paid out in chunks so that items with lots of bids can span several calls
+                let bid_cursor = CRANK_BID_CURSORS.may_load(deps.storage, bid_item_id)?;
+                let mut bids = get_bids_from_bid_item_id(&deps, bid_item_id, bid_cursor, bids_budget + 1)?;
+                let all_bids_processed = bids.len() <= bids_budget;
+                bids.truncate(bids_budget);
+
+                let mut winning_bid = if cancelled {  // Cancelled auctions refund every bid and assign no winner
+                    None
+                } else {
+                    WINNING_BIDS.may_load(deps.storage, bid_item_id)?
+                };
+
+                // The reserve is checked on the first chunk only, so later chunks of the item can't
+                // see another oracle rate
+                if let (Some(winning_key), None, Some(reserve_price)) = (winning_bid, bid_cursor, bid_item.reserve_price) {
+                    let highest_bid = BIDS.load(deps.storage, winning_key)?.amount;
+                    let reserve_price = match (reserve_price, &oracle_failure) {
+                        (Price::Quote(_), Some(reason)) => Err(reason.clone()),
+                        _ => native_price(deps.as_ref(), env, reserve_price, &mut rate).map_err(|error| error.to_string()),
+                    };
+
+                    // Nothing of the item is settled yet, it's left for a later crank and the rest of
+                    // its auction waits behind it
+                    let reserve_price = match reserve_price {
+                        Ok(reserve_price) => reserve_price,
+                        Err(reason) => {
+                            events.push(SettlementDeferred {
+                                auction_id,
+                                bid_item_id,
+                                reason: reason.clone(),
+                            }.into());
+
+                            oracle_failure = Some(reason);
+                            continue 'auctions;
+                        }
+                    };
+
+                    if highest_bid < reserve_price {
+                        UNMET_RESERVES.save(deps.storage, bid_item_id, &())?;
+
+                        events.push(ReserveNotMet {
+                            auction_id,
+                            bid_item_id,
+                            highest_bid,
+                            reserve_price,
+                        }.into());
+                    }
+                }
+
+                // Items under their reserve aren't sold, every bid is refunded
+                if UNMET_RESERVES.has(deps.storage, bid_item_id) {
+                    winning_bid = None;
+                }
+
+                // Refund other bids & process Winning bid
+                let (bid_payouts, refund_events, fee) = process_bids(&mut deps, env, &auction, bid_item_id, winning_bid.map(|bid| bid.bid_id), &bids)?;
+                payouts.extend(bid_payouts);
+                events.extend(refund_events);
+                crank_reward_fees += fee;
+
+                processed_bids += bids.len();
+                bids_budget -= bids.len();
+
+                if !all_bids_processed {
+                    if let Some((last_bid_id, _)) = bids.last() {
+                        CRANK_BID_CURSORS.save(deps.storage, bid_item_id, last_bid_id)?;
+                    }
+
+                    break 'auctions;
+                }
+
+                CRANK_BID_CURSORS.remove(deps.storage, bid_item_id);
+                UNMET_RESERVES.remove(deps.storage, bid_item_id);
+
+                // Update Bid Item
+                let mut winning_amount = None;
+
+                if cancelled {
+                    bid_item.current_state = BidItemStatus::Cancelled;
+                } else {
+                    if let Some(winning_key) = winning_bid {
+                        let winning_bid = BIDS.load(deps.storage, winning_key)?;
+
+                        // Denylisted winners don't get the item, their bid is held in the denied escrow
+                        if !is_denied(deps.storage, &winning_bid.bidder) {
+                            notifications.push(HookMsg::ItemWon {
+                                auction_id,
+                                bid_item_id,
+                                bid_id: winning_key.bid_id,
+                                winner: winning_bid.bidder.clone(),
+                                amount: winning_bid.amount,
+                            });
+
+                            bid_item.winner = Some(winning_bid.bidder);
+                            winning_amount = Some(winning_bid.amount);
+                        }
+                    }
+
+                    bid_item.current_state = BidItemStatus::Completed;
+                }
+
+                events.push(ItemSettled {
+                    auction_id,
+                    bid_item_id,
+                    status: bid_item.current_state,
+                    winner: bid_item.winner.clone(),
+                    amount: winning_amount,
+                    denom: DENOM.to_string(),
+                }.into());
+
+                let key = BidItemKey {
+                    auction_id,
+                    bid_item_id,
+                };
+
+                BID_ITEMS.save(deps.storage, key, &bid_item)?;
+                CRANK_CURSORS.save(deps.storage, auction_id, &bid_item_id)?;
+
+                cursor = Some(bid_item_id);
+                processed_bid_items += 1;
+
+                // Items without bids cost next to nothing to crank, so they don't pay
+                if !bid_item.total_bids.is_zero() {
+                    rewarded_bid_items += 1;
+                }
+            }
+        }
+
+        let completed_auctions = auctions_completed.len();
+
+        // Removing Auctions from Crank queue
+        for auction_completed in auctions_completed {
+            AUCTIONS_CRANK_QUEUE.remove(deps.storage, auction_completed);
+            CRANK_CURSORS.remove(deps.storage, auction_completed);
+            let mut count = AUCTIONS_CRANK_QUEUE_COUNT.load(deps.storage).unwrap_or(0);
+            count = count.saturating_sub(1);
+
+            AUCTIONS_CRANK_QUEUE_COUNT.save(deps.storage, &count)?;
+
+            // Update Auction status as Complete, cancelled auctions keep their status
+            let mut auction = AUCTIONS.load(deps.storage, auction_completed)?;
+            if auction.current_state != AuctionStatus::Cancelled {
+                events.push(AuctionStateChanged {
+                    auction_id: auction_completed,
+                    previous: auction.current_state,
+                    next: AuctionStatus::Completed,
+                }.into());
+
+                auction.current_state = AuctionStatus::Completed;
+                AUCTIONS.save(deps.storage, auction_completed, &auction)?;
+
+                notifications.push(HookMsg::AuctionCompleted { auction_id: auction_completed });
+            }
+        }
+
+        let pool = CRANK_REWARD_POOL.may_load(deps.storage)?.unwrap_or_default();
+        CRANK_REWARD_POOL.save(deps.storage, &(pool + crank_reward_fees))?;
+
+        Ok(CrankOutcome {
+            processed_bid_items,
+            rewarded_bid_items,
+            processed_bids,
+            completed_auctions,
+            payouts,
+            events,
+            hooks: hook_messages(deps.as_ref(), &notifications)?,
+        })
+    }
+
+    fn extract_auction_ids_to_process (deps: &DepsMut<'_>) -> Vec<AuctionId> {
+        let mut auction_ids_to_process: Vec<AuctionId> = vec![];
+        let auctions_crank_queue = AUCTIONS_CRANK_QUEUE.range(deps.storage, None, None, Order::Ascending);
+    
+        for auction_id in auctions_crank_queue {
+            auction_ids_to_process.push(auction_id.unwrap().0);
+        }
+
+        auction_ids_to_process
+    }
+    
+    /// Returns the first active bid item of the auction after the crank cursor.
+    fn next_pending_bid_item(deps: &DepsMut, auction_id: AuctionId, cursor: Option<BidItemId>) -> Result<Option<(BidItemId, BidItem)>> {
+        let iter = BID_ITEMS
+            .prefix(auction_id)
+            .range(deps.storage, cursor.map(Bound::exclusive), None, Order::Ascending);
+
+        for bid_item in iter {
+            let (key, value) = bid_item?;
+            if value.current_state == BidItemStatus::Active {
+                return Ok(Some((key, value)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub fn get_bids_from_bid_item_id(deps: &DepsMut, bid_item_id: BidItemId, start_after: Option<BidId>, limit: usize) -> Result<Vec<(BidId, Bid)>> {
+        let iter = BIDS
+            .prefix(bid_item_id)
+            .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+            .take(limit);
+
+        let mut results: Vec<(BidId, Bid)> = Vec::new();
+
+        for bid in iter {
+            let (key, value) = bid?;
+            results.push((key, value));
+        }
+
+        Ok(results)
+    }
+
+    /// Builds the payouts for the bids of a bid item: the winning bid goes to the auction owner and every
+    /// other bid is refunded to its bidder. Passing no winning bid refunds all of them.
+    ///
+    /// Pull mode auctions credit the claimable balances instead, and losing bids that were drawn
+    /// from a deposit always go back to that deposit. Anything owed to a denylisted bidder goes to
+    /// the denied escrow, including its winning bids.
+    ///
+    /// Losing remote bids are refunded with a transfer back over the channel they came from
+    /// whatever the payout mode, their winning bids pay the owner like any other.
+    ///
+    /// Also returns a refund event per losing bid and the crank reward fee kept out of the winning bid.
+    pub fn process_bids(deps: &mut DepsMut, env: &Env, auction: &Auction, bid_item_id: BidItemId, winning_bid_id: Option<BidId>, bids: &[(BidId, Bid)]) -> Result<(Vec<SubMsg>, Vec<Event>, Uint128)> {
+        // Auctions of the first version are the admin's until they're reindexed
+        let seller = match auction.owner.as_str() {
+            "" => &ADMIN.load(deps.storage)?,
+            _ => &auction.owner,
+        };
+        let config = CONFIG.load(deps.storage)?;
+
+        let mut payouts: Vec<SubMsg> = vec![];
+        let mut events: Vec<Event> = vec![];
+        let mut fee = Uint128::zero();
+
+        for (bid_id, bid) in bids {
+            let is_refund = Some(*bid_id) != winning_bid_id;
+
+            // Settled bids no longer count towards the bidder caps
+            let stats_key = bid.bidder.to_string();
+            let mut stats = BIDDER_STATS.may_load(deps.storage, &stats_key)?.unwrap_or_default();
+            stats.active_bids = stats.active_bids.saturating_sub(1);
+            stats.escrowed = stats.escrowed.saturating_sub(bid.amount);
+
+            if stats.active_bids == 0 {
+                BIDDER_STATS.remove(deps.storage, &stats_key);
+            } else {
+                BIDDER_STATS.save(deps.storage, &stats_key, &stats)?;
+            }
+
+            // Winning bids of denylisted bidders are held back whole, the sale can't go through
+            let denied = is_denied(deps.storage, &bid.bidder);
+
+            let (recipient, amount) = match &bid.bidder {
+                Bidder::Remote(remote) if is_refund => {
+                    let settlement = RemoteSettlement {
+                        channel_id: remote.channel_id.clone(),
+                        receiver: remote.address.clone(),
+                        amount: Coin::new(bid.amount, DENOM),
+                    };
+
+                    payouts.push(ibc::refund_msg(deps.storage, env, *bid_id, settlement)?);
+
+                    events.push(RefundIssued {
+                        bid_item_id,
+                        bid_id: *bid_id,
+                        bidder: bid.bidder.clone(),
+                        amount: bid.amount,
+                        denom: DENOM.to_string(),
+                        destination: RefundDestination::Remote,
+                    }.into());
+
+                    continue;
+                },
+                Bidder::Local(bidder) if is_refund || denied => (bidder, bid.amount),
+                _ => {
+                    fee = bid.amount.multiply_ratio(config.crank_reward_fee_bps, MAX_BPS);
+                    (seller, bid.amount - fee)
+                },
+            };
 
-        let _ = bids.into_iter().map(|bid|
-            BankMsg::Send {
-                to_address: if bid.0 != winning_bid_id { bid.1.bidder.to_string() } else { curr_admin_str.clone() },
-                amount: coins(bid.1.amount.into(), DENOM)
+            if amount.is_zero() {
+                continue;
             }
-        );
 
-        Ok(())
+            let destination = if denied {
+                DENIED_ESCROW.update(deps.storage, recipient, |escrowed| -> StdResult<_> {
+                    Ok(escrowed.unwrap_or_default() + amount)
+                })?;
+
+                RefundDestination::Escrow
+            } else if bid.from_deposit && is_refund {
+                DEPOSITS.update(deps.storage, recipient, |balance| -> StdResult<_> {
+                    Ok(balance.unwrap_or_default() + amount)
+                })?;
+
+                RefundDestination::Deposit
+            } else {
+                match auction.payout_mode {
+                    PayoutMode::Push => {
+                        payouts.push(SubMsg::new(BankMsg::Send {
+                            to_address: recipient.to_string(),
+                            amount: coins(amount.u128(), DENOM),
+                        }));
+
+                        RefundDestination::Wallet
+                    },
+                    PayoutMode::Pull => {
+                        CLAIMABLE.update(deps.storage, (recipient, DENOM), |claimable| -> StdResult<_> {
+                            Ok(claimable.unwrap_or_default() + amount)
+                        })?;
+
+                        RefundDestination::Claimable
+                    },
+                }
+            };
+
+            if is_refund {
+                events.push(RefundIssued {
+                    bid_item_id,
+                    bid_id: *bid_id,
+                    bidder: bid.bidder.clone(),
+                    amount,
+                    denom: DENOM.to_string(),
+                    destination,
+                }.into());
+            } else if denied {
+                events.push(PayoutEscrowed {
+                    bid_item_id,
+                    bid_id: *bid_id,
+                    bidder: recipient.clone(),
+                    amount,
+                    denom: DENOM.to_string(),
+                }.into());
+            }
+        }
+
+        Ok((payouts, events, fee))
     }
 }
 
 mod query {
 
-    use cw_storage_plus::Bound;
+    use cw_storage_plus::{Bound, PrimaryKey};
 
-    use crate::state::{Auction, AuctionId, BidItem, BidItemId, BidItemKey, BID_ITEMS, BID_ITEMS_TO_AUCTIONS};
+    use crate::msg::{BidItemPricesResp, BidderBidResp, CrankStatusResp, PagedResp, QueuedAuctionResp, StateIssue, VerifyStateResp};
+    use crate::msg::{AuctionProgress, BidItemProgress, VerifyCursor, VerifyStep};
+    use crate::state::{Auction, AuctionId, AuctionStatus, Bid, BidId, BidItem, BidItemId, BidItemKey, BidItemStatus, BidKey, Config, AUCTIONS_CRANK_QUEUE_COUNT, BIDS, BID_ITEMS, BID_ITEMS_TO_AUCTIONS, CLAIMABLE, CONFIG, CRANK_BID_CURSORS, CRANK_CURSORS, DEPOSITS, CRANK_REWARD_POOL, WINNING_BIDS};
 
     use super::*;
 
@@ -465,10 +2195,15 @@ mod query {
         Ok(admin)
     }
 
+    pub fn config(deps: Deps) -> Result<Config> {
+        let config = CONFIG.load(deps.storage)?;
+        Ok(config)
+    }
+
     pub fn get_auction(deps: Deps, id: AuctionId) -> Result<Auction> {
-        Ok(AUCTIONS
+        AUCTIONS
             .may_load(deps.storage, id)?
-            .ok_or(ContractError::InvalidAuctionId)?)
+            .ok_or(ContractError::InvalidAuctionId)
     }
 
     pub fn get_bid_item_by_id(deps: Deps, id: BidItemId) -> Result<BidItem> {
@@ -477,76 +2212,730 @@ mod query {
             .ok_or(ContractError::InvalidBidItemId)?;
 
         let key = BidItemKey {
-            auction_id: auction_id,
+            auction_id,
             bid_item_id: id,
         };
 
-        Ok(BID_ITEMS
+        BID_ITEMS
             .may_load(deps.storage, key)?
-            .ok_or(ContractError::InvalidBidItemId)?)
+            .ok_or(ContractError::InvalidBidItemId)
     }
 
-    pub fn get_bid_items(deps: Deps, start_after: Option<BidItemKey>, limit: u32) -> Result<Vec<(BidItemKey, BidItem)>> {
-        let start = start_after.map(Bound::exclusive);
-        let limit = limit as usize;
+    pub fn get_bid_items(deps: Deps, status: Option<BidItemStatus>, page: Page<BidItemKey>) -> Result<PagedResp<BidItemKey, BidItem>> {
+        let (min, max) = page.bounds();
 
-        let iter = BID_ITEMS.range(deps.storage, start, None, Order::Ascending)
-            .take(limit);
+        match status {
+            Some(status) => {
+                let iter = BID_ITEMS
+                    .idx
+                    .status
+                    .prefix(status.as_str().to_string())
+                    .range(deps.storage, min, max, page.order);
 
-        let result: Vec<(BidItemKey, BidItem)> = iter
-            .map(|item| {
-                let (key, value) = item?;
-                Ok((key, value))
-            })
+                paginate(iter, page.limit)
+            },
+            None => {
+                let iter = BID_ITEMS.range(deps.storage, min, max, page.order);
+
+                paginate(iter, page.limit)
+            },
+        }
+    }
+
+    pub fn get_auctions(deps: Deps, status: Option<AuctionStatus>, page: Page<AuctionId>) -> Result<PagedResp<AuctionId, Auction>> {
+        let (min, max) = page.bounds();
+
+        match status {
+            Some(status) => {
+                let iter = AUCTIONS
+                    .idx
+                    .status
+                    .prefix(status.as_str().to_string())
+                    .range(deps.storage, min, max, page.order);
+
+                paginate(iter, page.limit)
+            },
+            None => {
+                let iter = AUCTIONS.range(deps.storage, min, max, page.order);
+
+                paginate(iter, page.limit)
+            },
+        }
+    }
+
+    pub fn get_bid_items_by_auction_id(deps: Deps, auction_id: AuctionId, page: Page<BidItemId>) -> Result<PagedResp<BidItemId, BidItem>> {
+        let (min, max) = page.bounds();
+
+        let iter = BID_ITEMS
+            .prefix(auction_id)
+            .range(deps.storage, min, max, page.order);
+
+        paginate(iter, page.limit)
+    }
+
+    pub fn get_bid_items_by_id(deps: Deps, bid_items_ids: Vec<BidItemId>, page: Page<BidItemId>) -> Result<PagedResp<BidItemId, BidItem>> {
+        // Ids are taken in the order they were given, up to and including the one in start_after are skipped
+        let skip = match page.start_after {
+            Some(start_after) => bid_items_ids.iter().position(|id| *id == start_after).map_or(0, |pos| pos + 1),
+            None => 0,
+        };
+
+        let mut bid_items: Vec<StdResult<(BidItemId, BidItem)>> = vec![];
+
+        for bid_item_id in bid_items_ids.into_iter().skip(skip).take(page.limit + 1) {
+            bid_items.push(Ok((bid_item_id, get_bid_item_by_id(deps, bid_item_id)?)));
+        }
+
+        paginate(bid_items.into_iter(), page.limit)
+    }
+
+    pub fn get_bids_by_bidder(deps: Deps, bidder: Bidder, page: Page<BidKey>) -> Result<PagedResp<BidKey, BidderBidResp>> {
+        let (min, max) = page.bounds();
+
+        let iter = BIDS
+            .idx
+            .bidder
+            .prefix(bidder.to_string())
+            .range(deps.storage, min, max, page.order)
+            .map(|bid| {
+                let (key, bid) = bid?;
+
+                let auction_id = BID_ITEMS_TO_AUCTIONS.load(deps.storage, key.bid_item_id)?;
+                let winning = WINNING_BIDS.may_load(deps.storage, key.bid_item_id)? == Some(key);
+
+                Ok((key, BidderBidResp {
+                    auction_id,
+                    bid_item_id: key.bid_item_id,
+                    bid_id: key.bid_id,
+                    amount: bid.amount,
+                    placed: bid.placed,
+                    winning,
+                }))
+            });
+
+        paginate(iter, page.limit)
+    }
+
+    pub fn get_bids_by_bid_item(deps: Deps, bid_item_id: BidItemId, page: Page<BidId>) -> Result<PagedResp<BidId, Bid>> {
+        if !BID_ITEMS_TO_AUCTIONS.has(deps.storage, bid_item_id) {
+            return Err(ContractError::InvalidBidItemId);
+        }
+
+        let (min, max) = page.bounds();
+
+        let iter = BIDS
+            .prefix(bid_item_id)
+            .range(deps.storage, min, max, page.order);
+
+        paginate(iter, page.limit)
+    }
+
+    pub fn get_winning_bid(deps: Deps, bid_item_id: BidItemId) -> Result<Option<(BidId, Bid)>> {
+        if !BID_ITEMS_TO_AUCTIONS.has(deps.storage, bid_item_id) {
+            return Err(ContractError::InvalidBidItemId);
+        }
+
+        let Some(key) = WINNING_BIDS.may_load(deps.storage, bid_item_id)? else {
+            return Ok(None);
+        };
+
+        let bid = BIDS.load(deps.storage, key)?;
+
+        Ok(Some((key.bid_id, bid)))
+    }
+
+    pub fn bid_item_prices(deps: Deps, env: Env, bid_item_id: BidItemId) -> Result<BidItemPricesResp> {
+        let auction_id = BID_ITEMS_TO_AUCTIONS
+            .may_load(deps.storage, bid_item_id)?
+            .ok_or(ContractError::InvalidBidItemId)?;
+
+        let bid_item = BID_ITEMS.load(deps.storage, BidItemKey { auction_id, bid_item_id })?;
+        let mut rate = None;
+
+        let starting_price = bid_item.starting_price.map(|price| native_price(deps, &env, price, &mut rate)).transpose()?;
+        let reserve_price = bid_item.reserve_price.map(|price| native_price(deps, &env, price, &mut rate)).transpose()?;
+
+        Ok(BidItemPricesResp { starting_price, reserve_price })
+    }
+
+    pub fn claimable(deps: Deps, address: Addr) -> Result<Vec<Coin>> {
+        let claimable = CLAIMABLE
+            .prefix(&address)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
             .collect::<StdResult<_>>()?;
 
-        Ok(result)
+        Ok(claimable)
     }
 
-    pub fn get_auctions(deps: Deps, start_after: Option<AuctionId>, limit: u32) -> Result<Vec<(AuctionId, Auction)>> {
-        let start = start_after.map(Bound::exclusive);
-        let limit = limit as usize;
+    pub fn deposit_balance(deps: Deps, address: Addr) -> Result<Uint128> {
+        Ok(DEPOSITS.may_load(deps.storage, &address)?.unwrap_or_default())
+    }
 
-        let iter = AUCTIONS.range(deps.storage, start, None, Order::Ascending)
-            .take(limit);
+    pub fn get_denylist(deps: Deps, page: Page<Addr>) -> Result<PagedResp<Addr, Timestamp>> {
+        let min = page.start_after.as_ref().map(Bound::exclusive);
+        let max = page.start_before.as_ref().map(Bound::exclusive);
+
+        let iter = DENYLIST.range(deps.storage, min, max, page.order);
+
+        paginate(iter, page.limit)
+    }
+
+    pub fn get_hooks(deps: Deps, page: Page<Addr>) -> Result<PagedResp<Addr, Timestamp>> {
+        let min = page.start_after.as_ref().map(Bound::exclusive);
+        let max = page.start_before.as_ref().map(Bound::exclusive);
+
+        let iter = HOOKS.range(deps.storage, min, max, page.order);
+
+        paginate(iter, page.limit)
+    }
+
+    pub fn get_transfer_channels(deps: Deps, page: Page<String>) -> Result<PagedResp<String, Timestamp>> {
+        let min = page.start_after.as_deref().map(Bound::exclusive);
+        let max = page.start_before.as_deref().map(Bound::exclusive);
+
+        let iter = TRANSFER_CHANNELS.range(deps.storage, min, max, page.order);
+
+        paginate(iter, page.limit)
+    }
+
+    pub fn get_undelivered_settlements(deps: Deps, page: Page<BidId>) -> Result<PagedResp<BidId, RemoteSettlement>> {
+        let (min, max) = page.bounds();
+
+        let iter = UNDELIVERED_SETTLEMENTS.range(deps.storage, min, max, page.order);
+
+        paginate(iter, page.limit)
+    }
+
+    pub fn verify_state(deps: Deps, env: Env, auction_id: Option<AuctionId>, start_after: Option<VerifyCursor>, limit: Option<u32>) -> Result<VerifyStateResp> {
+        // Every entry read counts against the limit, so a single page never walks an unbounded map
+        let mut budget = Page::<AuctionId>::new(deps, None, None, limit, None)?.limit;
+        let mut issues: Vec<StateIssue> = vec![];
+        let mut checked_auctions: u64 = 0;
+
+        let mut cursor = match start_after {
+            Some(cursor) => cursor,
+            None => {
+                let first = match auction_id {
+                    Some(auction_id) => Some(auction_id),
+                    None => AUCTIONS.keys(deps.storage, None, None, Order::Ascending).next().transpose()?,
+                };
+
+                VerifyCursor {
+                    step: first.map_or(VerifyStep::BidItemLookups { start_after: None }, |auction_id| VerifyStep::Auction(AuctionProgress::new(auction_id))),
+                    unsettled: Uint128::zero(),
+                    escrowed: Uint128::zero(),
+                }
+            },
+        };
+
+        loop {
+            if budget == 0 {
+                return Ok(VerifyStateResp { checked_auctions, issues, next: Some(cursor) });
+            }
+
+            let step = match cursor.step {
+                VerifyStep::Auction(mut progress) => {
+                    if !verify_auction(deps, &mut progress, &mut cursor.unsettled, &mut budget, &mut issues)? {
+                        Some(VerifyStep::Auction(progress))
+                    } else {
+                        checked_auctions += 1;
+
+                        match auction_id {
+                            Some(_) => None,
+                            None => {
+                                let next = AUCTIONS.keys(deps.storage, Some(Bound::exclusive(progress.auction_id)), None, Order::Ascending).next().transpose()?;
+                                Some(next.map_or(VerifyStep::BidItemLookups { start_after: None }, |auction_id| VerifyStep::Auction(AuctionProgress::new(auction_id))))
+                            },
+                        }
+                    }
+                },
+                VerifyStep::BidItemLookups { mut start_after } => {
+                    let entries = BID_ITEMS_TO_AUCTIONS.range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending);
+                    let done = walk_map(entries, &mut budget, |bid_item_id, auction_id| {
+                        if !BID_ITEMS.has(deps.storage, BidItemKey { auction_id, bid_item_id }) {
+                            issues.push(StateIssue::OrphanedBidItem { bid_item_id, auction_id: Some(auction_id) });
+                        }
+                        start_after = Some(bid_item_id);
+                        Ok(())
+                    })?;
+
+                    Some(match done {
+                        true => VerifyStep::CrankQueue { start_after: None, queued: 0 },
+                        false => VerifyStep::BidItemLookups { start_after },
+                    })
+                },
+                VerifyStep::CrankQueue { mut start_after, mut queued } => {
+                    let entries = AUCTIONS_CRANK_QUEUE.range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending);
+                    let done = walk_map(entries, &mut budget, |auction_id, _| {
+                        queued += 1;
+                        start_after = Some(auction_id);
+                        Ok(())
+                    })?;
+
+                    Some(match done {
+                        true => {
+                            let queue_count = AUCTIONS_CRANK_QUEUE_COUNT.may_load(deps.storage)?.unwrap_or(0);
+                            issues.extend(counter_mismatch("auctions_crank_queue_count", None, None, queue_count.into(), queued.into()));
+                            VerifyStep::Deposits { start_after: None }
+                        },
+                        false => VerifyStep::CrankQueue { start_after, queued },
+                    })
+                },
+                VerifyStep::Deposits { mut start_after } => {
+                    let entries = DEPOSITS.range(deps.storage, start_after.as_ref().map(Bound::exclusive), None, Order::Ascending);
+                    let done = walk_map(entries, &mut budget, |addr, amount| {
+                        cursor.escrowed += amount;
+                        start_after = Some(addr);
+                        Ok(())
+                    })?;
+
+                    Some(match done {
+                        true => VerifyStep::DeniedEscrow { start_after: None },
+                        false => VerifyStep::Deposits { start_after },
+                    })
+                },
+                VerifyStep::DeniedEscrow { mut start_after } => {
+                    let entries = DENIED_ESCROW.range(deps.storage, start_after.as_ref().map(Bound::exclusive), None, Order::Ascending);
+                    let done = walk_map(entries, &mut budget, |addr, amount| {
+                        cursor.escrowed += amount;
+                        start_after = Some(addr);
+                        Ok(())
+                    })?;
+
+                    Some(match done {
+                        true => VerifyStep::Claimable { start_after: None },
+                        false => VerifyStep::DeniedEscrow { start_after },
+                    })
+                },
+                VerifyStep::Claimable { mut start_after } => {
+                    let min = start_after.as_ref().map(|(addr, denom)| Bound::exclusive((addr, denom.as_str())));
+                    let entries = CLAIMABLE.range(deps.storage, min, None, Order::Ascending);
+                    let done = walk_map(entries, &mut budget, |(addr, denom), amount| {
+                        if denom == DENOM {
+                            cursor.escrowed += amount;
+                        }
+                        start_after = Some((addr, denom));
+                        Ok(())
+                    })?;
+
+                    Some(match done {
+                        true => VerifyStep::UndeliveredSettlements { start_after: None },
+                        false => VerifyStep::Claimable { start_after },
+                    })
+                },
+                VerifyStep::UndeliveredSettlements { mut start_after } => {
+                    // Undelivered refunds came back to the contract, pending ones are outstanding on their channel
+                    let entries = UNDELIVERED_SETTLEMENTS.range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending);
+                    let done = walk_map(entries, &mut budget, |bid_id, settlement| {
+                        if settlement.amount.denom == DENOM {
+                            cursor.escrowed += settlement.amount.amount;
+                        }
+                        start_after = Some(bid_id);
+                        Ok(())
+                    })?;
+
+                    Some(match done {
+                        true => VerifyStep::IbcChannels { start_after: None },
+                        false => VerifyStep::UndeliveredSettlements { start_after },
+                    })
+                },
+                VerifyStep::IbcChannels { mut start_after } => {
+                    // What's outstanding on a channel never left the contract's balance
+                    let entries = IBC_CHANNELS.range(deps.storage, start_after.as_deref().map(Bound::exclusive), None, Order::Ascending);
+                    let done = walk_map(entries, &mut budget, |channel_id, channel| {
+                        cursor.escrowed += channel.outstanding;
+                        start_after = Some(channel_id);
+                        Ok(())
+                    })?;
+
+                    match done {
+                        true => {
+                            verify_escrow(deps, &env, cursor.unsettled + cursor.escrowed, &mut issues)?;
+                            None
+                        },
+                        false => Some(VerifyStep::IbcChannels { start_after }),
+                    }
+                },
+            };
+
+            match step {
+                Some(step) => cursor.step = step,
+                None => return Ok(VerifyStateResp { checked_auctions, issues, next: None }),
+            }
+        }
+    }
+
+    /// Visits entries until the budget runs out. Returns whether the end of the map was reached.
+    fn walk_map<K, V>(entries: impl Iterator<Item = StdResult<(K, V)>>, budget: &mut usize, mut visit: impl FnMut(K, V) -> Result<()>) -> Result<bool> {
+        for entry in entries {
+            if *budget == 0 {
+                return Ok(false);
+            }
+
+            let (key, value) = entry?;
+            *budget -= 1;
+            visit(key, value)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Checks the bid items, bids and counters of an auction as far as the budget goes, adding the
+    /// bids it still holds to `unsettled`. Returns whether the whole auction was checked.
+    fn verify_auction(deps: Deps, progress: &mut AuctionProgress, unsettled: &mut Uint128, budget: &mut usize, issues: &mut Vec<StateIssue>) -> Result<bool> {
+        let auction_id = progress.auction_id;
+
+        loop {
+            if let Some(item) = progress.bid_item.as_mut() {
+                if !verify_bids(deps, auction_id, item, unsettled, budget)? {
+                    return Ok(false);
+                }
+
+                verify_bid_item(deps, auction_id, item, issues)?;
+
+                progress.bid_items += 1;
+                progress.bids += item.bids;
+                progress.coins += item.coins;
+                progress.last_bid_item = Some(item.bid_item_id);
+                progress.bid_item = None;
+            }
+
+            if *budget == 0 {
+                return Ok(false);
+            }
+
+            let next = BID_ITEMS
+                .prefix(auction_id)
+                .keys(deps.storage, progress.last_bid_item.map(Bound::exclusive), None, Order::Ascending)
+                .next()
+                .transpose()?;
+
+            let Some(bid_item_id) = next else {
+                break;
+            };
+
+            *budget -= 1;
+
+            if BID_ITEMS_TO_AUCTIONS.may_load(deps.storage, bid_item_id)? != Some(auction_id) {
+                issues.push(StateIssue::OrphanedBidItem { bid_item_id, auction_id: Some(auction_id) });
+            }
+
+            progress.bid_item = Some(BidItemProgress::new(bid_item_id));
+        }
+
+        let auction = AUCTIONS.load(deps.storage, auction_id)?;
 
-        let result: Vec<(AuctionId, Auction)> = iter
-            .map(|item| {
-                let (key, value) = item?;
-                Ok((key, value))
+        issues.extend(counter_mismatch("auction.available_bid_items", Some(auction_id), None, auction.available_bid_items.u64().into(), progress.bid_items.into()));
+        issues.extend(counter_mismatch("auction.total_bids", Some(auction_id), None, auction.total_bids.u64().into(), progress.bids.into()));
+        issues.extend(counter_mismatch("auction.total_coins", Some(auction_id), None, auction.total_coins.u128(), progress.coins.u128()));
+
+        Ok(true)
+    }
+
+    /// Sums the bids of a bid item as far as the budget goes. Returns whether every bid was read.
+    fn verify_bids(deps: Deps, auction_id: AuctionId, item: &mut BidItemProgress, unsettled: &mut Uint128, budget: &mut usize) -> Result<bool> {
+        let bid_item = BID_ITEMS.load(deps.storage, BidItemKey { auction_id, bid_item_id: item.bid_item_id })?;
+
+        // Bids up to the crank cursor of a pending item have already been paid out, removed items
+        // only keep the bids the crank hasn't refunded yet
+        let bid_cursor = CRANK_BID_CURSORS.may_load(deps.storage, item.bid_item_id)?;
+        let removed = REMOVED_BID_ITEMS.has(deps.storage, item.bid_item_id);
+
+        let entries = BIDS.prefix(item.bid_item_id).range(deps.storage, item.start_after.map(Bound::exclusive), None, Order::Ascending);
+
+        walk_map(entries, budget, |bid_id, bid| {
+            item.bids += 1;
+            item.coins += bid.amount;
+
+            if item.highest.is_none_or(|(_, amount)| bid.amount > amount) {
+                item.highest = Some((bid_id, bid.amount));
+            }
+
+            if removed || (bid_item.current_state == BidItemStatus::Active && bid_cursor.is_none_or(|cursor| bid_id > cursor)) {
+                *unsettled += bid.amount;
+            }
+
+            item.start_after = Some(bid_id);
+            Ok(())
+        })
+    }
+
+    /// Checks the winning bid and the counters of a bid item once all its bids have been read.
+    fn verify_bid_item(deps: Deps, auction_id: AuctionId, item: &BidItemProgress, issues: &mut Vec<StateIssue>) -> Result<()> {
+        let bid_item_id = item.bid_item_id;
+        let bid_item = BID_ITEMS.load(deps.storage, BidItemKey { auction_id, bid_item_id })?;
+
+        let winning_bid = WINNING_BIDS.may_load(deps.storage, bid_item_id)?;
+        let winning_amount = match winning_bid {
+            Some(key) if key.bid_item_id == bid_item_id => BIDS.may_load(deps.storage, key)?.map(|bid| bid.amount),
+            _ => None,
+        };
+
+        // The winning bid of a removed item goes with the chunk of bids it's refunded in
+        if winning_amount != item.highest.map(|(_, amount)| amount) && !REMOVED_BID_ITEMS.has(deps.storage, bid_item_id) {
+            issues.push(StateIssue::WinningBidNotMaximum {
+                bid_item_id,
+                winning_bid: winning_bid.map(|key| key.bid_id),
+                highest_bid: item.highest.map(|(bid_id, _)| bid_id),
+            });
+        }
+
+        issues.extend(counter_mismatch("bid_item.total_bids", Some(auction_id), Some(bid_item_id), bid_item.total_bids.u64().into(), item.bids.into()));
+        issues.extend(counter_mismatch("bid_item.total_coins", Some(auction_id), Some(bid_item_id), bid_item.total_coins.u128(), item.coins.u128()));
+
+        Ok(())
+    }
+
+    /// Compares everything the walk found in escrow to the bank balance.
+    fn verify_escrow(deps: Deps, env: &Env, escrowed: Uint128, issues: &mut Vec<StateIssue>) -> Result<()> {
+        let escrowed = escrowed + CRANK_REWARD_POOL.may_load(deps.storage)?.unwrap_or_default();
+        let balance = deps.querier.query_balance(&env.contract.address, DENOM)?.amount;
+
+        if escrowed != balance {
+            issues.push(StateIssue::EscrowMismatch { escrowed, balance });
+        }
+
+        Ok(())
+    }
+
+    fn counter_mismatch(counter: &str, auction_id: Option<AuctionId>, bid_item_id: Option<BidItemId>, stored: u128, actual: u128) -> Option<StateIssue> {
+        (stored != actual).then(|| StateIssue::CounterMismatch {
+            counter: counter.to_string(),
+            auction_id,
+            bid_item_id,
+            stored: stored.into(),
+            actual: actual.into(),
+        })
+    }
+
+    /// The queue length comes from its counter, the queued auctions are paged like other lists and
+    /// the estimate only covers the auctions of the page.
+    pub fn crank_status(deps: Deps, start_after: Option<AuctionId>, limit: Option<u32>) -> Result<CrankStatusResp> {
+        let queue_length = AUCTIONS_CRANK_QUEUE_COUNT.may_load(deps.storage)?.unwrap_or(0);
+
+        let page = Page::new(deps, start_after, None, limit, None)?;
+        let (min, max) = page.bounds();
+        let auction_ids = AUCTIONS_CRANK_QUEUE
+            .range(deps.storage, min, max, page.order)
+            .map(|entry| entry.map(|(auction_id, _)| (auction_id, ())));
+        let PagedResp { items, next } = paginate(auction_ids, page.limit)?;
+
+        let mut queued_auctions: Vec<QueuedAuctionResp> = vec![];
+        let mut total_pending_bid_items: u64 = 0;
+
+        for (auction_id, _) in items {
+            let mut pending_bid_items: u64 = 0;
+            let cursor = CRANK_CURSORS.may_load(deps.storage, auction_id)?.map(Bound::exclusive);
+
+            for bid_item in BID_ITEMS.prefix(auction_id).range(deps.storage, cursor, None, Order::Ascending) {
+                if bid_item?.1.current_state == BidItemStatus::Active {
+                    pending_bid_items += 1;
+                }
+            }
+
+            total_pending_bid_items += pending_bid_items;
+            queued_auctions.push(QueuedAuctionResp { auction_id, pending_bid_items });
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+
+        // Each call settles up to crank_batch_size items, auctions without pending items still
+        // need one call to be marked as completed. Items with more than crank_max_bids bids take
+        // extra calls, so this is a lower bound.
+        let mut estimated_crank_calls = total_pending_bid_items.div_ceil(config.crank_batch_size as u64);
+
+        if estimated_crank_calls == 0 && !queued_auctions.is_empty() {
+            estimated_crank_calls = 1;
+        }
+
+        Ok(CrankStatusResp {
+            queue_length,
+            queued_auctions,
+            next,
+            estimated_crank_calls,
+            reward_per_bid_item: config.crank_reward,
+            reward_pool: CRANK_REWARD_POOL.may_load(deps.storage)?.unwrap_or_default(),
+        })
+    }
+
+    /// Pagination parameters of a list query, with the limit already capped by the config.
+    pub struct Page<K> {
+        pub start_after: Option<K>,
+        pub start_before: Option<K>,
+        pub limit: usize,
+        pub order: Order,
+    }
+
+    impl<'a, K: PrimaryKey<'a> + Clone> Page<K> {
+        pub fn new(deps: Deps, start_after: Option<K>, start_before: Option<K>, limit: Option<u32>, order: Option<Order>) -> Result<Self> {
+            let config = CONFIG.load(deps.storage)?;
+            let limit = limit.unwrap_or(config.default_page_limit).min(config.max_page_limit) as usize;
+
+            Ok(Self {
+                start_after,
+                start_before,
+                limit,
+                order: order.unwrap_or(Order::Ascending),
             })
+        }
+
+        fn bounds(&self) -> (Option<Bound<'a, K>>, Option<Bound<'a, K>>) {
+            (self.start_after.clone().map(Bound::exclusive), self.start_before.clone().map(Bound::exclusive))
+        }
+    }
+
+    /// Collects up to `limit` records. The next cursor is the last returned key when more records
+    /// are left, it goes into `start_after` for ascending pages and into `start_before` otherwise.
+    fn paginate<K: Clone, T>(iter: impl Iterator<Item = StdResult<(K, T)>>, limit: usize) -> Result<PagedResp<K, T>> {
+        let mut items: Vec<(K, T)> = iter
+            .take(limit + 1)
             .collect::<StdResult<_>>()?;
 
-        Ok(result)
+        let next = if items.len() > limit {
+            items.truncate(limit);
+            items.last().map(|item| item.0.clone())
+        } else {
+            None
+        };
+
+        Ok(PagedResp { items, next })
     }
+}
 
-    pub fn get_bid_items_by_auction_id(deps: Deps, auction_id: AuctionId) -> Result<Vec<(BidItemId, BidItem)>> {
-        let iter: Box<dyn Iterator<Item = std::result::Result<(BidItemId, BidItem), cosmwasm_std::StdError>>> = BID_ITEMS
-            .prefix(auction_id)
-            .range(deps.storage, None, None, Order::Ascending);
+fn validate_bid_item(bid_item: &BidItemInput) -> Result<()> {
+    validate_length("bid item name", &bid_item.name, MAX_NAME_LENGTH)?;
+    validate_optional_length("bid item description", &bid_item.description, MAX_DESCRIPTION_LENGTH)?;
+    validate_optional_length("bid item category", &bid_item.category, MAX_CATEGORY_LENGTH)?;
+
+    if bid_item.media_uris.len() > MAX_MEDIA_URIS {
+        return Err(ContractError::InvalidMetadata { msg: format!(
+            "a bid item can have at most {} media uris",
+            MAX_MEDIA_URIS)
+        });
+    }
 
-        let mut results: Vec<(BidItemId, BidItem)> = Vec::new();
+    for uri in &bid_item.media_uris {
+        validate_length("media uri", uri, MAX_URI_LENGTH)?;
+    }
 
-        for bid_item in iter {
-            let (key, value) = bid_item?;
-            results.push((key, value));
-        }
+    if bid_item.attributes.len() > MAX_ATTRIBUTES {
+        return Err(ContractError::InvalidMetadata { msg: format!(
+            "a bid item can have at most {} attributes",
+            MAX_ATTRIBUTES)
+        });
+    }
 
-        Ok(results)
+    for attribute in &bid_item.attributes {
+        validate_length("attribute key", &attribute.key, MAX_ATTRIBUTE_LENGTH)?;
+        validate_length("attribute value", &attribute.value, MAX_ATTRIBUTE_LENGTH)?;
+    }
+
+    Ok(())
+}
+
+/// Quote prices can only be set once there's an oracle to convert them.
+fn validate_prices(deps: Deps, bid_item: &BidItemInput) -> Result<()> {
+    let quoted = [bid_item.starting_price, bid_item.reserve_price]
+        .iter()
+        .any(|price| matches!(price, Some(Price::Quote(_))));
+
+    if quoted && CONFIG.load(deps.storage)?.oracle.is_none() {
+        return Err(ContractError::OracleNotConfigured);
+    }
+
+    Ok(())
+}
+
+fn validate_length(field: &str, value: &str, max: usize) -> Result<()> {
+    if value.trim().is_empty() {
+        return Err(ContractError::InvalidMetadata { msg: format!("{field} can't be empty") });
+    }
+
+    if value.len() > max {
+        return Err(ContractError::InvalidMetadata { msg: format!(
+            "{} is longer than {} bytes",
+            field, max)
+        });
+    }
+
+    Ok(())
+}
+
+fn validate_optional_length(field: &str, value: &Option<String>, max: usize) -> Result<()> {
+    match value {
+        Some(value) => validate_length(field, value, max),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn assert_not_paused(deps: Deps, scope: PauseScope) -> Result<()> {
+    if PAUSED.may_load(deps.storage)?.unwrap_or_default().contains(&scope) {
+        return Err(ContractError::Paused { scope: scope.as_str().to_string() });
+    }
+
+    Ok(())
+}
+
+/// Callbacks to every registered hook. They only reply on error, so a failing subscriber can't
+/// revert the bid or the crank that notified it.
+fn hook_messages(deps: Deps, notifications: &[HookMsg]) -> Result<Vec<SubMsg>> {
+    if notifications.is_empty() {
+        return Ok(vec![]);
     }
 
-    pub fn get_bid_items_by_id(deps: Deps, bid_items_ids: Vec<BidItemId>) -> Result<Vec<(BidItemId, BidItem)>> {
-        let mut results: Vec<(BidItemId, BidItem)> = vec![];
+    let hooks: Vec<Addr> = HOOKS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut messages: Vec<SubMsg> = vec![];
+
+    for notification in notifications {
+        let msg = to_json_binary(&HookExecuteMsg::BiddingHook(notification.clone()))?;
+
+        for hook in &hooks {
+            let execute = WasmMsg::Execute {
+                contract_addr: hook.to_string(),
+                msg: msg.clone(),
+                funds: vec![],
+            };
 
-        for bid_item_id in bid_items_ids {
+            // Out of gas can't be caught by the reply unless the hook has its own limit, without
+            // one a hook that never returns would fail the bid or the crank with it
+            let submsg = SubMsg::reply_on_error(execute, HOOK_REPLY_ID)
+                .with_gas_limit(config.hook_gas_limit)
+                .with_payload(to_json_binary(hook)?);
 
-            let bid_item = get_bid_item_by_id(deps, bid_item_id)?;
-            results.push((bid_item_id, bid_item));
+            messages.push(submsg);
         }
+    }
 
-        Ok(results)
+    Ok(messages)
+}
+
+fn assert_not_denied(deps: Deps, address: &Addr) -> Result<()> {
+    if DENYLIST.has(deps.storage, address) {
+        return Err(ContractError::AddressDenied { address: address.clone() });
     }
 
+    Ok(())
+}
+
+/// Only local bidders can be denylisted.
+fn is_denied(storage: &dyn Storage, bidder: &Bidder) -> bool {
+    bidder.local().is_some_and(|address| DENYLIST.has(storage, address))
+}
+
+fn is_bidder_allowed(deps: Deps, auction_id: AuctionId, access: &BidderAccess, bidder: &Addr) -> Result<bool> {
+    match access {
+        BidderAccess::Open => Ok(true),
+        BidderAccess::Allowlist => Ok(ALLOWLISTS.has(deps.storage, (auction_id, bidder))),
+        BidderAccess::Verifier { contract } => {
+            let msg = VerifierQueryMsg::IsAllowed { auction_id, bidder: bidder.clone() };
+            Ok(deps.querier.query_wasm_smart(contract, &msg)?)
+        },
+    }
 }
 
 fn assert_sent_amount(info: &MessageInfo) -> Result<Uint128> {
@@ -565,19 +2954,63 @@ fn get_sent_amount(info: &MessageInfo) -> Result<Option<Uint128>> {
     if info.funds.len() != 1 {
         return Err(ContractError::UnexpectedAssetsReceived { msg: format!(
             "Expecting to receive only {}, but got {} assets.",
-            DENOM.to_string(),
+            DENOM,
             info.funds.len()) 
         });
     }
 
     let coin = &info.funds[0];
 
-    if coin.denom != DENOM.to_string() {
+    if coin.denom != DENOM {
         return Err(ContractError::UnexpectedAssetsReceived { msg: format!(
             "Expecting denom: {}, found: {}",
-            DENOM.to_string(), coin.denom) 
+            DENOM, coin.denom) 
         });
     }
 
     Ok(Some(coin.amount))
-}
\ No newline at end of file
+}
+
+/// A price in the contract denom, quote prices are converted at the oracle rate rounding up, so a
+/// bid at the converted price is never worth less than the quote price. The rate is cached in `rate`.
+fn native_price(deps: Deps, env: &Env, price: Price, rate: &mut Option<Decimal>) -> Result<Uint128> {
+    let amount = match price {
+        Price::Native(amount) => return Ok(amount),
+        Price::Quote(amount) => amount,
+    };
+
+    let rate = match rate {
+        Some(rate) => *rate,
+        None => *rate.insert(oracle_rate(deps, env)?),
+    };
+
+    amount
+        .checked_div_ceil(rate)
+        .map_err(|_| ContractError::InvalidOraclePrice { price: rate })
+}
+
+/// Units of the quote asset per unit of the contract denom, rejected when the oracle price is stale
+/// or too far from its average.
+fn oracle_rate(deps: Deps, env: &Env) -> Result<Decimal> {
+    let oracle = CONFIG
+        .load(deps.storage)?
+        .oracle
+        .ok_or(ContractError::OracleNotConfigured)?;
+
+    let msg = OracleQueryMsg::Price { base: DENOM.to_string(), quote: oracle.quote };
+    let OraclePriceResp { price, twap, updated_at } = deps.querier.query_wasm_smart(oracle.contract, &msg)?;
+
+    if env.block.time.seconds().saturating_sub(updated_at.seconds()) > oracle.max_staleness {
+        return Err(ContractError::StaleOraclePrice { updated_at });
+    }
+
+    if let Some(invalid) = [price, twap].into_iter().find(Decimal::is_zero) {
+        return Err(ContractError::InvalidOraclePrice { price: invalid });
+    }
+
+    if price.abs_diff(twap) > twap * Decimal::bps(oracle.max_deviation_bps.into()) {
+        return Err(ContractError::OraclePriceDeviation { price, twap });
+    }
+
+    Ok(price)
+}
@@ -1,6 +1,8 @@
-use cosmwasm_std::{Addr, StdError};
+use cosmwasm_std::{Addr, Decimal, StdError, Timestamp, Uint128};
 use cw_utils::PaymentError;
 
+use crate::state::{BidId, Bidder};
+
 #[derive(thiserror::Error, PartialEq, Debug)]
 pub enum ContractError {
     #[error("{0}")]
@@ -15,12 +17,74 @@ pub enum ContractError {
     InvalidBidItemId,
     #[error("The auction is in a non-updateable state.")]
     AuctionNonUpdateable,
+    #[error("The bid item already has bids, it can only be changed while the auction is suspended.")]
+    BidItemNonUpdateable,
+    #[error("The prices of a bid item can't change once it has bids.")]
+    BidItemPricesLocked,
     #[error("The auction is already completed and can't accept bids.")]
     AuctionCompleted,
+    #[error("The auction has ended and can't accept bids.")]
+    AuctionExpired,
+    #[error("The auction end time must be in the future.")]
+    InvalidEndTime,
     #[error("{msg}")]
     AuctionInvalidStateUpdate { msg: String },
     #[error("Expecting to receive {denom}.")]
     NoFundsReceived{ denom: String },
     #[error("{msg}.")]
     UnexpectedAssetsReceived{ msg: String },
+    #[error("There is nothing to crank.")]
+    NothingToCrank,
+    #[error("{scope} is paused.")]
+    Paused { scope: String },
+    #[error("There is nothing to reindex.")]
+    NothingToReindex,
+    #[error("There is nothing to claim.")]
+    NothingToClaim,
+    #[error("Not enough deposited funds, {available} available.")]
+    InsufficientDeposit { available: Uint128 },
+    #[error("{bidder} is not allowed to bid on this auction.")]
+    BidderNotAllowed { bidder: Bidder },
+    #[error("{address} is denylisted.")]
+    AddressDenied { address: Addr },
+    #[error("Nothing is escrowed for {address}.")]
+    NothingEscrowed { address: Addr },
+    #[error("A bidder can have at most {max} active bids.")]
+    TooManyActiveBids { max: u32 },
+    #[error("A bidder can have at most {max} in active bids.")]
+    EscrowCapExceeded { max: Uint128 },
+    #[error("{contract} is already a hook.")]
+    HookAlreadyRegistered { contract: Addr },
+    #[error("{contract} is not a hook.")]
+    HookNotRegistered { contract: Addr },
+    #[error("There can be at most {max} hooks.")]
+    TooManyHooks { max: u32 },
+    #[error("Remote bids aren't taken from channel {channel_id}.")]
+    UnknownTransferChannel { channel_id: String },
+    #[error("Channel {channel_id} already takes remote bids.")]
+    TransferChannelExists { channel_id: String },
+    #[error("Channel {channel_id} isn't an open ICS-20 channel of the contract.")]
+    ChannelNotOpen { channel_id: String },
+    #[error("Invalid channel: {msg}.")]
+    InvalidChannel { msg: String },
+    #[error("Invalid transfer: {msg}.")]
+    InvalidTransfer { msg: String },
+    #[error("Bid {bid_id} has no undelivered settlement.")]
+    NoUndeliveredSettlement { bid_id: BidId },
+    #[error("Bids must be at least {starting_price}.")]
+    BidBelowStartingPrice { starting_price: Uint128 },
+    #[error("No price oracle is configured.")]
+    OracleNotConfigured,
+    #[error("The oracle price from {updated_at} is stale.")]
+    StaleOraclePrice { updated_at: Timestamp },
+    #[error("The oracle price {price} deviates too far from its average {twap}.")]
+    OraclePriceDeviation { price: Decimal, twap: Decimal },
+    #[error("Invalid oracle price {price}.")]
+    InvalidOraclePrice { price: Decimal },
+    #[error("Unknown reply id {id}.")]
+    UnknownReplyId { id: u64 },
+    #[error("Invalid config: {msg}.")]
+    InvalidConfig { msg: String },
+    #[error("Invalid metadata: {msg}.")]
+    InvalidMetadata { msg: String },
 }
@@ -0,0 +1,385 @@
+use cosmwasm_std::{to_json_string, Addr, Event, Timestamp, Uint128};
+use serde::Serialize;
+
+use crate::msg::BidItemInput;
+use crate::state::{AuctionId, AuctionStatus, BidId, BidItemId, BidItemStatus, Bidder, PayoutMode};
+
+// Typed events emitted next to the `action`/`response` attributes so indexers don't have to parse
+// the human-readable response. Wasm prefixes their type with `wasm-` on chain.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuctionCreated {
+    pub auction_id: AuctionId,
+    pub name: String,
+    pub description: Option<String>,
+    pub terms: Option<String>,
+    pub owner: Addr,
+    pub bid_items: u64,
+    pub ends_at: Option<Timestamp>,
+    pub payout_mode: PayoutMode,
+}
+
+impl From<AuctionCreated> for Event {
+    fn from(event: AuctionCreated) -> Self {
+        let mut ev = Event::new("auction_created")
+            .add_attribute("auction_id", event.auction_id.to_string())
+            .add_attribute("name", event.name)
+            .add_attribute("owner", event.owner)
+            .add_attribute("bid_items", event.bid_items.to_string())
+            .add_attribute("payout_mode", event.payout_mode.as_str());
+
+        if let Some(description) = event.description {
+            ev = ev.add_attribute("description", description);
+        }
+
+        if let Some(terms) = event.terms {
+            ev = ev.add_attribute("terms", terms);
+        }
+
+        if let Some(ends_at) = event.ends_at {
+            ev = ev.add_attribute("ends_at", ends_at.seconds().to_string());
+        }
+
+        ev
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BidItemAdded {
+    pub auction_id: AuctionId,
+    pub bid_item_id: BidItemId,
+    pub item: BidItemInput,
+}
+
+impl From<BidItemAdded> for Event {
+    fn from(event: BidItemAdded) -> Self {
+        let ev = Event::new("bid_item_added")
+            .add_attribute("auction_id", event.auction_id.to_string())
+            .add_attribute("bid_item_id", event.bid_item_id.to_string());
+
+        add_bid_item_attributes(ev, event.item)
+    }
+}
+
+/// The name, metadata or prices of a bid item were replaced.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BidItemUpdated {
+    pub auction_id: AuctionId,
+    pub bid_item_id: BidItemId,
+    pub item: BidItemInput,
+}
+
+impl From<BidItemUpdated> for Event {
+    fn from(event: BidItemUpdated) -> Self {
+        let ev = Event::new("bid_item_updated")
+            .add_attribute("auction_id", event.auction_id.to_string())
+            .add_attribute("bid_item_id", event.bid_item_id.to_string());
+
+        add_bid_item_attributes(ev, event.item)
+    }
+}
+
+/// Lists and prices are JSON encoded, the same way they're sent to the contract.
+fn add_bid_item_attributes(mut ev: Event, item: BidItemInput) -> Event {
+    ev = ev
+        .add_attribute("name", item.name)
+        .add_attribute("media_uris", json_attribute(&item.media_uris))
+        .add_attribute("attributes", json_attribute(&item.attributes));
+
+    if let Some(description) = item.description {
+        ev = ev.add_attribute("description", description);
+    }
+
+    if let Some(category) = item.category {
+        ev = ev.add_attribute("category", category);
+    }
+
+    if let Some(starting_price) = item.starting_price {
+        ev = ev.add_attribute("starting_price", json_attribute(&starting_price));
+    }
+
+    if let Some(reserve_price) = item.reserve_price {
+        ev = ev.add_attribute("reserve_price", json_attribute(&reserve_price));
+    }
+
+    ev
+}
+
+fn json_attribute<T: Serialize>(value: &T) -> String {
+    to_json_string(value).unwrap_or_default()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BidItemRemoved {
+    pub auction_id: AuctionId,
+    pub bid_item_id: BidItemId,
+}
+
+impl From<BidItemRemoved> for Event {
+    fn from(event: BidItemRemoved) -> Self {
+        Event::new("bid_item_removed")
+            .add_attribute("auction_id", event.auction_id.to_string())
+            .add_attribute("bid_item_id", event.bid_item_id.to_string())
+    }
+}
+
+/// A bid item removed while it had bids, it's deleted once the crank refunded them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BidItemCancelled {
+    pub auction_id: AuctionId,
+    pub bid_item_id: BidItemId,
+}
+
+impl From<BidItemCancelled> for Event {
+    fn from(event: BidItemCancelled) -> Self {
+        Event::new("bid_item_cancelled")
+            .add_attribute("auction_id", event.auction_id.to_string())
+            .add_attribute("bid_item_id", event.bid_item_id.to_string())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BidPlaced {
+    pub auction_id: AuctionId,
+    pub bid_item_id: BidItemId,
+    pub bid_id: BidId,
+    pub bidder: Bidder,
+    pub amount: Uint128,
+    pub denom: String,
+    pub from_deposit: bool,
+}
+
+impl From<BidPlaced> for Event {
+    fn from(event: BidPlaced) -> Self {
+        Event::new("bid_placed")
+            .add_attribute("auction_id", event.auction_id.to_string())
+            .add_attribute("bid_item_id", event.bid_item_id.to_string())
+            .add_attribute("bid_id", event.bid_id.to_string())
+            .add_attribute("bidder", event.bidder.to_string())
+            .add_attribute("amount", event.amount)
+            .add_attribute("denom", event.denom)
+            .add_attribute("from_deposit", event.from_deposit.to_string())
+    }
+}
+
+/// The previous winning bid of an item was beaten by `outbid_by`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BidOutbid {
+    pub bid_item_id: BidItemId,
+    pub bid_id: BidId,
+    pub bidder: Bidder,
+    pub amount: Uint128,
+    pub outbid_by: BidId,
+}
+
+impl From<BidOutbid> for Event {
+    fn from(event: BidOutbid) -> Self {
+        Event::new("bid_outbid")
+            .add_attribute("bid_item_id", event.bid_item_id.to_string())
+            .add_attribute("bid_id", event.bid_id.to_string())
+            .add_attribute("bidder", event.bidder.to_string())
+            .add_attribute("amount", event.amount)
+            .add_attribute("outbid_by", event.outbid_by.to_string())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuctionStateChanged {
+    pub auction_id: AuctionId,
+    pub previous: AuctionStatus,
+    pub next: AuctionStatus,
+}
+
+impl From<AuctionStateChanged> for Event {
+    fn from(event: AuctionStateChanged) -> Self {
+        Event::new("auction_state_changed")
+            .add_attribute("auction_id", event.auction_id.to_string())
+            .add_attribute("previous", event.previous.as_str())
+            .add_attribute("next", event.next.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ItemSettled {
+    pub auction_id: AuctionId,
+    pub bid_item_id: BidItemId,
+    pub status: BidItemStatus,
+    pub winner: Option<Bidder>,
+    pub amount: Option<Uint128>,
+    pub denom: String,
+}
+
+impl From<ItemSettled> for Event {
+    fn from(event: ItemSettled) -> Self {
+        let mut ev = Event::new("item_settled")
+            .add_attribute("auction_id", event.auction_id.to_string())
+            .add_attribute("bid_item_id", event.bid_item_id.to_string())
+            .add_attribute("status", event.status.as_str());
+
+        if let (Some(winner), Some(amount)) = (event.winner, event.amount) {
+            ev = ev
+                .add_attribute("winner", winner.to_string())
+                .add_attribute("amount", amount)
+                .add_attribute("denom", event.denom);
+        }
+
+        ev
+    }
+}
+
+/// The winning bid of an item was below its reserve price, the item isn't sold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReserveNotMet {
+    pub auction_id: AuctionId,
+    pub bid_item_id: BidItemId,
+    pub highest_bid: Uint128,
+    pub reserve_price: Uint128,
+}
+
+impl From<ReserveNotMet> for Event {
+    fn from(event: ReserveNotMet) -> Self {
+        Event::new("reserve_not_met")
+            .add_attribute("auction_id", event.auction_id.to_string())
+            .add_attribute("bid_item_id", event.bid_item_id.to_string())
+            .add_attribute("highest_bid", event.highest_bid)
+            .add_attribute("reserve_price", event.reserve_price)
+    }
+}
+
+/// The reserve price of an item couldn't be priced by the oracle, its auction stays queued until a
+/// later crank can settle it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettlementDeferred {
+    pub auction_id: AuctionId,
+    pub bid_item_id: BidItemId,
+    pub reason: String,
+}
+
+impl From<SettlementDeferred> for Event {
+    fn from(event: SettlementDeferred) -> Self {
+        Event::new("settlement_deferred")
+            .add_attribute("auction_id", event.auction_id.to_string())
+            .add_attribute("bid_item_id", event.bid_item_id.to_string())
+            .add_attribute("reason", event.reason)
+    }
+}
+
+/// Where a refund went, straight to the wallet, to the claimable ledger, back to the deposit, to
+/// the denied escrow or over IBC to a remote bidder.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RefundDestination {
+    Wallet,
+    Claimable,
+    Deposit,
+    Escrow,
+    Remote,
+}
+
+impl RefundDestination {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RefundDestination::Wallet => "wallet",
+            RefundDestination::Claimable => "claimable",
+            RefundDestination::Deposit => "deposit",
+            RefundDestination::Escrow => "escrow",
+            RefundDestination::Remote => "remote",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RefundIssued {
+    pub bid_item_id: BidItemId,
+    pub bid_id: BidId,
+    pub bidder: Bidder,
+    pub amount: Uint128,
+    pub denom: String,
+    pub destination: RefundDestination,
+}
+
+impl From<RefundIssued> for Event {
+    fn from(event: RefundIssued) -> Self {
+        Event::new("refund_issued")
+            .add_attribute("bid_item_id", event.bid_item_id.to_string())
+            .add_attribute("bid_id", event.bid_id.to_string())
+            .add_attribute("bidder", event.bidder.to_string())
+            .add_attribute("amount", event.amount)
+            .add_attribute("denom", event.denom)
+            .add_attribute("destination", event.destination.as_str())
+    }
+}
+
+/// A winning bid held back in the denied escrow because its bidder is denylisted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PayoutEscrowed {
+    pub bid_item_id: BidItemId,
+    pub bid_id: BidId,
+    pub bidder: Addr,
+    pub amount: Uint128,
+    pub denom: String,
+}
+
+impl From<PayoutEscrowed> for Event {
+    fn from(event: PayoutEscrowed) -> Self {
+        Event::new("payout_escrowed")
+            .add_attribute("bid_item_id", event.bid_item_id.to_string())
+            .add_attribute("bid_id", event.bid_id.to_string())
+            .add_attribute("bidder", event.bidder)
+            .add_attribute("amount", event.amount)
+            .add_attribute("denom", event.denom)
+    }
+}
+
+/// A hook contract failed to handle a notification, the call that sent it went through anyway.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HookFailed {
+    pub contract: Addr,
+    pub error: String,
+}
+
+impl From<HookFailed> for Event {
+    fn from(event: HookFailed) -> Self {
+        Event::new("hook_failed")
+            .add_attribute("contract", event.contract)
+            .add_attribute("error", event.error)
+    }
+}
+
+/// The refund of a remote bid couldn't be sent or wasn't accepted, it's held until retried.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettlementUndelivered {
+    pub bid_id: BidId,
+    pub channel_id: String,
+    pub reason: String,
+}
+
+impl From<SettlementUndelivered> for Event {
+    fn from(event: SettlementUndelivered) -> Self {
+        Event::new("settlement_undelivered")
+            .add_attribute("bid_id", event.bid_id.to_string())
+            .add_attribute("channel_id", event.channel_id)
+            .add_attribute("reason", event.reason)
+    }
+}
+
+/// An ICS-20 packet that couldn't be taken as a bid, it's acknowledged with an error so the other
+/// chain refunds its sender.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransferRejected {
+    pub channel_id: String,
+    pub sequence: u64,
+    pub sender: String,
+    pub amount: Uint128,
+    pub reason: String,
+}
+
+impl From<TransferRejected> for Event {
+    fn from(event: TransferRejected) -> Self {
+        Event::new("transfer_rejected")
+            .add_attribute("channel_id", event.channel_id)
+            .add_attribute("sequence", event.sequence.to_string())
+            .add_attribute("sender", event.sender)
+            .add_attribute("amount", event.amount)
+            .add_attribute("reason", event.reason)
+    }
+}
@@ -0,0 +1,293 @@
+use cosmwasm_std::{
+    coins, from_json, to_json_binary, to_json_string, BankMsg, DepsMut, Env, Event, Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannel,
+    IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse, IbcMsg, IbcOrder, IbcPacket, IbcPacketAckMsg,
+    IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, Response, StdAck, StdError, StdResult, Storage, SubMsg, Uint128,
+};
+use sha2::{Digest, Sha256};
+
+use crate::contract::{assert_not_paused, exec, BidSource, Result, DENOM, REFUND_REPLY_ID};
+use crate::error::ContractError;
+use crate::events::{SettlementUndelivered, TransferRejected};
+use crate::msg::{BidMemo, Ics20Packet, RefundMemo};
+use crate::state::{BidId, Bidder, IbcChannelState, PauseScope, RemoteBidder, RemoteSettlement, IBC_CHANNELS, PENDING_REFUNDS, TRANSFER_CHANNELS, UNDELIVERED_SETTLEMENTS};
+
+// The contract is an ICS-20 endpoint of its own. Another chain opens a channel from its transfer
+// port to the contract's port, and remote bidders send the bidding denom back over it with a
+// `BidMemo`. The contract can't mint vouchers, so it only takes back what it sent out over the
+// same channel, those funds stayed in its balance the whole time. Packets that can't bid are
+// acknowledged with an error and the other chain refunds them. Losing remote bids are refunded
+// with packets of their own, the ones that are refused or time out are held for a retry.
+
+pub const ICS20_VERSION: &str = "ics20-1";
+
+/// How long relayers have to deliver a packet before it times out and comes back.
+const TRANSFER_TIMEOUT_SECONDS: u64 = 60 * 60 * 24;
+
+pub fn ibc_channel_open(_deps: DepsMut, _env: Env, msg: IbcChannelOpenMsg) -> Result<IbcChannelOpenResponse> {
+    check_channel(msg.channel(), msg.counterparty_version())?;
+
+    Ok(Some(Ibc3ChannelOpenResponse { version: ICS20_VERSION.to_string() }))
+}
+
+/// Remote bids are only taken once an admin adds the channel to the transfer channels.
+pub fn ibc_channel_connect(deps: DepsMut, _env: Env, msg: IbcChannelConnectMsg) -> Result<IbcBasicResponse> {
+    let channel = msg.channel();
+    check_channel(channel, msg.counterparty_version())?;
+
+    let state = IbcChannelState {
+        counterparty: channel.counterparty_endpoint.clone(),
+        outstanding: Uint128::zero(),
+        open: true,
+    };
+    IBC_CHANNELS.save(deps.storage, &channel.endpoint.channel_id, &state)?;
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_channel_connect")
+        .add_attribute("channel_id", &channel.endpoint.channel_id))
+}
+
+/// A closed channel stops taking bids. What's outstanding on it can't come back anymore, refunds
+/// sent over it fail and are held.
+pub fn ibc_channel_close(deps: DepsMut, _env: Env, msg: IbcChannelCloseMsg) -> Result<IbcBasicResponse> {
+    let channel_id = &msg.channel().endpoint.channel_id;
+
+    IBC_CHANNELS.update(deps.storage, channel_id, |state| -> Result<_> {
+        let mut state = state.ok_or_else(|| ContractError::ChannelNotOpen { channel_id: channel_id.clone() })?;
+        state.open = false;
+        Ok(state)
+    })?;
+    TRANSFER_CHANNELS.remove(deps.storage, channel_id);
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_channel_close")
+        .add_attribute("channel_id", channel_id))
+}
+
+fn check_channel(channel: &IbcChannel, counterparty_version: Option<&str>) -> Result<()> {
+    if channel.order != IbcOrder::Unordered {
+        return Err(ContractError::InvalidChannel { msg: "ICS-20 channels are unordered".to_string() });
+    }
+
+    for version in [Some(channel.version.as_str()), counterparty_version].into_iter().flatten() {
+        if version != ICS20_VERSION {
+            return Err(ContractError::InvalidChannel { msg: format!("expecting version {ICS20_VERSION}, found {version}") });
+        }
+    }
+
+    Ok(())
+}
+
+/// A packet that can't be taken as a bid gets an error acknowledgement, the other chain refunds
+/// its sender. Failing instead would leave the packet unacknowledged.
+pub fn ibc_packet_receive(mut deps: DepsMut, env: Env, msg: IbcPacketReceiveMsg) -> Result<IbcReceiveResponse> {
+    let packet = msg.packet;
+
+    let transfer: Ics20Packet = match from_json(&packet.data) {
+        Ok(transfer) => transfer,
+        Err(error) => {
+            return Ok(IbcReceiveResponse::new(StdAck::error(error.to_string()))
+                .add_attribute("action", "reject_transfer"));
+        },
+    };
+
+    match receive_bid(deps.branch(), env, &packet, &transfer) {
+        Ok(response) => Ok(IbcReceiveResponse::new(StdAck::success(b"\x01"))
+            .add_submessages(response.messages)
+            .add_attributes(response.attributes)
+            .add_events(response.events)),
+        Err(error) => Ok(IbcReceiveResponse::new(StdAck::error(error.to_string()))
+            .add_attribute("action", "reject_transfer")
+            .add_event(Event::from(TransferRejected {
+                channel_id: packet.dest.channel_id,
+                sequence: packet.sequence,
+                sender: transfer.sender,
+                amount: transfer.amount,
+                reason: error.to_string(),
+            }))),
+    }
+}
+
+/// Every check comes before `place_bid`, which checks everything before its first write, so a
+/// rejected packet leaves nothing behind.
+fn receive_bid(mut deps: DepsMut, env: Env, packet: &IbcPacket, transfer: &Ics20Packet) -> Result<Response> {
+    let channel_id = &packet.dest.channel_id;
+
+    if !TRANSFER_CHANNELS.has(deps.storage, channel_id) {
+        return Err(ContractError::UnknownTransferChannel { channel_id: channel_id.clone() });
+    }
+
+    assert_not_paused(deps.as_ref(), PauseScope::Bidding)?;
+
+    if transfer.receiver != env.contract.address.as_str() {
+        return Err(ContractError::InvalidTransfer { msg: format!("the funds were sent to {}", transfer.receiver) });
+    }
+
+    let denom = received_denom(packet, &transfer.denom);
+
+    if denom != DENOM {
+        return Err(ContractError::UnexpectedAssetsReceived { msg: format!(
+            "Expecting denom: {}, found: {}",
+            DENOM, denom)
+        });
+    }
+
+    let mut channel = IBC_CHANNELS.load(deps.storage, channel_id)?;
+
+    if channel.outstanding < transfer.amount {
+        return Err(ContractError::InvalidTransfer { msg: format!("only {} was sent over {channel_id}", channel.outstanding) });
+    }
+
+    let memo: BidMemo = from_json(transfer.memo.as_bytes())
+        .map_err(|error| ContractError::InvalidTransfer { msg: error.to_string() })?;
+
+    let bidder = Bidder::Remote(RemoteBidder {
+        channel_id: channel_id.clone(),
+        address: transfer.sender.clone(),
+    });
+
+    let response = exec::place_bid(deps.branch(), env, bidder, memo.place_bid.bid_item_id, transfer.amount, BidSource::Remote)?;
+
+    channel.outstanding -= transfer.amount;
+    IBC_CHANNELS.save(deps.storage, channel_id, &channel)?;
+
+    Ok(response)
+}
+
+pub fn ibc_packet_ack(deps: DepsMut, env: Env, msg: IbcPacketAckMsg) -> Result<IbcBasicResponse> {
+    match from_json(&msg.acknowledgement.data) {
+        Ok(StdAck::Success(_)) => packet_delivered(deps, &env, &msg.original_packet),
+        Ok(StdAck::Error(error)) => packet_returned(deps, &env, &msg.original_packet, error),
+        Err(error) => packet_returned(deps, &env, &msg.original_packet, error.to_string()),
+    }
+}
+
+pub fn ibc_packet_timeout(deps: DepsMut, env: Env, msg: IbcPacketTimeoutMsg) -> Result<IbcBasicResponse> {
+    packet_returned(deps, &env, &msg.packet, "timeout".to_string())
+}
+
+/// The funds of a delivered packet are vouchers on the other chain now, they stay outstanding.
+fn packet_delivered(deps: DepsMut, env: &Env, packet: &IbcPacket) -> Result<IbcBasicResponse> {
+    let transfer: Ics20Packet = from_json(&packet.data)?;
+
+    let mut response = IbcBasicResponse::new().add_attribute("action", "ibc_packet_ack");
+
+    if let Some(bid_id) = refunded_bid(env, &transfer) {
+        PENDING_REFUNDS.remove(deps.storage, bid_id);
+        response = response.add_attribute("bid_id", bid_id.to_string());
+    }
+
+    Ok(response)
+}
+
+/// The other chain didn't take the funds of a packet. Refunds are held until an admin retries
+/// them, transfers go back to their sender.
+fn packet_returned(deps: DepsMut, env: &Env, packet: &IbcPacket, reason: String) -> Result<IbcBasicResponse> {
+    let transfer: Ics20Packet = from_json(&packet.data)?;
+
+    let mut response = IbcBasicResponse::new().add_attribute("action", "return_packet");
+
+    match refunded_bid(env, &transfer) {
+        Some(bid_id) => {
+            response = response
+                .add_attribute("bid_id", bid_id.to_string())
+                .add_events(hold_refund(deps.storage, bid_id, reason)?);
+        },
+        None => {
+            release(deps.storage, &packet.src.channel_id, transfer.amount)?;
+            response = response
+                .add_attribute("sender", &transfer.sender)
+                .add_message(BankMsg::Send { to_address: transfer.sender, amount: coins(transfer.amount.u128(), DENOM) });
+        },
+    }
+
+    Ok(response)
+}
+
+/// The bid a packet refunds, the contract is the sender of refunds only.
+fn refunded_bid(env: &Env, transfer: &Ics20Packet) -> Option<BidId> {
+    if transfer.sender != env.contract.address.as_str() {
+        return None;
+    }
+
+    from_json::<RefundMemo>(transfer.memo.as_bytes()).ok().map(|memo| memo.refund)
+}
+
+/// The packet refunding `settlement`, tracked in the pending refunds until it's acknowledged. It
+/// replies when it can't even be sent, on a closed channel for one, so the refund is held instead
+/// of failing the call that sent it.
+pub(crate) fn refund_msg(storage: &mut dyn Storage, env: &Env, bid_id: BidId, settlement: RemoteSettlement) -> Result<SubMsg> {
+    let transfer = Ics20Packet {
+        denom: settlement.amount.denom.clone(),
+        amount: settlement.amount.amount,
+        sender: env.contract.address.to_string(),
+        receiver: settlement.receiver.clone(),
+        memo: to_json_string(&RefundMemo { refund: bid_id })?,
+    };
+
+    let msg = send_transfer(storage, env, &settlement.channel_id, transfer)?;
+
+    PENDING_REFUNDS.save(storage, bid_id, &settlement)?;
+
+    Ok(SubMsg::reply_on_error(msg, REFUND_REPLY_ID).with_payload(to_json_binary(&bid_id)?))
+}
+
+/// The packet sending `transfer` over a channel of the contract, its funds are outstanding on the
+/// channel until they come back.
+pub(crate) fn send_transfer(storage: &mut dyn Storage, env: &Env, channel_id: &str, transfer: Ics20Packet) -> Result<IbcMsg> {
+    IBC_CHANNELS.update(storage, channel_id, |state| -> Result<_> {
+        let mut state = state.ok_or_else(|| ContractError::ChannelNotOpen { channel_id: channel_id.to_string() })?;
+        state.outstanding += transfer.amount;
+        Ok(state)
+    })?;
+
+    Ok(IbcMsg::SendPacket {
+        channel_id: channel_id.to_string(),
+        data: to_json_binary(&transfer)?,
+        timeout: env.block.time.plus_seconds(TRANSFER_TIMEOUT_SECONDS).into(),
+    })
+}
+
+/// Takes back funds that were outstanding on a channel but didn't leave.
+fn release(storage: &mut dyn Storage, channel_id: &str, amount: Uint128) -> StdResult<()> {
+    IBC_CHANNELS.update(storage, channel_id, |state| -> StdResult<_> {
+        let mut state = state.ok_or_else(|| StdError::not_found(format!("channel {channel_id}")))?;
+        state.outstanding = state.outstanding.checked_sub(amount)?;
+        Ok(state)
+    })?;
+
+    Ok(())
+}
+
+/// Moves a pending refund to the undelivered settlements, `None` when it isn't pending anymore.
+pub(crate) fn hold_refund(storage: &mut dyn Storage, bid_id: BidId, reason: String) -> StdResult<Option<Event>> {
+    let Some(settlement) = PENDING_REFUNDS.may_load(storage, bid_id)? else {
+        return Ok(None);
+    };
+
+    PENDING_REFUNDS.remove(storage, bid_id);
+    release(storage, &settlement.channel_id, settlement.amount.amount)?;
+    UNDELIVERED_SETTLEMENTS.save(storage, bid_id, &settlement)?;
+
+    Ok(Some(SettlementUndelivered { bid_id, channel_id: settlement.channel_id, reason }.into()))
+}
+
+/// The denom ICS-20 credited on this chain for the funds of a received packet.
+pub fn received_denom(packet: &IbcPacket, denom: &str) -> String {
+    let source_prefix = format!("{}/{}/", packet.src.port_id, packet.src.channel_id);
+
+    // Funds coming back drop the hop they took out of this chain, others get the hop they came in by
+    let path = match denom.strip_prefix(&source_prefix) {
+        Some(unwound) if !is_trace(unwound) => return unwound.to_string(),
+        Some(unwound) => unwound.to_string(),
+        None => format!("{}/{}/{denom}", packet.dest.port_id, packet.dest.channel_id),
+    };
+
+    format!("ibc/{}", hex::encode_upper(Sha256::digest(path.as_bytes())))
+}
+
+/// Whether a denom starts with a `<port>/<channel>/` hop, base denoms can have slashes too.
+fn is_trace(denom: &str) -> bool {
+    let mut parts = denom.splitn(3, '/');
+
+    matches!((parts.next(), parts.next(), parts.next()), (Some(port), Some(channel), Some(_)) if !port.is_empty() && channel.starts_with("channel-"))
+}
@@ -42,7 +42,7 @@ macro_rules! impl_monotonic_id {
             type SuperSuffix = u64;
 
             #[inline]
-            fn key(&self) -> Vec<cw_storage_plus::Key> {
+            fn key(&self) -> Vec<cw_storage_plus::Key<'_>> {
                 use cw_storage_plus::IntKey;
                 vec![cw_storage_plus::Key::Val32(self.0.to_cw_bytes())]
 
@@ -51,7 +51,7 @@ macro_rules! impl_monotonic_id {
         }
 
         impl<'a> cw_storage_plus::Prefixer<'a> for $name {
-            fn prefix(&self) -> Vec<cw_storage_plus::Key> {
+            fn prefix(&self) -> Vec<cw_storage_plus::Key<'_>> {
                 use cw_storage_plus::IntKey;
                 vec![cw_storage_plus::Key::Val32(self.0.to_cw_bytes())]
             }
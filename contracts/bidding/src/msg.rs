@@ -1,19 +1,38 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Addr;
-use crate::state::{Auction, AuctionId, AuctionStatus, BidItem, BidItemId, BidItemKey};
+use cosmwasm_std::{Addr, Coin, Decimal, Order, Timestamp, Uint128};
+use crate::state::{Auction, AuctionId, AuctionStatus, Bid, BidId, BidItem, BidItemAttribute, BidItemStatus, BidItemId, BidItemKey, BidKey, Bidder, BidderAccess, BidderStats, Config, OracleConfig, PauseScope, PayoutMode, Price, RemoteSettlement};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub admin: Addr,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
+/// Messages the chain can send, e.g. from an end-blocker.
+#[cw_serde]
+pub enum SudoMsg {
+    AdvanceCrank {
+        max_items: Option<u32>,
+    },
+    CloseExpiredAuctions {},
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     // Leave {},
     // Donate {},
+    /// Only the admin can create auctions. `owner` lists the auction for a seller, who then manages
+    /// it and is paid its winning bids, the admin owns it otherwise.
     CreateAuction {
         name: String,
-        bid_items: Vec<String>,
+        description: Option<String>,
+        terms: Option<String>,
+        ends_at: Option<Timestamp>,
+        payout_mode: Option<PayoutMode>,
+        owner: Option<String>,
+        bid_items: Vec<BidItemInput>,
     },
     SetAuctionState {
         id: AuctionId,
@@ -21,12 +40,144 @@ pub enum ExecuteMsg {
     },
     AddBidItems {
         auction_id: AuctionId,
-        bid_items: Vec<String>,
+        bid_items: Vec<BidItemInput>,
     },
+    /// Uses the attached funds, or `amount` from the sender's deposit when nothing is attached.
     PlaceBid {
         bid_item_id: BidItemId,
+        amount: Option<Uint128>,
+    },
+    AdvanceCrank {
+        max_items: Option<u32>,
+    },
+    CancelAuction {
+        id: AuctionId,
+    },
+    UpdateBidItem {
+        bid_item_id: BidItemId,
+        bid_item: BidItemInput,
+    },
+    RemoveBidItem {
+        bid_item_id: BidItemId,
+    },
+    UpdateConfig(ConfigUpdate),
+    FundCrankRewards {},
+    /// Pauses the given scope, or all of them.
+    Pause {
+        scope: Option<PauseScope>,
+    },
+    Unpause {
+        scope: Option<PauseScope>,
     },
-    AdvanceCrank {},
+    Deposit {},
+    Withdraw {
+        amount: Option<Uint128>,
+    },
+    Claim {},
+    ClaimFor {
+        denoms: Vec<String>,
+    },
+    SetBidderAccess {
+        auction_id: AuctionId,
+        access: BidderAccess,
+    },
+    UpdateAllowlist {
+        auction_id: AuctionId,
+        add: Vec<Addr>,
+        remove: Vec<Addr>,
+    },
+    DenyAddresses {
+        addresses: Vec<String>,
+    },
+    AllowAddresses {
+        addresses: Vec<String>,
+    },
+    /// Pays out what's held for a denylisted address to `recipient`, or to the address itself:
+    /// its denied escrow, and its deposits and claimable balances while it's still denied.
+    ResolveEscrow {
+        address: String,
+        recipient: Option<String>,
+    },
+    AddHook {
+        contract: Addr,
+    },
+    RemoveHook {
+        contract: Addr,
+    },
+    /// Sends the bidding denom attached to `remote_address` over an open ICS-20 channel, it comes
+    /// back to the sender if the other chain doesn't take it.
+    Transfer {
+        channel_id: String,
+        remote_address: String,
+    },
+    /// Takes remote bids from ICS-20 packets received over `channel_id`, our end of an open channel.
+    AddTransferChannel {
+        channel_id: String,
+    },
+    /// Stops taking remote bids from the channel, its bids are still refunded over it.
+    RemoveTransferChannel {
+        channel_id: String,
+    },
+    /// Resends an undelivered refund, over `channel_id` or the channel it was first sent over.
+    RetrySettlement {
+        bid_id: BidId,
+        channel_id: Option<String>,
+    },
+    /// Saves up to `limit` records of a contract migrated from the first version again, so they
+    /// get the index entries its plain maps didn't write. Admin only, repeated until it's done.
+    Reindex {
+        limit: Option<u32>,
+    },
+}
+
+/// Config fields to update, the ones left empty keep their current value.
+#[cw_serde]
+#[derive(Default)]
+pub struct ConfigUpdate {
+    pub default_page_limit: Option<u32>,
+    pub max_page_limit: Option<u32>,
+    pub crank_reward: Option<Uint128>,
+    pub crank_reward_fee_bps: Option<u16>,
+    pub crank_batch_size: Option<u32>,
+    pub max_crank_batch_size: Option<u32>,
+    pub crank_max_bids: Option<u32>,
+    pub guardian: Option<String>,
+    /// Removes the guardian, only the admin can pause afterwards.
+    #[serde(default)]
+    pub clear_guardian: bool,
+    /// Zero removes the cap.
+    pub max_active_bids_per_bidder: Option<u32>,
+    /// Zero removes the cap.
+    pub max_escrow_per_bidder: Option<Uint128>,
+    pub oracle: Option<OracleConfig>,
+    pub hook_gas_limit: Option<u64>,
+}
+
+#[cw_serde]
+pub struct BidItemInput {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub media_uris: Vec<String>,
+    pub category: Option<String>,
+    #[serde(default)]
+    pub attributes: Vec<BidItemAttribute>,
+    pub starting_price: Option<Price>,
+    pub reserve_price: Option<Price>,
+}
+
+impl BidItemInput {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            media_uris: vec![],
+            category: None,
+            attributes: vec![],
+            starting_price: None,
+            reserve_price: None,
+        }
+    }
 }
 
 #[cw_serde]
@@ -42,33 +193,394 @@ pub struct BidItemsByIdResp {
     pub bid_state: AuctionStatus,
 }
 
+/// A page of a list query. When more records are left, `next` holds the last returned key, to be
+/// passed as `start_after` for ascending queries or as `start_before` for descending ones.
+#[cw_serde]
+pub struct PagedResp<K, T> {
+    pub items: Vec<(K, T)>,
+    pub next: Option<K>,
+}
+
+/// `next` is the `start_after` of the next page of queued auctions, `estimated_crank_calls` only
+/// counts the auctions of this page.
+#[cw_serde]
+pub struct CrankStatusResp {
+    pub queue_length: u64,
+    pub queued_auctions: Vec<QueuedAuctionResp>,
+    pub next: Option<AuctionId>,
+    pub estimated_crank_calls: u64,
+    pub reward_per_bid_item: Uint128,
+    pub reward_pool: Uint128,
+}
+
+#[cw_serde]
+pub struct QueuedAuctionResp {
+    pub auction_id: AuctionId,
+    pub pending_bid_items: u64,
+}
+
+/// Prices of a bid item in the bidding denom, quote prices at the current oracle rate.
+#[cw_serde]
+pub struct BidItemPricesResp {
+    pub starting_price: Option<Uint128>,
+    pub reserve_price: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct BidderBidResp {
+    pub auction_id: AuctionId,
+    pub bid_item_id: BidItemId,
+    pub bid_id: BidId,
+    pub amount: Uint128,
+    pub placed: Timestamp,
+    pub winning: bool,
+}
+
+/// Inconsistencies found by the `VerifyState` query. `next` is passed back as `start_after` to
+/// resume the walk, it's `None` once everything has been checked.
+#[cw_serde]
+pub struct VerifyStateResp {
+    pub checked_auctions: u64,
+    pub issues: Vec<StateIssue>,
+    pub next: Option<VerifyCursor>,
+}
+
+/// Where a `VerifyState` walk stopped and what it summed up to there. The escrow is compared to
+/// the balance with these totals, so they're only exact if the state doesn't change between pages.
+#[cw_serde]
+pub struct VerifyCursor {
+    pub step: VerifyStep,
+    pub unsettled: Uint128,
+    pub escrowed: Uint128,
+}
+
+/// The phases of a `VerifyState` walk, in the order they run.
+#[cw_serde]
+pub enum VerifyStep {
+    Auction(AuctionProgress),
+    BidItemLookups {
+        start_after: Option<BidItemId>,
+    },
+    CrankQueue {
+        start_after: Option<AuctionId>,
+        queued: u64,
+    },
+    Deposits {
+        start_after: Option<Addr>,
+    },
+    DeniedEscrow {
+        start_after: Option<Addr>,
+    },
+    Claimable {
+        start_after: Option<(Addr, String)>,
+    },
+    UndeliveredSettlements {
+        start_after: Option<BidId>,
+    },
+    IbcChannels {
+        start_after: Option<String>,
+    },
+}
+
+/// The part of an auction checked so far.
+#[cw_serde]
+pub struct AuctionProgress {
+    pub auction_id: AuctionId,
+    pub last_bid_item: Option<BidItemId>,
+    pub bid_items: u64,
+    pub bids: u64,
+    pub coins: Uint128,
+    pub bid_item: Option<BidItemProgress>,
+}
+
+impl AuctionProgress {
+    pub fn new(auction_id: AuctionId) -> Self {
+        Self {
+            auction_id,
+            last_bid_item: None,
+            bid_items: 0,
+            bids: 0,
+            coins: Uint128::zero(),
+            bid_item: None,
+        }
+    }
+}
+
+/// The bids of a bid item checked so far.
+#[cw_serde]
+pub struct BidItemProgress {
+    pub bid_item_id: BidItemId,
+    pub start_after: Option<BidId>,
+    pub bids: u64,
+    pub coins: Uint128,
+    pub highest: Option<(BidId, Uint128)>,
+}
+
+impl BidItemProgress {
+    pub fn new(bid_item_id: BidItemId) -> Self {
+        Self {
+            bid_item_id,
+            start_after: None,
+            bids: 0,
+            coins: Uint128::zero(),
+            highest: None,
+        }
+    }
+}
+
+#[cw_serde]
+pub enum StateIssue {
+    /// The bid item and its auction lookup don't point at each other.
+    OrphanedBidItem {
+        bid_item_id: BidItemId,
+        auction_id: Option<AuctionId>,
+    },
+    /// The stored winning bid is missing or lower than the highest bid of the item.
+    WinningBidNotMaximum {
+        bid_item_id: BidItemId,
+        winning_bid: Option<BidId>,
+        highest_bid: Option<BidId>,
+    },
+    CounterMismatch {
+        counter: String,
+        auction_id: Option<AuctionId>,
+        bid_item_id: Option<BidItemId>,
+        stored: Uint128,
+        actual: Uint128,
+    },
+    /// Unsettled bids, deposits, claimable funds and the crank reward pool against the bank balance.
+    EscrowMismatch {
+        escrowed: Uint128,
+        balance: Uint128,
+    },
+}
+
+// List queries take an exclusive `start_after` lower bound and an exclusive `start_before` upper
+// bound, whatever the order. The limit defaults to, and is capped by, the contract config.
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
     #[returns(Addr)]
     Admin {},
+    #[returns(Config)]
+    Config {},
     #[returns(Auction)]
     Auction {
         id: AuctionId
     },
-    #[returns(Vec<(BidItemId, BidItem)>)]
+    #[returns(PagedResp<BidItemId, BidItem>)]
     BidItemsByAuctionId {
-        auction_id: AuctionId
+        auction_id: AuctionId,
+        start_after: Option<BidItemId>,
+        start_before: Option<BidItemId>,
+        limit: Option<u32>,
+        order: Option<Order>,
     },
     #[returns(BidItem)]
     BidItem {
         id: BidItemId
     },
-    #[returns(Vec<(BidItemKey, BidItem)>)]
+    #[returns(PagedResp<BidItemKey, BidItem>)]
     BidItems {
+        status: Option<BidItemStatus>,
         start_after: Option<BidItemKey>,
+        start_before: Option<BidItemKey>,
+        limit: Option<u32>,
+        order: Option<Order>,
     },
-    #[returns(Vec<(AuctionId, Auction)>)]
+    #[returns(PagedResp<AuctionId, Auction>)]
     Auctions {
+        status: Option<AuctionStatus>,
         start_after: Option<AuctionId>,
+        start_before: Option<AuctionId>,
+        limit: Option<u32>,
+        order: Option<Order>,
     },
-    #[returns(Vec<(BidItemId, BidItem)>)]
+    /// Bid items in the order of `bid_items_ids`, a page resumes after the id in `start_after`.
+    #[returns(PagedResp<BidItemId, BidItem>)]
     BidItemsById {
         bid_items_ids: Vec<BidItemId>,
-    }
+        start_after: Option<BidItemId>,
+        limit: Option<u32>,
+    },
+    #[returns(PagedResp<BidKey, BidderBidResp>)]
+    BidsByBidder {
+        bidder: Bidder,
+        start_after: Option<BidKey>,
+        start_before: Option<BidKey>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+    #[returns(PagedResp<BidId, Bid>)]
+    BidsByBidItem {
+        bid_item_id: BidItemId,
+        start_after: Option<BidId>,
+        start_before: Option<BidId>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+    #[returns(Option<(BidId, Bid)>)]
+    WinningBid {
+        bid_item_id: BidItemId,
+    },
+    #[returns(BidItemPricesResp)]
+    BidItemPrices {
+        bid_item_id: BidItemId,
+    },
+    #[returns(CrankStatusResp)]
+    CrankStatus {
+        start_after: Option<AuctionId>,
+        limit: Option<u32>,
+    },
+    #[returns(Vec<PauseScope>)]
+    Paused {},
+    #[returns(Vec<Coin>)]
+    Claimable {
+        address: Addr,
+    },
+    #[returns(Uint128)]
+    DepositBalance {
+        address: Addr,
+    },
+    /// Whether the bidder can bid on the auction, asking its verifier contract if it has one.
+    #[returns(bool)]
+    IsBidderAllowed {
+        auction_id: AuctionId,
+        bidder: Addr,
+    },
+    #[returns(BidderStats)]
+    BidderStats {
+        address: Addr,
+    },
+    #[returns(PagedResp<Addr, Timestamp>)]
+    Denylist {
+        start_after: Option<Addr>,
+        start_before: Option<Addr>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+    #[returns(PagedResp<Addr, Timestamp>)]
+    Hooks {
+        start_after: Option<Addr>,
+        start_before: Option<Addr>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+    #[returns(Uint128)]
+    DeniedEscrow {
+        address: Addr,
+    },
+    /// Channels remote bids are taken from, with the time they were added.
+    #[returns(PagedResp<String, Timestamp>)]
+    TransferChannels {
+        start_after: Option<String>,
+        start_before: Option<String>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+    #[returns(PagedResp<BidId, RemoteSettlement>)]
+    UndeliveredSettlements {
+        start_after: Option<BidId>,
+        start_before: Option<BidId>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    },
+    /// Reads up to `limit` entries of the contract's maps and reports what doesn't add up. Follow
+    /// `next` until it's `None` to walk everything. With `auction_id` only that auction is checked
+    /// and the contract wide checks are skipped.
+    #[returns(VerifyStateResp)]
+    VerifyState {
+        auction_id: Option<AuctionId>,
+        start_after: Option<VerifyCursor>,
+        limit: Option<u32>,
+    },
+}
+
+/// Query a verifier contract has to answer for auctions with `BidderAccess::Verifier`.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum VerifierQueryMsg {
+    #[returns(bool)]
+    IsAllowed {
+        auction_id: AuctionId,
+        bidder: Addr,
+    },
+}
+
+/// Query the price oracle set in the config has to answer.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum OracleQueryMsg {
+    #[returns(OraclePriceResp)]
+    Price {
+        base: String,
+        quote: String,
+    },
+}
+
+#[cw_serde]
+pub struct OraclePriceResp {
+    /// Units of the quote asset per unit of the base denom.
+    pub price: Decimal,
+    /// Time weighted average of the price, the price is checked against it.
+    pub twap: Decimal,
+    pub updated_at: Timestamp,
+}
+
+/// Execute message sent to hook contracts, they handle it as `{"bidding_hook": {...}}`.
+#[cw_serde]
+pub enum HookExecuteMsg {
+    BiddingHook(HookMsg),
+}
+
+#[cw_serde]
+pub enum HookMsg {
+    BidPlaced {
+        auction_id: AuctionId,
+        bid_item_id: BidItemId,
+        bid_id: BidId,
+        bidder: Bidder,
+        amount: Uint128,
+    },
+    ItemWon {
+        auction_id: AuctionId,
+        bid_item_id: BidItemId,
+        bid_id: BidId,
+        winner: Bidder,
+        amount: Uint128,
+    },
+    AuctionCompleted {
+        auction_id: AuctionId,
+    },
+}
+
+/// Data of an ICS-20 packet, amounts are sent as strings.
+#[cw_serde]
+pub struct Ics20Packet {
+    /// The denom trace as seen by the sending chain.
+    pub denom: String,
+    pub amount: Uint128,
+    pub sender: String,
+    pub receiver: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub memo: String,
+}
+
+/// Memo of an ICS-20 packet that bids with the funds it carries. The funds have to be the bidding
+/// denom coming back over the channel it was sent out by.
+#[cw_serde]
+pub struct BidMemo {
+    pub place_bid: RemoteBid,
+}
+
+#[cw_serde]
+pub struct RemoteBid {
+    pub bid_item_id: BidItemId,
+}
+
+/// Memo of the packets refunding remote bidders, so a refund that comes back can be held for a
+/// retry.
+#[cw_serde]
+pub struct RefundMemo {
+    pub refund: BidId,
 }
@@ -1,7 +1,9 @@
+use std::fmt;
 use std::mem;
+use std::str::FromStr;
 
-use cosmwasm_std::{Addr, StdError, StdResult, Timestamp, Uint128, Uint64};
-use cw_storage_plus::{IntKey, Item, Key, KeyDeserialize, Map, PrimaryKey};
+use cosmwasm_std::{Addr, Coin, IbcEndpoint, StdError, StdResult, Timestamp, Uint128, Uint64};
+use cw_storage_plus::{Index, IndexList, IndexedMap, IntKey, Item, Key, KeyDeserialize, Map, MultiIndex, PrimaryKey};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -11,15 +13,152 @@ use crate::impl_monotonic_id;
 // Bid Items has one to many Bids
 
 pub const ADMIN: Item<Addr> = Item::new("admin");
+/// Version of the code that instantiated or last migrated the contract.
+pub const CONTRACT_VERSION: Item<String> = Item::new("contract_version");
+/// Where re-saving the records of a migrated contract got to, until every record is indexed.
+pub const REINDEX_CURSOR: Item<ReindexStep> = Item::new("reindex_cursor");
+pub const CONFIG: Item<Config> = Item::new("config");
 // pub const DONATION_DENOM: Item<String> = Item::new("donation_denom");
 pub const ADMINS: Map<&Addr, Timestamp> = Map::new("admins");
-pub const AUCTIONS: Map<AuctionId, Auction> = Map::new("auctions");
-pub const BID_ITEMS: Map<BidItemKey, BidItem> = Map::new("bid_items");
+pub const AUCTIONS: IndexedMap<AuctionId, Auction, AuctionIndexes> = IndexedMap::new("auctions", AuctionIndexes {
+    status: MultiIndex::new(|_pk, auction| auction.current_state.as_str().to_string(), "auctions", "auctions__status"),
+});
+pub const BID_ITEMS: IndexedMap<BidItemKey, BidItem, BidItemIndexes> = IndexedMap::new("bid_items", BidItemIndexes {
+    status: MultiIndex::new(|_pk, bid_item| bid_item.current_state.as_str().to_string(), "bid_items", "bid_items__status"),
+});
 pub const BID_ITEMS_TO_AUCTIONS: Map<BidItemId, AuctionId> = Map::new("bid_items_to_auctions");
-pub const BIDS: Map<BidKey, Bid> = Map::new("bids");
+pub const BIDS: IndexedMap<BidKey, Bid, BidIndexes> = IndexedMap::new("bids", BidIndexes {
+    bidder: MultiIndex::new(|_pk, bid| bid.bidder.to_string(), "bids", "bids__bidder"),
+});
 pub const AUCTIONS_CRANK_QUEUE: Map<AuctionId, ()> = Map::new("auctions_crank_queue");
 pub const AUCTIONS_CRANK_QUEUE_COUNT: Item<u64> = Item::new("auctions_crank_queue_count");
 pub const WINNING_BIDS: Map<BidItemId, BidKey> = Map::new("winning_bids");
+pub const CRANK_REWARD_POOL: Item<Uint128> = Item::new("crank_reward_pool");
+/// Auctions with an end time, keyed by (ends_at seconds, auction id) so expired ones come first.
+pub const AUCTION_EXPIRIES: Map<(u64, AuctionId), ()> = Map::new("auction_expiries");
+/// Settled funds waiting to be claimed, by (address, denom).
+pub const CLAIMABLE: Map<(&Addr, &str), Uint128> = Map::new("claimable");
+/// Funds bidders keep in the contract to bid without attaching funds.
+pub const DEPOSITS: Map<&Addr, Uint128> = Map::new("deposits");
+pub const PAUSED: Item<Vec<PauseScope>> = Item::new("paused");
+pub const CRANK_CURSORS: Map<AuctionId, BidItemId> = Map::new("crank_cursors");
+pub const CRANK_BID_CURSORS: Map<BidItemId, BidId> = Map::new("crank_bid_cursors");
+/// Bid items removed while they had bids, the crank refunds their bids before deleting them.
+pub const REMOVED_BID_ITEMS: Map<BidItemId, AuctionId> = Map::new("removed_bid_items");
+/// Bidders allowed on auctions with an allowlist.
+pub const ALLOWLISTS: Map<(AuctionId, &Addr), ()> = Map::new("allowlists");
+/// By bidder, as formatted by `Bidder`'s `Display`.
+pub const BIDDER_STATS: Map<&str, BidderStats> = Map::new("bidder_stats");
+/// Addresses that can't bid or be paid out, with the time they were denied.
+pub const DENYLIST: Map<&Addr, Timestamp> = Map::new("denylist");
+/// Settlement payouts held back from denylisted addresses until an admin resolves them.
+pub const DENIED_ESCROW: Map<&Addr, Uint128> = Map::new("denied_escrow");
+/// Contracts notified about bids and settlements, with the time they were added.
+pub const HOOKS: Map<&Addr, Timestamp> = Map::new("hooks");
+/// ICS-20 channels opened with the contract, by our end's channel id.
+pub const IBC_CHANNELS: Map<&str, IbcChannelState> = Map::new("ibc_channels");
+/// Open channels remote bids are taken from, with the time an admin added them.
+pub const TRANSFER_CHANNELS: Map<&str, Timestamp> = Map::new("transfer_channels");
+/// Refunds sent to remote bidders, until their packet is acknowledged.
+pub const PENDING_REFUNDS: Map<BidId, RemoteSettlement> = Map::new("pending_refunds");
+/// Refunds to remote bidders that didn't reach the other chain, waiting to be retried.
+pub const UNDELIVERED_SETTLEMENTS: Map<BidId, RemoteSettlement> = Map::new("undelivered_settlements");
+/// Bid items the crank found under their reserve price, while their bids are being refunded.
+pub const UNMET_RESERVES: Map<BidItemId, ()> = Map::new("unmet_reserves");
+
+#[derive(PartialEq, Clone, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct Config {
+    pub default_page_limit: u32,
+    pub max_page_limit: u32,
+    /// Paid to the crank caller for every bid item it settles or refunds bids of, items without
+    /// bids earn nothing.
+    pub crank_reward: Uint128,
+    /// Share of every winning bid, in basis points, that goes to the crank reward pool.
+    pub crank_reward_fee_bps: u16,
+    /// Bid items settled per crank call, callers can ask for up to max_crank_batch_size.
+    pub crank_batch_size: u32,
+    pub max_crank_batch_size: u32,
+    /// Bids paid out per crank call, items with more bids are settled over several calls.
+    pub crank_max_bids: u32,
+    /// Can pause and unpause the contract besides the admin.
+    pub guardian: Option<Addr>,
+    /// Unsettled bids a single bidder can have across all auctions.
+    pub max_active_bids_per_bidder: Option<u32>,
+    /// Total amount a single bidder can have in unsettled bids.
+    pub max_escrow_per_bidder: Option<Uint128>,
+    /// Converts the prices of bid items that are set in a quote asset.
+    pub oracle: Option<OracleConfig>,
+    /// Gas a single hook call can use. A hook that runs out fails on its own instead of using up
+    /// the gas of the call that notified it.
+    pub hook_gas_limit: u64,
+}
+
+#[derive(PartialEq, Clone, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct OracleConfig {
+    pub contract: Addr,
+    /// Asset quote prices are denominated in, e.g. "usd".
+    pub quote: String,
+    /// Oracle prices older than this, in seconds, are rejected.
+    pub max_staleness: u64,
+    /// How far the oracle price can be from its time weighted average, in basis points.
+    pub max_deviation_bps: u16,
+}
+
+/// A price set by the seller, in the bidding denom or in the oracle's quote asset. Quote prices
+/// are converted at the oracle rate whenever they're checked.
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Price {
+    Native(Uint128),
+    Quote(Uint128),
+}
+
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseScope {
+    Bidding,
+    Listing,
+    Settlement,
+}
+
+impl PauseScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PauseScope::Bidding => "bidding",
+            PauseScope::Listing => "listing",
+            PauseScope::Settlement => "settlement",
+        }
+    }
+}
+
+/// How settlement pays out, Push sends the funds right away and Pull credits claimable balances.
+#[derive(PartialEq, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutMode {
+    #[default]
+    Push,
+    Pull,
+}
+
+impl PayoutMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayoutMode::Push => "push",
+            PayoutMode::Pull => "pull",
+        }
+    }
+}
+
+/// Who can bid on an auction, anyone, the bidders on its allowlist or the ones an external
+/// verifier contract approves.
+#[derive(PartialEq, Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum BidderAccess {
+    #[default]
+    Open,
+    Allowlist,
+    Verifier { contract: Addr },
+}
 
 #[derive(PartialEq, Clone, Copy, Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
@@ -28,6 +167,19 @@ pub enum AuctionStatus {
     Suspended,
     PendingCompletion,
     Completed,
+    Cancelled,
+}
+
+impl AuctionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuctionStatus::Active => "active",
+            AuctionStatus::Suspended => "suspended",
+            AuctionStatus::PendingCompletion => "pending_completion",
+            AuctionStatus::Completed => "completed",
+            AuctionStatus::Cancelled => "cancelled",
+        }
+    }
 }
 
 #[derive(PartialEq, Clone, Copy, Serialize, Deserialize, JsonSchema, Debug)]
@@ -35,35 +187,214 @@ pub enum AuctionStatus {
 pub enum BidItemStatus {
     Active,
     Completed,
+    Cancelled,
+}
+
+impl BidItemStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BidItemStatus::Active => "active",
+            BidItemStatus::Completed => "completed",
+            BidItemStatus::Cancelled => "cancelled",
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, JsonSchema, Debug)]
 pub struct Auction {
     pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub terms: Option<String>,
+    /// Seller of the auction, it's paid the winning bids.
+    #[serde(default = "unowned")]
+    pub owner: Addr,
     pub available_bid_items: Uint64,
     pub total_bids: Uint64,
     pub total_coins: Uint128,
     pub current_state: AuctionStatus,
+    #[serde(default)]
+    pub ends_at: Option<Timestamp>,
+    #[serde(default)]
+    pub payout_mode: PayoutMode,
+    #[serde(default)]
+    pub bidder_access: BidderAccess,
+}
+
+/// Owner of auctions created before owners were stored, they're the admin's until
+/// `ExecuteMsg::Reindex` stores the admin as their owner.
+fn unowned() -> Addr {
+    Addr::unchecked("")
 }
 
 #[derive(PartialEq, Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct BidItem {
     pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub media_uris: Vec<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub attributes: Vec<BidItemAttribute>,
     pub total_bids: Uint64,
     pub total_coins: Uint128,
-    pub winner: Option<Addr>,
+    pub winner: Option<Bidder>,
     pub current_state: BidItemStatus,
+    /// Bids below it are rejected.
+    #[serde(default)]
+    pub starting_price: Option<Price>,
+    /// The item isn't sold when the winning bid is below it, every bid is refunded instead.
+    #[serde(default)]
+    pub reserve_price: Option<Price>,
+}
+
+#[derive(PartialEq, Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct BidItemAttribute {
+    pub key: String,
+    pub value: String,
 }
 
-#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[derive(PartialEq, Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct Bid {
     pub amount: Uint128,
-    pub bidder: Addr,
+    pub bidder: Bidder,
     pub placed: Timestamp,
+    /// Bids drawn from the bidder's deposit are refunded back into it.
+    #[serde(default)]
+    pub from_deposit: bool,
+}
+
+/// Local bidders are stored as a plain address, remote ones bid with ICS-20 transfers from
+/// another chain and are refunded over the channel they came from.
+#[derive(PartialEq, Eq, Serialize, Deserialize, JsonSchema, Debug, Clone)]
+#[serde(untagged)]
+pub enum Bidder {
+    Local(Addr),
+    Remote(RemoteBidder),
+}
+
+impl Bidder {
+    /// The local account, `None` for remote bidders.
+    pub fn local(&self) -> Option<&Addr> {
+        match self {
+            Bidder::Local(addr) => Some(addr),
+            Bidder::Remote(_) => None,
+        }
+    }
 }
 
-///////////////////////////
-/// Keys Definitions   ///
+/// Remote bidders go by `<channel id>/<address>`, which no local address can look like.
+impl fmt::Display for Bidder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bidder::Local(addr) => write!(f, "{addr}"),
+            Bidder::Remote(remote) => write!(f, "{}/{}", remote.channel_id, remote.address),
+        }
+    }
+}
+
+impl FromStr for Bidder {
+    type Err = StdError;
+
+    fn from_str(s: &str) -> StdResult<Self> {
+        if s.is_empty() {
+            return Err(StdError::generic_err("empty bidder"));
+        }
+
+        Ok(match s.split_once('/') {
+            Some((channel_id, address)) => Bidder::Remote(RemoteBidder {
+                channel_id: channel_id.to_string(),
+                address: address.to_string(),
+            }),
+            None => Bidder::Local(Addr::unchecked(s)),
+        })
+    }
+}
+
+impl From<Addr> for Bidder {
+    fn from(addr: Addr) -> Self {
+        Bidder::Local(addr)
+    }
+}
+
+/// The sender of an ICS-20 transfer on the other end of `channel_id`.
+#[derive(PartialEq, Eq, Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct RemoteBidder {
+    pub channel_id: String,
+    pub address: String,
+}
+
+/// The records `ExecuteMsg::Reindex` saves again, in the order it goes through them.
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ReindexStep {
+    Auctions { start_after: Option<AuctionId> },
+    BidItems { start_after: Option<BidItemKey> },
+    Bids { start_after: Option<BidKey> },
+}
+
+/// An ICS-20 channel of the contract. `outstanding` is the bidding denom sent over it that hasn't
+/// come back yet, it's still in the contract's balance and the most the channel's packets can bid.
+#[derive(PartialEq, Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct IbcChannelState {
+    pub counterparty: IbcEndpoint,
+    pub outstanding: Uint128,
+    pub open: bool,
+}
+
+/// A refund transfer of `amount` to `receiver` over `channel_id`.
+#[derive(PartialEq, Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct RemoteSettlement {
+    pub channel_id: String,
+    pub receiver: String,
+    pub amount: Coin,
+}
+
+/// Bids of a bidder that haven't been settled yet, checked against the per bidder caps.
+#[derive(PartialEq, Serialize, Deserialize, JsonSchema, Debug, Clone, Default)]
+pub struct BidderStats {
+    pub active_bids: u32,
+    pub escrowed: Uint128,
+}
+
+pub struct AuctionIndexes<'a> {
+    pub status: MultiIndex<'a, String, Auction, AuctionId>,
+}
+
+impl IndexList<Auction> for AuctionIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Auction>> + '_> {
+        let v: Vec<&dyn Index<Auction>> = vec![&self.status];
+        Box::new(v.into_iter())
+    }
+}
+
+pub struct BidItemIndexes<'a> {
+    pub status: MultiIndex<'a, String, BidItem, BidItemKey>,
+}
+
+impl IndexList<BidItem> for BidItemIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<BidItem>> + '_> {
+        let v: Vec<&dyn Index<BidItem>> = vec![&self.status];
+        Box::new(v.into_iter())
+    }
+}
+
+pub struct BidIndexes<'a> {
+    pub bidder: MultiIndex<'a, String, Bid, BidKey>,
+}
+
+impl IndexList<Bid> for BidIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Bid>> + '_> {
+        let v: Vec<&dyn Index<Bid>> = vec![&self.bidder];
+        Box::new(v.into_iter())
+    }
+}
+
+//////////////////////////
+//  Keys Definitions    //
 //////////////////////////
 
 #[inline]
@@ -111,7 +442,7 @@ impl<'a> PrimaryKey<'a> for BidItemKey {
     type Suffix = BidItemId;
     type SuperSuffix = Self;
 
-    fn key(&self) -> Vec<Key> {
+    fn key(&self) -> Vec<Key<'_>> {
         let mut keys = self.auction_id.key();
         keys.extend(self.bid_item_id.key());
         keys
@@ -158,7 +489,7 @@ impl<'a> PrimaryKey<'a> for BidKey {
     type Suffix = BidId;
     type SuperSuffix = Self;
 
-    fn key(&self) -> Vec<Key> {
+    fn key(&self) -> Vec<Key<'_>> {
         let mut keys = self.bid_item_id.key();
         keys.extend(self.bid_id.key());
         keys
@@ -2,12 +2,34 @@ const _INITIAL_BALANCE: u128 = 200_000;
 const _DENOM: &str = "eth";
 
 #[cfg(test)]
-mod tests {
-    use cosmwasm_std::{coins, Addr, Coin};
-    use cw_multi_test::{App, ContractWrapper, Executor};
+pub mod suite;
+
+#[cfg(test)]
+mod invariants;
+
+#[cfg(test)]
+mod ibc;
+
+#[cfg(test)]
+pub mod oracle;
 
-    use crate::{msg::{ExecuteMsg, InstantiateMsg, QueryMsg}, state::{Auction, AuctionId, AuctionStatus, BidItem, BidItemId, BidItemKey}, tests::{_DENOM, _INITIAL_BALANCE}};
-    use crate::contract::{execute, instantiate, query};
+#[cfg(test)]
+pub mod transfer;
+
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod tests {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{coins, from_json, to_json_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Order, Reply, ReplyOn, Response, StdError, StdResult, SubMsg, SubMsgResult, Timestamp, Uint128, Uint64};
+    use cw_storage_plus::{Item, Map};
+    use cw_multi_test::{App, AppResponse, ContractWrapper, Executor};
+
+    use crate::{msg::{AuctionProgress, BidItemInput, BidItemPricesResp, BidderBidResp, ConfigUpdate, CrankStatusResp, ExecuteMsg, HookExecuteMsg, HookMsg, InstantiateMsg, MigrateMsg, PagedResp, QueryMsg, QueuedAuctionResp, StateIssue, SudoMsg, VerifierQueryMsg, VerifyCursor, VerifyStateResp, VerifyStep}, state::{Auction, AuctionId, AuctionStatus, Bid, BidId, BidItem, BidItemAttribute, BidItemId, BidItemKey, BidItemStatus, BidKey, BidderAccess, BidderStats, Config, OracleConfig, PauseScope, PayoutMode, Price, ADMIN, AUCTIONS_CRANK_QUEUE_COUNT, BID_ITEMS_TO_AUCTIONS, CONFIG, WINNING_BIDS}, tests::{_DENOM, _INITIAL_BALANCE}};
+    use crate::contract::{execute, instantiate, migrate, query, reply};
+    use crate::error::ContractError;
+    use crate::tests::oracle::{self, MockOracleMsg};
+    use crate::tests::suite::{event_attr, BiddingSuite};
 
     #[test]
     fn instantiation() {
@@ -63,12 +85,12 @@ mod tests {
             )
             .unwrap();
 
-        let bid_items= vec![ "My first bid item".to_string(), "My second bid item".to_string() ];
+        let bid_items= vec![ BidItemInput::new("My first bid item"), BidItemInput::new("My second bid item") ];
 
         let resp = app.execute_contract(
             Addr::unchecked("owner"),
             addr.clone(),
-            &ExecuteMsg::CreateAuction { name: "TestAuction #1".to_string(), bid_items: bid_items },
+            &ExecuteMsg::CreateAuction { name: "TestAuction #1".to_string(), description: None, terms: None, ends_at: None, payout_mode: None, owner: None, bid_items },
             &[],
         )
         .unwrap();
@@ -115,12 +137,12 @@ mod tests {
             )
             .unwrap();
 
-        let bid_items= vec![ "My first bid item".to_string(), "My second bid item".to_string() ];
+        let bid_items= vec![ BidItemInput::new("My first bid item"), BidItemInput::new("My second bid item") ];
 
         let resp = app.execute_contract(
             Addr::unchecked("owner"),
             addr.clone(),
-            &ExecuteMsg::CreateAuction { name: "TestAuction #1".to_string(), bid_items: bid_items },
+            &ExecuteMsg::CreateAuction { name: "TestAuction #1".to_string(), description: None, terms: None, ends_at: None, payout_mode: None, owner: None, bid_items },
             &[],
         )
         .unwrap();
@@ -192,17 +214,17 @@ mod tests {
         });
 
         let bid_items= vec![ 
-            "TA1 1st bid item".to_string(),
-            "TA1 2nd bid item".to_string(),
-            "TA1 3rd bid item".to_string(),
-            "TA1 4th bid item".to_string(),
-            "TA1 5th bid item".to_string(),
+            BidItemInput::new("TA1 1st bid item"),
+            BidItemInput::new("TA1 2nd bid item"),
+            BidItemInput::new("TA1 3rd bid item"),
+            BidItemInput::new("TA1 4th bid item"),
+            BidItemInput::new("TA1 5th bid item"),
         ];
 
         let resp = app.execute_contract(
             Addr::unchecked("owner"),
             addr.clone(),
-            &ExecuteMsg::CreateAuction { name: "TestAuction #1".to_string(), bid_items: bid_items },
+            &ExecuteMsg::CreateAuction { name: "TestAuction #1".to_string(), description: None, terms: None, ends_at: None, payout_mode: None, owner: None, bid_items },
             &[],
         )
         .unwrap();
@@ -219,22 +241,23 @@ mod tests {
         let auction_id_u32_first = auction_id.parse::<u32>().unwrap();
 
         let bid_items= vec![ 
-            "TA1 6th bid item".to_string(),
-            "TA1 7th bid item".to_string(),
+            BidItemInput::new("TA1 6th bid item"),
+            BidItemInput::new("TA1 7th bid item"),
         ];
 
         app.execute_contract(
             Addr::unchecked("owner"),
             addr.clone(),
-            &ExecuteMsg::AddBidItems { auction_id: AuctionId(auction_id_u32_first), bid_items: bid_items },
+            &ExecuteMsg::AddBidItems { auction_id: AuctionId(auction_id_u32_first), bid_items },
             &[],
         )
         .unwrap();
 
         let resp: Vec<(BidItemId, BidItem)> = app
             .wrap()
-            .query_wasm_smart(&addr, &QueryMsg::BidItemsByAuctionId { auction_id: AuctionId(auction_id_u32_first) })
-            .unwrap();
+            .query_wasm_smart::<PagedResp<BidItemId, BidItem>>(&addr, &QueryMsg::BidItemsByAuctionId { auction_id: AuctionId(auction_id_u32_first), start_after: None, start_before: None, limit: None, order: None })
+            .unwrap()
+            .items;
 
         assert_eq!(resp.len(), 7);
 
@@ -243,7 +266,7 @@ mod tests {
                 app.execute_contract(
                     Addr::unchecked("user"),
                     addr.clone(),
-                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0 },
+                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0, amount: None },
                     &coins(4, "eth"),
                 )
                 .unwrap();
@@ -251,7 +274,7 @@ mod tests {
                 app.execute_contract(
                     Addr::unchecked("user"),
                     addr.clone(),
-                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0 },
+                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0, amount: None },
                     &coins(1, "eth"),
                 )
                 .unwrap();
@@ -260,7 +283,7 @@ mod tests {
                 app.execute_contract(
                     Addr::unchecked("user"),
                     addr.clone(),
-                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0 },
+                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0, amount: None },
                     &coins(8, "eth"),
                 )
                 .unwrap();
@@ -268,7 +291,7 @@ mod tests {
                 app.execute_contract(
                     Addr::unchecked("user"),
                     addr.clone(),
-                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0 },
+                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0, amount: None },
                     &coins(10, "eth"),
                 )
                 .unwrap();
@@ -276,7 +299,7 @@ mod tests {
                 app.execute_contract(
                     Addr::unchecked("user"),
                     addr.clone(),
-                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0 },
+                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0, amount: None },
                     &coins(16, "eth"),
                 )
                 .unwrap();
@@ -285,7 +308,7 @@ mod tests {
                 app.execute_contract(
                     Addr::unchecked("user"),
                     addr.clone(),
-                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0 },
+                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0, amount: None },
                     &coins(36, "eth"),
                 )
                 .unwrap();
@@ -293,7 +316,7 @@ mod tests {
                 app.execute_contract(
                     Addr::unchecked("user"),
                     addr.clone(),
-                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0 },
+                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0, amount: None },
                     &coins(35, "eth"),
                 )
                 .unwrap();
@@ -301,7 +324,7 @@ mod tests {
                 app.execute_contract(
                     Addr::unchecked("user"),
                     addr.clone(),
-                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0 },
+                    &ExecuteMsg::PlaceBid { bid_item_id: bid_item.0, amount: None },
                     &coins(5, "eth"),
                 )
                 .unwrap();
@@ -326,13 +349,22 @@ mod tests {
 
         assert_eq!(auctions_crank_queue_count, "1");
 
+        let resp: CrankStatusResp = app
+            .wrap()
+            .query_wasm_smart(&addr, &QueryMsg::CrankStatus { start_after: None, limit: None })
+            .unwrap();
+
+        assert_eq!(resp.queue_length, 1);
+        assert_eq!(resp.queued_auctions, vec![QueuedAuctionResp { auction_id: AuctionId(auction_id_u32_first), pending_bid_items: 7 }]);
+        assert_eq!(resp.estimated_crank_calls, 3);
+
         // Total Bids -> TestAuction #1 { BidItem1, BidItem2, BidItem3, BidItem4, BidItem5, BidItem6, BidItem7 }
 
         // Will Process TestAuction #1 { BidItem1, BidItem2, BidItem3 }
         app.execute_contract(
             Addr::unchecked("owner"),
             addr.clone(),
-            &ExecuteMsg::AdvanceCrank {  },
+            &ExecuteMsg::AdvanceCrank { max_items: None },
             &[],
         )
         .unwrap();
@@ -341,7 +373,7 @@ mod tests {
         app.execute_contract(
             Addr::unchecked("user"),
             addr.clone(),
-            &ExecuteMsg::AdvanceCrank {  },
+            &ExecuteMsg::AdvanceCrank { max_items: None },
             &[],
         )
         .unwrap();
@@ -350,20 +382,1510 @@ mod tests {
         app.execute_contract(
             Addr::unchecked("owner"),
             addr.clone(),
-            &ExecuteMsg::AdvanceCrank {  },
+            &ExecuteMsg::AdvanceCrank { max_items: None },
             &[],
         )
         .unwrap();
 
-        // Will process nothing0
-        app.execute_contract(
+        let resp: CrankStatusResp = app
+            .wrap()
+            .query_wasm_smart(&addr, &QueryMsg::CrankStatus { start_after: None, limit: None })
+            .unwrap();
+
+        assert_eq!(resp.queue_length, 0);
+        assert!(resp.queued_auctions.is_empty());
+        assert_eq!(resp.estimated_crank_calls, 0);
+
+        // Will process nothing, so it's rejected
+        let err = app.execute_contract(
             Addr::unchecked("user"),
             addr.clone(),
-            &ExecuteMsg::AdvanceCrank {  },
+            &ExecuteMsg::AdvanceCrank { max_items: None },
             &[],
         )
-        .unwrap();
+        .unwrap_err();
+
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NothingToCrank);
+
+    }
+
+    #[test]
+    fn cancel_auction() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user", _INITIAL_BALANCE)
+            .build();
+
+        let user = suite.addr("user");
+        let owner = suite.admin.clone();
+
+        let (auction_id, bid_items) = suite.create_auction(&["My first bid item", "My second bid item"]).unwrap();
+
+        for bid_item_id in &bid_items {
+            suite.bid(&user, *bid_item_id, 10).unwrap();
+        }
+
+        suite.assert_balance(&user, _INITIAL_BALANCE - 20);
+
+        // Only the admin or the auction owner can cancel
+        suite.execute(&user, &ExecuteMsg::CancelAuction { id: auction_id }, &[]).unwrap_err();
+        suite.execute(&owner, &ExecuteMsg::CancelAuction { id: auction_id }, &[]).unwrap();
+
+        // Cancelled auctions don't accept bids
+        suite.bid(&user, bid_items[0], 10).unwrap_err();
+
+        suite.execute(&user, &ExecuteMsg::AdvanceCrank { max_items: None }, &[]).unwrap();
+
+        suite.assert_balance(&user, _INITIAL_BALANCE);
+        suite.assert_auction_state(auction_id, AuctionStatus::Cancelled);
+
+        for bid_item_id in bid_items {
+            suite.assert_bid_item(bid_item_id, BidItemStatus::Cancelled, None);
+        }
+    }
+
+    #[test]
+    fn update_and_remove_bid_items() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user", _INITIAL_BALANCE)
+            .build();
+
+        let user = suite.addr("user");
+        let owner = suite.admin.clone();
+
+        let (auction_id, bid_items) = suite.create_auction(&["My frist bid item", "My second bid item"]).unwrap();
+        let (first_item, second_item) = (bid_items[0], bid_items[1]);
+
+        // Items without bids can be renamed
+        suite.execute(&owner, &ExecuteMsg::UpdateBidItem { bid_item_id: first_item, bid_item: BidItemInput::new("My first bid item") }, &[]).unwrap();
+        assert_eq!(suite.bid_item(first_item).name, "My first bid item");
+
+        suite.bid(&user, second_item, 10).unwrap();
+
+        // Items with bids can't be changed while the auction is active
+        let err = suite.execute(&owner, &ExecuteMsg::RemoveBidItem { bid_item_id: second_item }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::BidItemNonUpdateable);
+
+        suite.execute(&owner, &ExecuteMsg::RemoveBidItem { bid_item_id: first_item }, &[]).unwrap();
+        assert_eq!(suite.auction(auction_id).available_bid_items.u64(), 1);
+
+        // Once suspended, the prices of items with bids still can't move
+        suite.set_auction_state(auction_id, AuctionStatus::Suspended).unwrap();
+        let repriced = BidItemInput { starting_price: Some(Price::Native(Uint128::new(5))), ..BidItemInput::new("My second bid item") };
+        let err = suite.execute(&owner, &ExecuteMsg::UpdateBidItem { bid_item_id: second_item, bid_item: repriced }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::BidItemPricesLocked);
+
+        suite.execute(&owner, &ExecuteMsg::UpdateBidItem { bid_item_id: second_item, bid_item: BidItemInput::new("My 2nd bid item") }, &[]).unwrap();
+        assert_eq!(suite.bid_item(second_item).name, "My 2nd bid item");
+
+        // Items with bids are cancelled on removal, the crank refunds their bids and deletes them
+        let resp = suite.execute(&owner, &ExecuteMsg::RemoveBidItem { bid_item_id: second_item }, &[]).unwrap();
+        assert_eq!(event_attr(&resp, "bid_item_cancelled", "bid_item_id"), vec![second_item.to_string()]);
+        suite.assert_bid_item(second_item, BidItemStatus::Cancelled, None);
+        suite.assert_balance(&user, _INITIAL_BALANCE - 10);
+
+        let err = suite.execute(&owner, &ExecuteMsg::RemoveBidItem { bid_item_id: second_item }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::InvalidBidItemId);
+        assert!(suite.verify_state(Some(auction_id), None).iter().all(|resp| resp.issues.is_empty()));
+
+        let resp = suite.crank(None).unwrap();
+        assert_eq!(event_attr(&resp, "bid_item_removed", "bid_item_id"), vec![second_item.to_string()]);
+
+        suite.assert_balance(&user, _INITIAL_BALANCE);
+        let auction = suite.auction(auction_id);
+        assert_eq!((auction.available_bid_items.u64(), auction.total_bids.u64(), auction.total_coins.u128()), (0, 0, 0));
+
+        let resp: PagedResp<BidItemId, BidItem> = suite.query(&QueryMsg::BidItemsByAuctionId { auction_id, start_after: None, start_before: None, limit: None, order: None });
+        assert!(resp.items.is_empty());
+
+        suite.app
+            .wrap()
+            .query_wasm_smart::<BidItem>(&suite.contract, &QueryMsg::BidItem { id: second_item })
+            .unwrap_err();
+    }
+
+    #[test]
+    fn removed_bid_items_are_refunded_in_chunks() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user", _INITIAL_BALANCE)
+            .with_config(ConfigUpdate { crank_max_bids: Some(2), ..Default::default() })
+            .build();
+
+        let user = suite.addr("user");
+        let owner = suite.admin.clone();
+
+        let (auction_id, bid_items) = suite.create_auction(&["Painting", "Sculpture"]).unwrap();
+        for amount in 1..=5 {
+            suite.bid(&user, bid_items[0], amount * 10).unwrap();
+        }
+
+        suite.set_auction_state(auction_id, AuctionStatus::Suspended).unwrap();
+        suite.execute(&owner, &ExecuteMsg::RemoveBidItem { bid_item_id: bid_items[0] }, &[]).unwrap();
+
+        // Bidding on a removed item fails even once its auction is active again
+        suite.set_auction_state(auction_id, AuctionStatus::Active).unwrap();
+        let err = suite.bid(&user, bid_items[0], 100).unwrap_err();
+        assert_eq!(err, ContractError::InvalidBidItemId);
+
+        // Two bids per call, the item is deleted with the last chunk
+        for remaining in [3, 1] {
+            suite.crank(None).unwrap();
+            assert_eq!(suite.bid_item(bid_items[0]).total_bids.u64(), remaining);
+            assert!(suite.verify_state(Some(auction_id), None).iter().all(|resp| resp.issues.is_empty()));
+        }
+
+        suite.crank(None).unwrap();
+        suite.assert_balance(&user, _INITIAL_BALANCE);
+        assert_eq!(suite.auction(auction_id).available_bid_items.u64(), 1);
+        assert_eq!(suite.crank(None).unwrap_err(), ContractError::NothingToCrank);
+    }
+
+    #[test]
+    fn bid_item_metadata() {
+        let mut suite = BiddingSuite::builder().build();
+        let owner = suite.admin.clone();
+
+        let bid_item = BidItemInput {
+            name: "Painting".to_string(),
+            description: Some("Oil on canvas".to_string()),
+            media_uris: vec!["ipfs://painting.png".to_string()],
+            category: Some("art".to_string()),
+            attributes: vec![BidItemAttribute { key: "year".to_string(), value: "1889".to_string() }],
+            starting_price: None,
+            reserve_price: None,
+        };
+
+        let msg = ExecuteMsg::CreateAuction {
+            name: "TestAuction #1".to_string(),
+            description: Some("Spring art sale".to_string()),
+            terms: Some("No returns".to_string()),
+            ends_at: None,
+            payout_mode: None,
+            owner: None,
+            bid_items: vec![bid_item.clone()],
+        };
+
+        let (auction_id, bid_items) = suite.create_auction_from(&owner, &msg).unwrap();
+
+        let auction = suite.auction(auction_id);
+        assert_eq!(auction.description, Some("Spring art sale".to_string()));
+        assert_eq!(auction.terms, Some("No returns".to_string()));
+
+        let resp = suite.bid_item(bid_items[0]);
+        assert_eq!(resp.description, bid_item.description);
+        assert_eq!(resp.media_uris, bid_item.media_uris);
+        assert_eq!(resp.category, bid_item.category);
+        assert_eq!(resp.attributes, bid_item.attributes);
+
+        let too_many_uris = BidItemInput {
+            media_uris: vec!["ipfs://painting.png".to_string(); 11],
+            ..bid_item
+        };
+
+        let err = suite.execute(&owner, &ExecuteMsg::AddBidItems { auction_id, bid_items: vec![too_many_uris] }, &[]).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMetadata { .. }));
+
+        let err = suite.execute(&owner, &ExecuteMsg::AddBidItems { auction_id, bid_items: vec![BidItemInput::new("x".repeat(129))] }, &[]).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMetadata { .. }));
+    }
+
+    #[test]
+    fn bids_by_bidder() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user", _INITIAL_BALANCE)
+            .with_account("other_user", _INITIAL_BALANCE)
+            .build();
+
+        let user = suite.addr("user");
+
+        let (auction_id, bid_items) = suite.create_auction(&["My first bid item", "My second bid item"]).unwrap();
+
+        let bids = [
+            ("user", bid_items[0], 5),
+            ("other_user", bid_items[0], 7),
+            ("user", bid_items[1], 3),
+            ("other_user", bid_items[1], 2),
+        ];
+
+        for (bidder, bid_item_id, amount) in bids {
+            let bidder = suite.addr(bidder);
+            suite.bid(&bidder, bid_item_id, amount).unwrap();
+        }
+
+        let resp: Vec<BidderBidResp> = suite
+            .query::<PagedResp<BidKey, BidderBidResp>>(&QueryMsg::BidsByBidder { bidder: user.clone().into(), start_after: None, start_before: None, limit: None, order: None })
+            .items
+            .into_iter()
+            .map(|bid| bid.1)
+            .collect();
+
+        assert_eq!(resp.len(), 2);
+        assert_eq!(resp[0].auction_id, auction_id);
+        assert_eq!((resp[0].bid_item_id, resp[0].amount.u128(), resp[0].winning), (bid_items[0], 5, false));
+        assert_eq!((resp[1].bid_item_id, resp[1].amount.u128(), resp[1].winning), (bid_items[1], 3, true));
+
+        let start_after = BidKey { bid_item_id: resp[0].bid_item_id, bid_id: resp[0].bid_id };
+
+        let resp: PagedResp<BidKey, BidderBidResp> = suite.query(&QueryMsg::BidsByBidder { bidder: user.into(), start_after: Some(start_after), start_before: None, limit: Some(1), order: None });
+
+        assert_eq!(resp.items.len(), 1);
+        assert_eq!(resp.items[0].1.bid_item_id, bid_items[1]);
+        assert_eq!(resp.next, None);
+    }
+
+    #[test]
+    fn bids_by_bid_item_and_winning_bid() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user", _INITIAL_BALANCE)
+            .build();
+
+        let user = suite.addr("user");
+
+        let (_, bid_items) = suite.create_auction(&["My first bid item"]).unwrap();
+        let bid_item_id = bid_items[0];
+
+        let resp: Option<(BidId, Bid)> = suite.query(&QueryMsg::WinningBid { bid_item_id });
+        assert_eq!(resp, None);
+
+        for amount in [4, 9, 6] {
+            suite.bid(&user, bid_item_id, amount).unwrap();
+        }
+
+        let resp: Option<(BidId, Bid)> = suite.query(&QueryMsg::WinningBid { bid_item_id });
+        assert_eq!(resp.unwrap().1.amount.u128(), 9);
+
+        let resp: Vec<(BidId, Bid)> = suite
+            .query::<PagedResp<BidId, Bid>>(&QueryMsg::BidsByBidItem { bid_item_id, start_after: None, start_before: None, limit: None, order: None })
+            .items;
+
+        let amounts: Vec<u128> = resp.iter().map(|bid| bid.1.amount.u128()).collect();
+        assert_eq!(amounts, vec![4, 9, 6]);
+
+        let resp: Vec<(BidId, Bid)> = suite
+            .query::<PagedResp<BidId, Bid>>(&QueryMsg::BidsByBidItem { bid_item_id, start_after: None, start_before: Some(resp[2].0), limit: Some(1), order: Some(Order::Descending) })
+            .items;
+
+        assert_eq!(resp.len(), 1);
+        assert_eq!(resp[0].1.amount.u128(), 9);
+
+        suite.app
+            .wrap()
+            .query_wasm_smart::<Option<(BidId, Bid)>>(&suite.contract, &QueryMsg::WinningBid { bid_item_id: BidItemId(100) })
+            .unwrap_err();
+    }
+
+    #[test]
+    fn crank_rewards() {
+        // 3 per settled item with bids, and 10% of every winning bid goes to the pool
+        let mut suite = BiddingSuite::builder()
+            .with_account("owner", _INITIAL_BALANCE)
+            .with_account("user", _INITIAL_BALANCE)
+            .with_config(ConfigUpdate { crank_reward: Some(3u128.into()), crank_reward_fee_bps: Some(1_000), ..Default::default() })
+            .build();
+
+        let owner = suite.admin.clone();
+        let user = suite.addr("user");
+        let keeper = suite.addr("keeper");
+
+        suite.execute(&user, &ExecuteMsg::FundCrankRewards {}, &coins(5, _DENOM)).unwrap_err();
+        suite.execute(&owner, &ExecuteMsg::FundCrankRewards {}, &coins(5, _DENOM)).unwrap();
+
+        let (auction_id, bid_items) = suite.create_auction(&["My first bid item", "My second bid item"]).unwrap();
+
+        suite.bid(&user, bid_items[0], 20).unwrap();
+
+        // Nothing is queued yet
+        let err = suite.execute(&keeper, &ExecuteMsg::AdvanceCrank { max_items: None }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::NothingToCrank);
 
+        suite.close_auction(auction_id).unwrap();
+        suite.execute(&keeper, &ExecuteMsg::AdvanceCrank { max_items: None }, &[]).unwrap();
+
+        // The pool had 5 + 2 of fees, only the item with a bid pays 3
+        suite.assert_balance(&keeper, 3);
+        suite.assert_balance(&owner, _INITIAL_BALANCE - 5 + 18);
+
+        let resp = suite.crank_status();
+        assert_eq!(resp.reward_per_bid_item.u128(), 3);
+        assert_eq!(resp.reward_pool.u128(), 4);
+
+        suite.assert_balance(&suite.contract.clone(), 4);
+
+        // An auction without bids is settled for free
+        let (auction_id, _) = suite.create_auction(&["My third bid item", "My fourth bid item"]).unwrap();
+        suite.close_auction(auction_id).unwrap();
+
+        let resp = suite.execute(&keeper, &ExecuteMsg::AdvanceCrank { max_items: None }, &[]).unwrap();
+        let wasm = resp.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+        assert!(wasm.attributes.iter().any(|attr| attr.key == "crank_reward" && attr.value == "0"));
+
+        suite.assert_auction_state(auction_id, AuctionStatus::Completed);
+        suite.assert_balance(&keeper, 3);
+        assert_eq!(suite.crank_status().reward_pool.u128(), 4);
+    }
+
+    #[test]
+    fn crank_batching() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user0", _INITIAL_BALANCE)
+            .with_account("user1", _INITIAL_BALANCE)
+            .with_account("user2", _INITIAL_BALANCE)
+            .with_account("user3", _INITIAL_BALANCE)
+            .build();
+
+        let users: Vec<Addr> = (0..4).map(|num| suite.addr(&format!("user{num}"))).collect();
+        let owner = suite.admin.clone();
+
+        // Batch size can't go over the maximum
+        suite.execute(&owner, &ExecuteMsg::UpdateConfig(ConfigUpdate { crank_batch_size: Some(31), ..Default::default() }), &[]).unwrap_err();
+        suite.execute(&owner, &ExecuteMsg::UpdateConfig(ConfigUpdate { crank_batch_size: Some(2), crank_max_bids: Some(3), ..Default::default() }), &[]).unwrap();
+
+        let (auction_id, bid_items) = suite.create_auction(&["Bid item #0", "Bid item #1", "Bid item #2", "Bid item #3", "Bid item #4"]).unwrap();
+
+        for (num, user) in users.iter().enumerate() {
+            suite.bid(user, bid_items[0], 10 * (num as u128 + 1)).unwrap();
+        }
+
+        suite.close_auction(auction_id).unwrap();
+
+        // Only 3 of the 4 bids fit in the first call, the item stays pending
+        suite.crank(None).unwrap();
+
+        for user in &users[..3] {
+            suite.assert_balance(user, _INITIAL_BALANCE);
+        }
+
+        suite.assert_bid_item(bid_items[0], BidItemStatus::Active, None);
+
+        // The remaining bid settles the first item, and the call stops at the requested single item
+        suite.crank(Some(1)).unwrap();
+
+        suite.assert_bid_item(bid_items[0], BidItemStatus::Completed, Some(&users[3]));
+        suite.assert_balance(&owner, 40);
+
+        let resp = suite.crank_status();
+
+        assert_eq!(resp.queued_auctions, vec![QueuedAuctionResp { auction_id, pending_bid_items: 4 }]);
+        assert_eq!(resp.estimated_crank_calls, 2);
+
+        // Requests over the maximum are capped, which is still enough for everything left
+        suite.crank(Some(100)).unwrap();
+
+        suite.assert_auction_state(auction_id, AuctionStatus::Completed);
+        assert_eq!(suite.crank_status().queue_length, 0);
+        assert_eq!(suite.crank_until_idle(), 0);
+    }
+
+    #[test]
+    fn crank_status_pages() {
+        let mut suite = BiddingSuite::builder().build();
+
+        let mut auction_ids = vec![];
+        for _ in 0..3 {
+            let (auction_id, _) = suite.create_auction(&["Painting", "Sculpture"]).unwrap();
+            suite.close_auction(auction_id).unwrap();
+            auction_ids.push(auction_id);
+        }
+
+        // The queue length counts every queued auction, the estimate only the auctions of the page
+        let resp = suite.crank_status_page(None, Some(2));
+        assert_eq!(resp.queue_length, 3);
+        assert_eq!(resp.queued_auctions.iter().map(|auction| auction.auction_id).collect::<Vec<_>>(), auction_ids[..2]);
+        assert_eq!(resp.next, Some(auction_ids[1]));
+        assert_eq!(resp.estimated_crank_calls, 2);
+
+        let resp = suite.crank_status_page(resp.next, Some(2));
+        assert_eq!(resp.queued_auctions, vec![QueuedAuctionResp { auction_id: auction_ids[2], pending_bid_items: 2 }]);
+        assert_eq!(resp.next, None);
+    }
+
+    #[test]
+    fn sudo_settlement() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user", _INITIAL_BALANCE)
+            .build();
+
+        let user = suite.addr("user");
+        let owner = suite.admin.clone();
+        let now = suite.app.block_info().time;
+
+        // End times must be in the future
+        suite.create_auction_with(&[], Some(now), None).unwrap_err();
+
+        let (first, bid_items) = suite.create_auction_with(&["My first bid item"], Some(now.plus_seconds(60)), None).unwrap();
+        let (second, _) = suite.create_auction_with(&["My first bid item"], Some(now.plus_seconds(3_600)), None).unwrap();
+
+        suite.bid(&user, bid_items[0], 50).unwrap();
+
+        // An empty end-blocker run is fine
+        suite.sudo(&SudoMsg::AdvanceCrank { max_items: None }).unwrap();
+
+        suite.app.update_block(|block| block.time = block.time.plus_seconds(60));
+
+        let err = suite.bid(&user, bid_items[0], 60).unwrap_err();
+        assert_eq!(err, ContractError::AuctionExpired);
+
+        suite.sudo(&SudoMsg::CloseExpiredAuctions {}).unwrap();
+
+        suite.assert_auction_state(first, AuctionStatus::Completed);
+        suite.assert_bid_item(bid_items[0], BidItemStatus::Completed, Some(&user));
+        suite.assert_balance(&owner, 50);
+
+        // The second auction hasn't ended yet
+        suite.assert_auction_state(second, AuctionStatus::Active);
+    }
+
+    #[test]
+    fn suspended_auctions_close_once_reactivated() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user", _INITIAL_BALANCE)
+            .build();
+
+        let user = suite.addr("user");
+        let now = suite.app.block_info().time;
+
+        let (auction_id, bid_items) = suite.create_auction_with(&["My first bid item"], Some(now.plus_seconds(60)), None).unwrap();
+        suite.bid(&user, bid_items[0], 50).unwrap();
+        suite.set_auction_state(auction_id, AuctionStatus::Suspended).unwrap();
+
+        // The auction expires while it's suspended, so it isn't closed yet
+        suite.app.update_block(|block| block.time = block.time.plus_seconds(60));
+        suite.sudo(&SudoMsg::CloseExpiredAuctions {}).unwrap();
+        suite.assert_auction_state(auction_id, AuctionStatus::Suspended);
+
+        suite.set_auction_state(auction_id, AuctionStatus::Active).unwrap();
+        suite.sudo(&SudoMsg::CloseExpiredAuctions {}).unwrap();
+
+        suite.assert_auction_state(auction_id, AuctionStatus::Completed);
+        suite.assert_bid_item(bid_items[0], BidItemStatus::Completed, Some(&user));
+    }
+
+    #[test]
+    fn pull_payouts() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user0", _INITIAL_BALANCE)
+            .with_account("user1", _INITIAL_BALANCE)
+            .build();
+
+        let users: Vec<Addr> = (0..2).map(|num| suite.addr(&format!("user{num}"))).collect();
+        let owner = suite.admin.clone();
+
+        let (auction_id, bid_items) = suite.create_auction_with(&["My first bid item"], None, Some(PayoutMode::Pull)).unwrap();
+
+        for (num, user) in users.iter().enumerate() {
+            suite.bid(user, bid_items[0], 10 * (num as u128 + 1)).unwrap();
+        }
+
+        suite.close_auction(auction_id).unwrap();
+        assert_eq!(suite.crank_until_idle(), 1);
+
+        // Nothing was sent, the funds stay in the contract until claimed
+        suite.assert_balance(&users[0], _INITIAL_BALANCE - 10);
+        suite.assert_balance(&suite.contract.clone(), 30);
+
+        let claimable: Vec<Coin> = suite.query(&QueryMsg::Claimable { address: users[0].clone() });
+        assert_eq!(claimable, coins(10, _DENOM));
+
+        let claimable: Vec<Coin> = suite.query(&QueryMsg::Claimable { address: owner.clone() });
+        assert_eq!(claimable, coins(20, _DENOM));
+
+        suite.execute(&users[0], &ExecuteMsg::Claim {}, &[]).unwrap();
+        suite.assert_balance(&users[0], _INITIAL_BALANCE);
+
+        let err = suite.execute(&users[0], &ExecuteMsg::Claim {}, &[]).unwrap_err();
+        assert_eq!(err, ContractError::NothingToClaim);
+
+        // Only the requested denoms are claimed
+        let err = suite.execute(&owner, &ExecuteMsg::ClaimFor { denoms: vec!["btc".to_string()] }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::NothingToClaim);
+
+        suite.execute(&owner, &ExecuteMsg::ClaimFor { denoms: vec![_DENOM.to_string()] }, &[]).unwrap();
+
+        suite.assert_balance(&owner, 20);
+        suite.assert_balance(&suite.contract.clone(), 0);
+    }
+
+    #[test]
+    fn bidder_deposits() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user0", _INITIAL_BALANCE)
+            .with_account("user1", _INITIAL_BALANCE)
+            .build();
+
+        let users: Vec<Addr> = (0..2).map(|num| suite.addr(&format!("user{num}"))).collect();
+
+        let (auction_id, bid_items) = suite.create_auction(&["My first bid item"]).unwrap();
+
+        suite.execute(&users[0], &ExecuteMsg::Deposit {}, &coins(100, _DENOM)).unwrap();
+        suite.bid_from_deposit(&users[0], bid_items[0], 30).unwrap();
+
+        let err = suite.bid_from_deposit(&users[0], bid_items[0], 80).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientDeposit { available: 70u128.into() });
+
+        // Attaching funds and drawing from the deposit at once is ambiguous
+        suite.execute(&users[1], &ExecuteMsg::PlaceBid { bid_item_id: bid_items[0], amount: Some(40u128.into()) }, &coins(40, _DENOM)).unwrap_err();
+
+        suite.bid(&users[1], bid_items[0], 40).unwrap();
+
+        suite.close_auction(auction_id).unwrap();
+        suite.crank_until_idle();
+
+        // The losing bid went back into the deposit instead of the wallet
+        let deposit: Uint128 = suite.query(&QueryMsg::DepositBalance { address: users[0].clone() });
+        assert_eq!(deposit.u128(), 100);
+
+        suite.assert_balance(&users[0], _INITIAL_BALANCE - 100);
+
+        suite.execute(&users[0], &ExecuteMsg::Withdraw { amount: Some(60u128.into()) }, &[]).unwrap();
+        suite.execute(&users[0], &ExecuteMsg::Withdraw { amount: None }, &[]).unwrap();
+
+        suite.assert_balance(&users[0], _INITIAL_BALANCE);
+
+        let err = suite.execute(&users[0], &ExecuteMsg::Withdraw { amount: None }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientDeposit { available: Uint128::zero() });
+    }
+
+    #[test]
+    fn pause_scopes() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user", _INITIAL_BALANCE)
+            .build();
+
+        let owner = suite.admin.clone();
+        let guardian = suite.addr("guardian");
+        let user = suite.addr("user");
+
+        suite.execute(&owner, &ExecuteMsg::UpdateConfig(ConfigUpdate { guardian: Some(guardian.to_string()), ..Default::default() }), &[]).unwrap();
+
+        let (auction_id, bid_items) = suite.create_auction(&["My first bid item"]).unwrap();
+
+        let err = suite.execute(&user, &ExecuteMsg::Pause { scope: None }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized { sender: user.clone() });
+
+        suite.execute(&guardian, &ExecuteMsg::Pause { scope: Some(PauseScope::Bidding) }, &[]).unwrap();
+
+        let err = suite.bid(&user, bid_items[0], 10).unwrap_err();
+        assert_eq!(err, ContractError::Paused { scope: "bidding".to_string() });
+
+        // Other scopes keep working
+        suite.execute(&owner, &ExecuteMsg::AddBidItems { auction_id, bid_items: vec![ BidItemInput::new("My second bid item") ] }, &[]).unwrap();
+
+        suite.execute(&guardian, &ExecuteMsg::Pause { scope: None }, &[]).unwrap();
+
+        let paused: Vec<PauseScope> = suite.query(&QueryMsg::Paused {});
+        assert_eq!(paused, vec![PauseScope::Bidding, PauseScope::Listing, PauseScope::Settlement]);
+
+        let err = suite.create_auction(&[]).unwrap_err();
+        assert_eq!(err, ContractError::Paused { scope: "listing".to_string() });
+
+        let err = suite.execute(&user, &ExecuteMsg::AdvanceCrank { max_items: None }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::Paused { scope: "settlement".to_string() });
+
+        // Claims stay open, there's just nothing to claim here
+        let err = suite.execute(&user, &ExecuteMsg::Claim {}, &[]).unwrap_err();
+        assert_eq!(err, ContractError::NothingToClaim);
+
+        suite.execute(&owner, &ExecuteMsg::Unpause { scope: None }, &[]).unwrap();
+
+        suite.bid(&user, bid_items[0], 10).unwrap();
+
+        // A guardian can't be set and cleared at once, once cleared it can't pause anymore
+        let update = ConfigUpdate { guardian: Some(guardian.to_string()), clear_guardian: true, ..Default::default() };
+        suite.execute(&owner, &ExecuteMsg::UpdateConfig(update), &[]).unwrap_err();
+
+        suite.execute(&owner, &ExecuteMsg::UpdateConfig(ConfigUpdate { clear_guardian: true, ..Default::default() }), &[]).unwrap();
+        assert_eq!(suite.query::<Config>(&QueryMsg::Config {}).guardian, None);
+
+        let err = suite.execute(&guardian, &ExecuteMsg::Pause { scope: None }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized { sender: guardian });
+    }
+
+    #[test]
+    fn structured_events() {
+        // Attributes of the first event of the given type, without the contract address multi-test adds
+        fn attributes(resp: &AppResponse, ty: &str) -> Vec<(String, String)> {
+            resp.events
+                .iter()
+                .find(|ev| ev.ty == format!("wasm-{ty}"))
+                .unwrap()
+                .attributes
+                .iter()
+                .filter(|attr| attr.key != "_contract_address")
+                .map(|attr| (attr.key.clone(), attr.value.clone()))
+                .collect()
+        }
+
+        fn attr(key: &str, value: impl ToString) -> (String, String) {
+            (key.to_string(), value.to_string())
+        }
+
+        let mut suite = BiddingSuite::builder()
+            .with_account("user0", _INITIAL_BALANCE)
+            .with_account("user1", _INITIAL_BALANCE)
+            .build();
+
+        let owner = suite.admin.clone();
+        let users: Vec<Addr> = (0..2).map(|num| suite.addr(&format!("user{num}"))).collect();
+
+        let msg = ExecuteMsg::CreateAuction { name: "TestAuction #1".to_string(), description: None, terms: None, ends_at: None, payout_mode: None, owner: None, bid_items: vec![ BidItemInput::new("My first bid item") ] };
+        let resp = suite.execute(&owner, &msg, &[]).unwrap();
+
+        let auction_id = AuctionId(event_attr(&resp, "auction_created", "auction_id")[0].parse().unwrap());
+
+        assert_eq!(attributes(&resp, "auction_created"), vec![
+            attr("auction_id", auction_id),
+            attr("name", "TestAuction #1"),
+            attr("owner", &owner),
+            attr("bid_items", 1),
+            attr("payout_mode", "push"),
+        ]);
+
+        let bid_item_id = BidItemId(attributes(&resp, "bid_item_added")[1].1.parse::<u32>().unwrap());
+
+        assert_eq!(attributes(&resp, "bid_item_added"), vec![
+            attr("auction_id", auction_id),
+            attr("bid_item_id", bid_item_id),
+            attr("name", "My first bid item"),
+            attr("media_uris", "[]"),
+            attr("attributes", "[]"),
+        ]);
+
+        let mut bid_ids = vec![];
+
+        for (num, user) in users.iter().enumerate() {
+            let resp = suite.execute(user, &ExecuteMsg::PlaceBid { bid_item_id, amount: None }, &coins(10 * (num as u128 + 1), _DENOM)).unwrap();
+
+            let placed = attributes(&resp, "bid_placed");
+            bid_ids.push(placed[2].1.clone());
+
+            assert_eq!(placed, vec![
+                attr("auction_id", auction_id),
+                attr("bid_item_id", bid_item_id),
+                attr("bid_id", &bid_ids[num]),
+                attr("bidder", user),
+                attr("amount", 10 * (num + 1)),
+                attr("denom", _DENOM),
+                attr("from_deposit", false),
+            ]);
+
+            if num == 1 {
+                assert_eq!(attributes(&resp, "bid_outbid"), vec![
+                    attr("bid_item_id", bid_item_id),
+                    attr("bid_id", &bid_ids[0]),
+                    attr("bidder", &users[0]),
+                    attr("amount", 10),
+                    attr("outbid_by", &bid_ids[1]),
+                ]);
+            }
+        }
+
+        let resp = suite.close_auction(auction_id).unwrap();
+
+        assert_eq!(attributes(&resp, "auction_state_changed"), vec![
+            attr("auction_id", auction_id),
+            attr("previous", "active"),
+            attr("next", "pending_completion"),
+        ]);
+
+        let resp = suite.crank(None).unwrap();
+
+        let wasm = resp.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+        assert!(wasm.attributes.iter().any(|attr| attr.key == "action" && attr.value == "advance_crank"));
+
+        assert_eq!(attributes(&resp, "refund_issued"), vec![
+            attr("bid_item_id", bid_item_id),
+            attr("bid_id", &bid_ids[0]),
+            attr("bidder", &users[0]),
+            attr("amount", 10),
+            attr("denom", _DENOM),
+            attr("destination", "wallet"),
+        ]);
+
+        assert_eq!(attributes(&resp, "item_settled"), vec![
+            attr("auction_id", auction_id),
+            attr("bid_item_id", bid_item_id),
+            attr("status", "completed"),
+            attr("winner", &users[1]),
+            attr("amount", 20),
+            attr("denom", _DENOM),
+        ]);
+
+        assert_eq!(attributes(&resp, "auction_state_changed"), vec![
+            attr("auction_id", auction_id),
+            attr("previous", "pending_completion"),
+            attr("next", "completed"),
+        ]);
+    }
+
+    #[test]
+    fn verify_state() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user0", _INITIAL_BALANCE)
+            .with_account("user1", _INITIAL_BALANCE)
+            .build();
+
+        let users: Vec<Addr> = (0..2).map(|num| suite.addr(&format!("user{num}"))).collect();
+
+        let (auction_id, bid_items) = suite.create_auction(&["Bid item #0", "Bid item #1"]).unwrap();
+        let (second_auction_id, _) = suite.create_auction(&["Bid item #2"]).unwrap();
+
+        let first_bid = suite.bid(&users[0], bid_items[0], 10).unwrap();
+        suite.bid(&users[1], bid_items[0], 20).unwrap();
+        suite.bid(&users[0], bid_items[1], 5).unwrap();
+
+        let resp: VerifyStateResp = suite.query(&QueryMsg::VerifyState { auction_id: None, start_after: None, limit: None });
+        assert_eq!(resp, VerifyStateResp { checked_auctions: 2, issues: vec![], next: None });
+
+        // The limit counts entries read, a page can stop in the middle of an auction's bids
+        let resp: VerifyStateResp = suite.query(&QueryMsg::VerifyState { auction_id: None, start_after: None, limit: Some(2) });
+        assert_eq!(resp.checked_auctions, 0);
+        assert!(matches!(resp.next, Some(VerifyCursor { step: VerifyStep::Auction(AuctionProgress { bid_item: Some(_), .. }), .. })));
+
+        let pages = suite.verify_state(None, Some(2));
+        assert!(pages.len() > 2);
+        assert_eq!(pages.iter().map(|page| page.checked_auctions).sum::<u64>(), 2);
+        assert!(pages.iter().all(|page| page.issues.is_empty()));
+
+        // Break the state behind the contract's back
+        {
+            let contract = suite.contract.clone();
+            let mut storage = suite.app.contract_storage_mut(&contract);
+
+            WINNING_BIDS.save(storage.as_mut(), bid_items[0], &BidKey { bid_item_id: bid_items[0], bid_id: first_bid }).unwrap();
+            AUCTIONS_CRANK_QUEUE_COUNT.save(storage.as_mut(), &3).unwrap();
+            BID_ITEMS_TO_AUCTIONS.save(storage.as_mut(), BidItemId(999), &auction_id).unwrap();
+        }
+
+        suite.app.send_tokens(users[0].clone(), suite.contract.clone(), &coins(7, _DENOM)).unwrap();
+
+        let expected = vec![
+            StateIssue::WinningBidNotMaximum { bid_item_id: bid_items[0], winning_bid: Some(first_bid), highest_bid: Some(BidId(first_bid.0 + 1)) },
+            StateIssue::OrphanedBidItem { bid_item_id: BidItemId(999), auction_id: Some(auction_id) },
+            StateIssue::CounterMismatch { counter: "auctions_crank_queue_count".to_string(), auction_id: None, bid_item_id: None, stored: 3u128.into(), actual: Uint128::zero() },
+            StateIssue::EscrowMismatch { escrowed: 35u128.into(), balance: 42u128.into() },
+        ];
+
+        let issues: Vec<StateIssue> = suite.verify_state(None, None).into_iter().flat_map(|page| page.issues).collect();
+        assert_eq!(issues, expected);
+
+        // Found the same way one entry at a time
+        let issues: Vec<StateIssue> = suite.verify_state(None, Some(1)).into_iter().flat_map(|page| page.issues).collect();
+        assert_eq!(issues, expected);
+
+        // Contract wide checks are skipped when only one auction is walked
+        let resp: VerifyStateResp = suite.query(&QueryMsg::VerifyState { auction_id: Some(second_auction_id), start_after: None, limit: None });
+        assert_eq!(resp, VerifyStateResp { checked_auctions: 1, issues: vec![], next: None });
+    }
+
+    #[test]
+    fn verify_state_across_pages() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user0", _INITIAL_BALANCE)
+            .with_account("user1", _INITIAL_BALANCE)
+            .build();
+
+        let users: Vec<Addr> = (0..2).map(|num| suite.addr(&format!("user{num}"))).collect();
+        let limit = suite.query::<Config>(&QueryMsg::Config {}).max_page_limit;
+
+        // More auctions than a page can hold, each with bids still in escrow
+        for num in 0..limit + 5 {
+            let (_, bid_items) = suite.create_auction(&[&format!("Bid item #{num}")]).unwrap();
+            suite.bid(&users[0], bid_items[0], 10).unwrap();
+            suite.bid(&users[1], bid_items[0], 20).unwrap();
+        }
+
+        suite.execute(&users[0], &ExecuteMsg::Deposit {}, &coins(15, _DENOM)).unwrap();
+
+        let pages = suite.verify_state(None, Some(limit));
+        assert!(pages.len() > 1);
+        assert_eq!(pages.iter().map(|page| page.checked_auctions).sum::<u64>(), u64::from(limit) + 5);
+        assert!(pages.iter().all(|page| page.issues.is_empty()));
+
+        suite.app.send_tokens(users[1].clone(), suite.contract.clone(), &coins(3, _DENOM)).unwrap();
+
+        let escrowed = 30 * u128::from(limit + 5) + 15;
+        let issues: Vec<StateIssue> = suite.verify_state(None, Some(limit)).into_iter().flat_map(|page| page.issues).collect();
+        assert_eq!(issues, vec![StateIssue::EscrowMismatch { escrowed: escrowed.into(), balance: (escrowed + 3).into() }]);
+    }
+
+    #[test]
+    fn bidder_access_and_caps() {
+        // Verifier that only allows the bidder it was instantiated with
+        fn verifier_instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, allowed: Addr) -> StdResult<Response> {
+            Item::<Addr>::new("allowed").save(deps.storage, &allowed)?;
+            Ok(Response::new())
+        }
+
+        fn verifier_execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+            Ok(Response::new())
+        }
+
+        fn verifier_query(deps: Deps, _env: Env, msg: VerifierQueryMsg) -> StdResult<Binary> {
+            let VerifierQueryMsg::IsAllowed { bidder, .. } = msg;
+            to_json_binary(&(Item::<Addr>::new("allowed").load(deps.storage)? == bidder))
+        }
+
+        let mut suite = BiddingSuite::builder()
+            .with_account("user0", _INITIAL_BALANCE)
+            .with_account("user1", _INITIAL_BALANCE)
+            .with_config(ConfigUpdate { max_active_bids_per_bidder: Some(2), max_escrow_per_bidder: Some(50u128.into()), ..Default::default() })
+            .build();
+
+        let users: Vec<Addr> = (0..2).map(|num| suite.addr(&format!("user{num}"))).collect();
+        let owner = suite.admin.clone();
+
+        // Caps count every unsettled bid of the bidder
+        let (auction_id, bid_items) = suite.create_auction(&["Bid item #0", "Bid item #1", "Bid item #2"]).unwrap();
+
+        suite.bid(&users[1], bid_items[0], 30).unwrap();
+
+        let err = suite.bid(&users[1], bid_items[1], 30).unwrap_err();
+        assert_eq!(err, ContractError::EscrowCapExceeded { max: 50u128.into() });
+
+        suite.bid(&users[1], bid_items[1], 20).unwrap();
+
+        let err = suite.bid(&users[1], bid_items[2], 5).unwrap_err();
+        assert_eq!(err, ContractError::TooManyActiveBids { max: 2 });
+
+        let stats: BidderStats = suite.query(&QueryMsg::BidderStats { address: users[1].clone() });
+        assert_eq!(stats, BidderStats { active_bids: 2, escrowed: 50u128.into() });
+
+        suite.close_auction(auction_id).unwrap();
+        suite.crank_until_idle();
+
+        let stats: BidderStats = suite.query(&QueryMsg::BidderStats { address: users[1].clone() });
+        assert_eq!(stats, BidderStats::default());
+
+        // Zero removes a cap
+        suite.execute(&owner, &ExecuteMsg::UpdateConfig(ConfigUpdate { max_active_bids_per_bidder: Some(0), ..Default::default() }), &[]).unwrap();
+
+        let config: Config = suite.query(&QueryMsg::Config {});
+        assert_eq!(config.max_active_bids_per_bidder, None);
+        assert_eq!(config.max_escrow_per_bidder, Some(50u128.into()));
+
+        // Allowlists are managed by the admin or the auction owner
+        let (auction_id, bid_items) = suite.create_auction(&["Bid item #3"]).unwrap();
+
+        let err = suite.execute(&users[0], &ExecuteMsg::SetBidderAccess { auction_id, access: BidderAccess::Allowlist }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized { sender: users[0].clone() });
+
+        suite.execute(&owner, &ExecuteMsg::SetBidderAccess { auction_id, access: BidderAccess::Allowlist }, &[]).unwrap();
+
+        let err = suite.bid(&users[0], bid_items[0], 10).unwrap_err();
+        assert_eq!(err, ContractError::BidderNotAllowed { bidder: users[0].clone().into() });
+
+        suite.execute(&owner, &ExecuteMsg::UpdateAllowlist { auction_id, add: vec![users[0].clone()], remove: vec![] }, &[]).unwrap();
+        suite.bid(&users[0], bid_items[0], 10).unwrap();
+
+        let allowed: bool = suite.query(&QueryMsg::IsBidderAllowed { auction_id, bidder: users[1].clone() });
+        assert!(!allowed);
+
+        // A verifier contract decides instead of the allowlist
+        let code_id = suite.app.store_code(Box::new(ContractWrapper::new(verifier_execute, verifier_instantiate, verifier_query)));
+        let verifier = suite.app
+            .instantiate_contract(code_id, owner.clone(), &users[1], &[], "Verifier", None)
+            .unwrap();
+
+        suite.execute(&owner, &ExecuteMsg::SetBidderAccess { auction_id, access: BidderAccess::Verifier { contract: verifier } }, &[]).unwrap();
+
+        let err = suite.bid(&users[0], bid_items[0], 20).unwrap_err();
+        assert_eq!(err, ContractError::BidderNotAllowed { bidder: users[0].clone().into() });
+
+        suite.bid(&users[1], bid_items[0], 20).unwrap();
+
+        let allowed: bool = suite.query(&QueryMsg::IsBidderAllowed { auction_id, bidder: users[1].clone() });
+        assert!(allowed);
+    }
+
+    #[test]
+    fn denylist() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user0", _INITIAL_BALANCE)
+            .with_account("user1", _INITIAL_BALANCE)
+            .with_account("user2", _INITIAL_BALANCE)
+            .build();
+
+        let users: Vec<Addr> = (0..3).map(|num| suite.addr(&format!("user{num}"))).collect();
+        let owner = suite.admin.clone();
+
+        let (auction_id, bid_items) = suite.create_auction(&["Bid item #0", "Bid item #1"]).unwrap();
+
+        suite.bid(&users[0], bid_items[0], 10).unwrap();
+        suite.bid(&users[1], bid_items[0], 20).unwrap();
+        let denied_winning_bid = suite.bid(&users[0], bid_items[1], 5).unwrap();
+        suite.bid(&users[2], bid_items[1], 3).unwrap();
+
+        // Balances that were there before the addresses got denied
+        suite.execute(&users[0], &ExecuteMsg::Deposit {}, &coins(30, _DENOM)).unwrap();
+
+        let (pull_auction_id, pull_bid_items) = suite.create_auction_with(&["Bid item #2"], None, Some(PayoutMode::Pull)).unwrap();
+        suite.bid(&users[2], pull_bid_items[0], 4).unwrap();
+        suite.bid(&users[1], pull_bid_items[0], 6).unwrap();
+        suite.close_auction(pull_auction_id).unwrap();
+        suite.crank_until_idle();
+
+        let err = suite.execute(&users[1], &ExecuteMsg::DenyAddresses { addresses: vec![users[0].to_string()] }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized { sender: users[1].clone() });
+
+        suite.execute(&owner, &ExecuteMsg::DenyAddresses { addresses: vec!["Not an address".to_string()] }, &[]).unwrap_err();
+        suite.execute(&owner, &ExecuteMsg::DenyAddresses { addresses: vec![users[0].to_string(), users[2].to_string()] }, &[]).unwrap();
+
+        let err = suite.bid(&users[0], bid_items[0], 30).unwrap_err();
+        assert_eq!(err, ContractError::AddressDenied { address: users[0].clone() });
+
+        let err = suite.execute(&users[0], &ExecuteMsg::Deposit {}, &coins(30, _DENOM)).unwrap_err();
+        assert_eq!(err, ContractError::AddressDenied { address: users[0].clone() });
+
+        // Nothing leaves the contract for a denied address
+        let err = suite.execute(&users[0], &ExecuteMsg::Withdraw { amount: None }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::AddressDenied { address: users[0].clone() });
+
+        let err = suite.execute(&users[2], &ExecuteMsg::Claim {}, &[]).unwrap_err();
+        assert_eq!(err, ContractError::AddressDenied { address: users[2].clone() });
+
+        let denylist: PagedResp<Addr, Timestamp> = suite.query(&QueryMsg::Denylist { start_after: None, start_before: None, limit: None, order: None });
+        let mut denied: Vec<Addr> = denylist.items.into_iter().map(|(address, _)| address).collect();
+        denied.sort();
+
+        let mut expected = vec![users[0].clone(), users[2].clone()];
+        expected.sort();
+
+        assert_eq!(denied, expected);
+
+        suite.close_auction(auction_id).unwrap();
+        let resp = suite.crank(None).unwrap();
+
+        // Refunds and the winning bid of the denylisted bidders are held back, the other winner pays as usual
+        assert_eq!(event_attr(&resp, "refund_issued", "destination"), vec!["escrow", "escrow"]);
+        assert_eq!(event_attr(&resp, "payout_escrowed", "bid_id"), vec![denied_winning_bid.to_string()]);
+
+        // The denied winner isn't recorded or reported as the winner
+        suite.assert_bid_item(bid_items[0], BidItemStatus::Completed, Some(&users[1]));
+        suite.assert_bid_item(bid_items[1], BidItemStatus::Completed, None);
+        assert_eq!(event_attr(&resp, "item_settled", "winner"), vec![users[1].to_string()]);
+
+        let escrowed: Uint128 = suite.query(&QueryMsg::DeniedEscrow { address: users[0].clone() });
+        assert_eq!(escrowed.u128(), 15);
+
+        let escrowed: Uint128 = suite.query(&QueryMsg::DeniedEscrow { address: users[2].clone() });
+        assert_eq!(escrowed.u128(), 3);
+
+        suite.assert_balance(&owner, 20);
+        suite.assert_balance(&users[0], _INITIAL_BALANCE - 45);
+
+        let verified: VerifyStateResp = suite.query(&QueryMsg::VerifyState { auction_id: None, start_after: None, limit: None });
+        assert_eq!(verified.issues, vec![]);
+
+        // Only admins resolve the escrow, to the address itself or to someone else
+        let err = suite.execute(&users[1], &ExecuteMsg::ResolveEscrow { address: users[0].to_string(), recipient: None }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized { sender: users[1].clone() });
+
+        // The deposit and claimable balances of denied addresses are released with their escrow
+        suite.execute(&owner, &ExecuteMsg::ResolveEscrow { address: users[0].to_string(), recipient: Some(owner.to_string()) }, &[]).unwrap();
+        suite.execute(&owner, &ExecuteMsg::ResolveEscrow { address: users[2].to_string(), recipient: None }, &[]).unwrap();
+
+        suite.assert_balance(&owner, 20 + 15 + 30);
+        suite.assert_balance(&users[2], _INITIAL_BALANCE);
+
+        let deposit: Uint128 = suite.query(&QueryMsg::DepositBalance { address: users[0].clone() });
+        assert_eq!(deposit, Uint128::zero());
+
+        let err = suite.execute(&owner, &ExecuteMsg::ResolveEscrow { address: users[2].to_string(), recipient: None }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::NothingEscrowed { address: users[2].clone() });
+
+        // Balances of allowed addresses are theirs to take out
+        let err = suite.execute(&owner, &ExecuteMsg::ResolveEscrow { address: users[1].to_string(), recipient: Some(owner.to_string()) }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::NothingEscrowed { address: users[1].clone() });
+
+        suite.execute(&owner, &ExecuteMsg::AllowAddresses { addresses: vec![users[2].to_string()] }, &[]).unwrap();
+
+        let err = suite.execute(&users[2], &ExecuteMsg::Claim {}, &[]).unwrap_err();
+        assert_eq!(err, ContractError::NothingToClaim);
+
+        let verified: VerifyStateResp = suite.query(&QueryMsg::VerifyState { auction_id: None, start_after: None, limit: None });
+        assert_eq!(verified.issues, vec![]);
+
+        let denylist: PagedResp<Addr, Timestamp> = suite.query(&QueryMsg::Denylist { start_after: None, start_before: None, limit: None, order: None });
+        assert_eq!(denylist.items.len(), 1);
+        assert_eq!(denylist.items[0].0, users[0]);
+    }
+
+    #[test]
+    fn hooks() {
+        // Subscriber that records every notification, or fails on all of them
+        fn hook_instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, fail: bool) -> StdResult<Response> {
+            Item::<bool>::new("fail").save(deps.storage, &fail)?;
+            Ok(Response::new())
+        }
+
+        fn hook_execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: HookExecuteMsg) -> StdResult<Response> {
+            if Item::<bool>::new("fail").load(deps.storage)? {
+                return Err(StdError::generic_err("hook failed"));
+            }
+
+            let HookExecuteMsg::BiddingHook(msg) = msg;
+            let received = Item::<Vec<HookMsg>>::new("received");
+            let mut messages = received.may_load(deps.storage)?.unwrap_or_default();
+            messages.push(msg);
+            received.save(deps.storage, &messages)?;
+
+            Ok(Response::new())
+        }
+
+        fn hook_query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+            to_json_binary(&Item::<Vec<HookMsg>>::new("received").may_load(deps.storage)?.unwrap_or_default())
+        }
+
+        let mut suite = BiddingSuite::builder()
+            .with_account("user0", _INITIAL_BALANCE)
+            .with_account("user1", _INITIAL_BALANCE)
+            .build();
+
+        let users: Vec<Addr> = (0..2).map(|num| suite.addr(&format!("user{num}"))).collect();
+        let owner = suite.admin.clone();
+
+        let code_id = suite.app.store_code(Box::new(ContractWrapper::new(hook_execute, hook_instantiate, hook_query)));
+        let recorder = suite.app.instantiate_contract(code_id, owner.clone(), &false, &[], "Recorder", None).unwrap();
+        let failing = suite.app.instantiate_contract(code_id, owner.clone(), &true, &[], "Failing", None).unwrap();
+
+        let err = suite.execute(&users[0], &ExecuteMsg::AddHook { contract: recorder.clone() }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized { sender: users[0].clone() });
+
+        suite.execute(&owner, &ExecuteMsg::AddHook { contract: recorder.clone() }, &[]).unwrap();
+        suite.execute(&owner, &ExecuteMsg::AddHook { contract: failing.clone() }, &[]).unwrap();
+
+        let err = suite.execute(&owner, &ExecuteMsg::AddHook { contract: failing.clone() }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::HookAlreadyRegistered { contract: failing.clone() });
+
+        let (auction_id, bid_items) = suite.create_auction(&["Bid item #0", "Bid item #1"]).unwrap();
+
+        // The failing hook neither blocks the bid nor the crank
+        let bid_id = suite.bid(&users[0], bid_items[0], 10).unwrap();
+
+        suite.close_auction(auction_id).unwrap();
+        let resp = suite.crank(None).unwrap();
+
+        assert_eq!(event_attr(&resp, "hook_failed", "contract"), vec![failing.to_string(), failing.to_string()]);
+        suite.assert_auction_state(auction_id, AuctionStatus::Completed);
+        suite.assert_balance(&owner, 10);
+
+        let received: Vec<HookMsg> = suite.app.wrap().query_wasm_smart(&recorder, &Empty {}).unwrap();
+
+        assert_eq!(received, vec![
+            HookMsg::BidPlaced { auction_id, bid_item_id: bid_items[0], bid_id, bidder: users[0].clone().into(), amount: 10u128.into() },
+            HookMsg::ItemWon { auction_id, bid_item_id: bid_items[0], bid_id, winner: users[0].clone().into(), amount: 10u128.into() },
+            HookMsg::AuctionCompleted { auction_id },
+        ]);
+
+        suite.execute(&owner, &ExecuteMsg::RemoveHook { contract: failing.clone() }, &[]).unwrap();
+
+        let hooks: PagedResp<Addr, Timestamp> = suite.query(&QueryMsg::Hooks { start_after: None, start_before: None, limit: None, order: None });
+        assert_eq!(hooks.items, vec![(recorder, suite.app.block_info().time)]);
+
+        let err = suite.execute(&owner, &ExecuteMsg::RemoveHook { contract: failing.clone() }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::HookNotRegistered { contract: failing });
+    }
+
+    #[test]
+    fn oracle_prices() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user0", _INITIAL_BALANCE)
+            .with_account("user1", _INITIAL_BALANCE)
+            .build();
+
+        let users: Vec<Addr> = (0..2).map(|num| suite.addr(&format!("user{num}"))).collect();
+        let owner = suite.admin.clone();
+
+        let painting = BidItemInput {
+            starting_price: Some(Price::Quote(20u128.into())),
+            reserve_price: Some(Price::Quote(100u128.into())),
+            ..BidItemInput::new("Painting")
+        };
+        let sculpture = BidItemInput {
+            reserve_price: Some(Price::Native(30u128.into())),
+            ..BidItemInput::new("Sculpture")
+        };
+        let create_auction = ExecuteMsg::CreateAuction {
+            name: "TestAuction".to_string(),
+            description: None,
+            terms: None,
+            ends_at: None,
+            payout_mode: None,
+            owner: None,
+            bid_items: vec![painting, sculpture],
+        };
+
+        // Quote prices can't be converted without an oracle
+        let err = suite.execute(&owner, &create_auction, &[]).unwrap_err();
+        assert_eq!(err, ContractError::OracleNotConfigured);
+
+        // 2 usd per eth
+        let now = suite.app.block_info().time;
+        let code_id = suite.app.store_code(oracle::contract());
+        let set_price = |price: u64, twap: u64, updated_at: Timestamp| MockOracleMsg::SetPrice {
+            price: Decimal::from_atomics(price, 0).unwrap(),
+            twap: Decimal::from_atomics(twap, 0).unwrap(),
+            updated_at,
+        };
+        let oracle = suite.app.instantiate_contract(code_id, owner.clone(), &set_price(2, 2, now), &[], "Oracle", None).unwrap();
+
+        let oracle_config = OracleConfig { contract: oracle.clone(), quote: "usd".to_string(), max_staleness: 3_600, max_deviation_bps: 10_001 };
+        let err = suite.execute(&owner, &ExecuteMsg::UpdateConfig(ConfigUpdate { oracle: Some(oracle_config.clone()), ..Default::default() }), &[]).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidConfig { .. }));
+
+        let oracle_config = OracleConfig { max_deviation_bps: 1_000, ..oracle_config };
+        suite.execute(&owner, &ExecuteMsg::UpdateConfig(ConfigUpdate { oracle: Some(oracle_config), ..Default::default() }), &[]).unwrap();
+
+        let resp = suite.execute(&owner, &create_auction, &[]).unwrap();
+        let auction_id = AuctionId(event_attr(&resp, "auction_created", "auction_id")[0].parse().unwrap());
+        let bid_items: Vec<BidItemId> = event_attr(&resp, "bid_item_added", "bid_item_id").iter().map(|id| BidItemId(id.parse().unwrap())).collect();
+
+        let prices: BidItemPricesResp = suite.query(&QueryMsg::BidItemPrices { bid_item_id: bid_items[0] });
+        assert_eq!(prices, BidItemPricesResp { starting_price: Some(10u128.into()), reserve_price: Some(50u128.into()) });
+
+        let err = suite.bid(&users[0], bid_items[0], 9).unwrap_err();
+        assert_eq!(err, ContractError::BidBelowStartingPrice { starting_price: 10u128.into() });
+
+        suite.bid(&users[0], bid_items[0], 40).unwrap();
+        suite.bid(&users[1], bid_items[1], 10).unwrap();
+
+        // Prices older than max_staleness are rejected
+        suite.app.update_block(|block| block.time = block.time.plus_seconds(3_601));
+        let err = suite.bid(&users[1], bid_items[0], 45).unwrap_err();
+        assert_eq!(err, ContractError::StaleOraclePrice { updated_at: now });
+
+        // So are prices 50% away from their average
+        let now = suite.app.block_info().time;
+        suite.app.execute_contract(owner.clone(), oracle.clone(), &set_price(3, 2, now), &[]).unwrap();
+        let err = suite.bid(&users[1], bid_items[0], 45).unwrap_err();
+        assert_eq!(err, ContractError::OraclePriceDeviation { price: Decimal::from_atomics(3u64, 0).unwrap(), twap: Decimal::from_atomics(2u64, 0).unwrap() });
+
+        suite.app.execute_contract(owner.clone(), oracle.clone(), &set_price(2, 2, now), &[]).unwrap();
+        suite.bid(&users[1], bid_items[1], 35).unwrap();
+
+        // The painting's highest bid is under its 50 eth reserve, so it isn't sold and every bid is refunded
+        suite.close_auction(auction_id).unwrap();
+        let resp = suite.crank(None).unwrap();
+
+        assert_eq!(event_attr(&resp, "reserve_not_met", "bid_item_id"), vec![bid_items[0].to_string()]);
+        assert_eq!(event_attr(&resp, "reserve_not_met", "reserve_price"), vec!["50"]);
+        suite.assert_auction_state(auction_id, AuctionStatus::Completed);
+        suite.assert_bid_item(bid_items[0], BidItemStatus::Completed, None);
+        suite.assert_bid_item(bid_items[1], BidItemStatus::Completed, Some(&users[1]));
+        suite.assert_balance(&users[0], _INITIAL_BALANCE);
+        suite.assert_balance(&users[1], _INITIAL_BALANCE - 35);
+        suite.assert_balance(&owner, 35);
+    }
+
+    #[test]
+    fn stale_oracle_defers_settlement() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("user0", _INITIAL_BALANCE)
+            .with_account("user1", _INITIAL_BALANCE)
+            .build();
+
+        let users: Vec<Addr> = (0..2).map(|num| suite.addr(&format!("user{num}"))).collect();
+        let owner = suite.admin.clone();
+
+        // 2 usd per eth
+        let now = suite.app.block_info().time;
+        let code_id = suite.app.store_code(oracle::contract());
+        let set_price = |updated_at: Timestamp| MockOracleMsg::SetPrice {
+            price: Decimal::from_atomics(2u64, 0).unwrap(),
+            twap: Decimal::from_atomics(2u64, 0).unwrap(),
+            updated_at,
+        };
+        let oracle = suite.app.instantiate_contract(code_id, owner.clone(), &set_price(now), &[], "Oracle", None).unwrap();
+
+        let oracle_config = OracleConfig { contract: oracle.clone(), quote: "usd".to_string(), max_staleness: 3_600, max_deviation_bps: 1_000 };
+        suite.execute(&owner, &ExecuteMsg::UpdateConfig(ConfigUpdate { oracle: Some(oracle_config), ..Default::default() }), &[]).unwrap();
+
+        // The painting's reserve needs the oracle to settle, the sculpture's doesn't
+        let create_auction = |bid_item: BidItemInput| ExecuteMsg::CreateAuction {
+            name: "TestAuction".to_string(),
+            description: None,
+            terms: None,
+            ends_at: None,
+            payout_mode: None,
+            owner: None,
+            bid_items: vec![bid_item],
+        };
+        let painting = BidItemInput { reserve_price: Some(Price::Quote(100u128.into())), ..BidItemInput::new("Painting") };
+        let sculpture = BidItemInput { reserve_price: Some(Price::Native(30u128.into())), ..BidItemInput::new("Sculpture") };
+
+        let (quoted_auction, quoted_items) = suite.create_auction_from(&owner, &create_auction(painting)).unwrap();
+        let (native_auction, native_items) = suite.create_auction_from(&owner, &create_auction(sculpture)).unwrap();
+
+        suite.bid(&users[0], quoted_items[0], 60).unwrap();
+        suite.bid(&users[1], native_items[0], 35).unwrap();
+
+        suite.close_auction(quoted_auction).unwrap();
+        suite.close_auction(native_auction).unwrap();
+
+        // The oracle stops updating before the auctions are settled, the painting waits and the
+        // sculpture is settled behind it
+        suite.app.update_block(|block| block.time = block.time.plus_seconds(3_601));
+        let resp = suite.sudo(&SudoMsg::AdvanceCrank { max_items: None }).unwrap();
+
+        assert_eq!(event_attr(&resp, "settlement_deferred", "bid_item_id"), vec![quoted_items[0].to_string()]);
+        assert_eq!(event_attr(&resp, "settlement_deferred", "reason"), vec![ContractError::StaleOraclePrice { updated_at: now }.to_string()]);
+        suite.assert_auction_state(quoted_auction, AuctionStatus::PendingCompletion);
+        suite.assert_bid_item(quoted_items[0], BidItemStatus::Active, None);
+        suite.assert_auction_state(native_auction, AuctionStatus::Completed);
+        suite.assert_bid_item(native_items[0], BidItemStatus::Completed, Some(&users[1]));
+
+        // Deferring isn't progress, so it earns no crank reward
+        let err = suite.crank(None).unwrap_err();
+        assert_eq!(err, ContractError::NothingToCrank);
+        assert_eq!(suite.crank_status().queue_length, 1);
+
+        // Once the oracle is back the painting clears its 50 eth reserve
+        let now = suite.app.block_info().time;
+        suite.app.execute_contract(owner.clone(), oracle, &set_price(now), &[]).unwrap();
+        let resp = suite.crank(None).unwrap();
+
+        assert!(event_attr(&resp, "settlement_deferred", "bid_item_id").is_empty());
+        assert!(event_attr(&resp, "reserve_not_met", "bid_item_id").is_empty());
+        suite.assert_auction_state(quoted_auction, AuctionStatus::Completed);
+        suite.assert_bid_item(quoted_items[0], BidItemStatus::Completed, Some(&users[0]));
+        suite.assert_balance(&users[0], _INITIAL_BALANCE - 60);
+        suite.assert_balance(&owner, 95);
+    }
+
+    #[test]
+    fn auction_owners() {
+        let mut suite = BiddingSuite::builder()
+            .with_account("seller", 0)
+            .with_account("user0", _INITIAL_BALANCE)
+            .with_account("user1", _INITIAL_BALANCE)
+            .build();
+
+        let seller = suite.addr("seller");
+        let users: Vec<Addr> = (0..2).map(|num| suite.addr(&format!("user{num}"))).collect();
+
+        let owner = suite.admin.clone();
+        let create_auction = |name: &str, owner: Option<&Addr>| ExecuteMsg::CreateAuction {
+            name: name.to_string(),
+            description: None,
+            terms: None,
+            ends_at: None,
+            payout_mode: None,
+            owner: owner.map(Addr::to_string),
+            bid_items: vec![BidItemInput::new("Painting")],
+        };
+
+        // Only the admin lists auctions, for itself or for a seller who then manages them
+        let err = suite.create_auction_from(&seller, &create_auction("Sold", None)).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized { sender: seller.clone() });
+
+        let (own, _) = suite.create_auction_from(&owner, &create_auction("Own", None)).unwrap();
+        assert_eq!(suite.auction(own).owner, owner);
+
+        let (sold, sold_items) = suite.create_auction_from(&owner, &create_auction("Sold", Some(&seller))).unwrap();
+        let sold_item = sold_items[0];
+        assert_eq!(suite.auction(sold).owner, seller);
+
+        let (cancelled, _) = suite.create_auction_from(&owner, &create_auction("Cancelled", Some(&seller))).unwrap();
+
+        let add_bid_items = ExecuteMsg::AddBidItems { auction_id: sold, bid_items: vec![BidItemInput::new("Sculpture")] };
+        let err = suite.execute(&users[0], &add_bid_items, &[]).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized { sender: users[0].clone() });
+        suite.execute(&seller, &add_bid_items, &[]).unwrap();
+
+        let err = suite.execute(&users[0], &ExecuteMsg::CancelAuction { id: cancelled }, &[]).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized { sender: users[0].clone() });
+        suite.execute(&seller, &ExecuteMsg::CancelAuction { id: cancelled }, &[]).unwrap();
+
+        suite.bid(&users[0], sold_item, 10).unwrap();
+        suite.bid(&users[1], sold_item, 20).unwrap();
+
+        let close = ExecuteMsg::SetAuctionState { id: sold, status: AuctionStatus::PendingCompletion };
+        let err = suite.execute(&users[0], &close, &[]).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized { sender: users[0].clone() });
+        suite.execute(&seller, &close, &[]).unwrap();
+        suite.crank_until_idle();
+
+        // The winning bid goes to the seller, not to the admin
+        suite.assert_bid_item(sold_item, BidItemStatus::Completed, Some(&users[1]));
+        suite.assert_balance(&seller, 20);
+        suite.assert_balance(&suite.admin.clone(), 0);
+        suite.assert_balance(&users[0], _INITIAL_BALANCE);
+        suite.assert_auction_state(cancelled, AuctionStatus::Cancelled);
+    }
+
+    #[test]
+    fn hook_gas_limit() {
+        const LOOP_GAS: u64 = 1_000;
+
+        // A hook that never returns runs until the chain stops it. With a limit on its submessage
+        // only the hook fails, without one it takes the whole transaction's gas with it.
+        fn run_looping_hook(gas_limit: Option<u64>) -> SubMsgResult {
+            let gas_limit = gas_limit.expect("the hook would use up the gas of the whole transaction");
+            let mut gas_used = 0;
+
+            loop {
+                gas_used += LOOP_GAS;
+
+                if gas_used > gas_limit {
+                    return SubMsgResult::Err(format!("out of gas: limit {gas_limit}"));
+                }
+            }
+        }
+
+        let mut deps = mock_dependencies();
+        let admin = deps.api.addr_make("admin");
+        let bidder = deps.api.addr_make("bidder");
+        let looping = deps.api.addr_make("looping");
+
+        instantiate(deps.as_mut(), mock_env(), message_info(&admin, &[]), InstantiateMsg { admin: admin.clone() }).unwrap();
+
+        let update = ConfigUpdate { hook_gas_limit: Some(0), ..Default::default() };
+        let err = execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), ExecuteMsg::UpdateConfig(update)).unwrap_err();
+        assert_eq!(err, ContractError::InvalidConfig { msg: "hook_gas_limit can't be zero".to_string() });
+
+        let update = ConfigUpdate { hook_gas_limit: Some(50_000), ..Default::default() };
+        execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), ExecuteMsg::UpdateConfig(update)).unwrap();
+        execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), ExecuteMsg::AddHook { contract: looping.clone() }).unwrap();
+
+        let msg = ExecuteMsg::CreateAuction {
+            name: "TestAuction".to_string(),
+            description: None,
+            terms: None,
+            ends_at: None,
+            payout_mode: None,
+            owner: None,
+            bid_items: vec![BidItemInput::new("Bid item #0")],
+        };
+        let resp = execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), msg).unwrap();
+        let bid_item_id: u32 = resp.events.iter()
+            .find(|event| event.ty == "bid_item_added")
+            .and_then(|event| event.attributes.iter().find(|attr| attr.key == "bid_item_id"))
+            .unwrap()
+            .value
+            .parse()
+            .unwrap();
+
+        let msg = ExecuteMsg::PlaceBid { bid_item_id: BidItemId(bid_item_id), amount: None };
+        let resp = execute(deps.as_mut(), mock_env(), message_info(&bidder, &coins(10, _DENOM)), msg).unwrap();
+
+        let hooks: Vec<&SubMsg> = resp.messages.iter().filter(|submsg| submsg.reply_on == ReplyOn::Error).collect();
+        assert_eq!(hooks.len(), 1);
+        assert_eq!(hooks[0].gas_limit, Some(50_000));
+
+        // The failure comes back as a reply, the bid stays placed
+        let reply_msg = Reply { id: hooks[0].id, payload: hooks[0].payload.clone(), gas_used: 50_000, result: run_looping_hook(hooks[0].gas_limit) };
+        let resp = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+        let failed = resp.events.iter().find(|event| event.ty == "hook_failed").unwrap();
+        assert!(failed.attributes.iter().any(|attr| attr.key == "contract" && attr.value == looping.as_str()));
+
+        let bid_item: BidItem = from_json(query(deps.as_ref(), mock_env(), QueryMsg::BidItem { id: BidItemId(bid_item_id) }).unwrap()).unwrap();
+        assert_eq!(bid_item.total_bids, Uint64::one());
+    }
+
+    #[test]
+    fn migrate_legacy_state() {
+        // Records as the first version of the contract stored them
+        #[cw_serde]
+        struct LegacyAuction {
+            name: String,
+            available_bid_items: Uint64,
+            total_bids: Uint64,
+            total_coins: Uint128,
+            current_state: AuctionStatus,
+        }
+
+        #[cw_serde]
+        struct LegacyBidItem {
+            name: String,
+            total_bids: Uint64,
+            total_coins: Uint128,
+            winner: Option<Addr>,
+            current_state: BidItemStatus,
+        }
+
+        #[cw_serde]
+        struct LegacyBid {
+            amount: Uint128,
+            bidder: Addr,
+            placed: Timestamp,
+        }
+
+        let mut deps = mock_dependencies();
+        let admin = deps.api.addr_make("admin");
+        let bidder = deps.api.addr_make("bidder");
+        let auction_id = AuctionId(1);
+        let bid_item_id = BidItemId(1);
+        let key = BidKey { bid_item_id, bid_id: BidId(1) };
+
+        ADMIN.save(&mut deps.storage, &admin).unwrap();
+        Map::<AuctionId, LegacyAuction>::new("auctions").save(&mut deps.storage, auction_id, &LegacyAuction {
+            name: "Legacy".to_string(),
+            available_bid_items: Uint64::one(),
+            total_bids: Uint64::one(),
+            total_coins: Uint128::new(10),
+            current_state: AuctionStatus::Active,
+        }).unwrap();
+        Map::<BidItemKey, LegacyBidItem>::new("bid_items").save(&mut deps.storage, BidItemKey { auction_id, bid_item_id }, &LegacyBidItem {
+            name: "Painting".to_string(),
+            total_bids: Uint64::one(),
+            total_coins: Uint128::new(10),
+            winner: None,
+            current_state: BidItemStatus::Active,
+        }).unwrap();
+        Map::<BidKey, LegacyBid>::new("bids").save(&mut deps.storage, key, &LegacyBid {
+            amount: Uint128::new(10),
+            bidder: bidder.clone(),
+            placed: Timestamp::from_seconds(1),
+        }).unwrap();
+        BID_ITEMS_TO_AUCTIONS.save(&mut deps.storage, bid_item_id, &auction_id).unwrap();
+
+        let resp = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(resp.attributes[1].value, "");
+        assert!(CONFIG.exists(&deps.storage));
+
+        // The old records read with the defaults of the newer fields
+        let auction: Auction = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Auction { id: auction_id }).unwrap()).unwrap();
+        assert_eq!(auction.payout_mode, PayoutMode::Push);
+        assert_eq!(auction.description, None);
+
+        let bid_item: BidItem = from_json(query(deps.as_ref(), mock_env(), QueryMsg::BidItem { id: bid_item_id }).unwrap()).unwrap();
+        assert_eq!(bid_item.media_uris, Vec::<String>::new());
+
+        // But filtered listings miss them until they're reindexed, a couple of records at a time
+        let active_auctions = QueryMsg::Auctions { status: Some(AuctionStatus::Active), start_after: None, start_before: None, limit: None, order: None };
+        let auctions: PagedResp<AuctionId, Auction> = from_json(query(deps.as_ref(), mock_env(), active_auctions.clone()).unwrap()).unwrap();
+        assert_eq!(auctions.items.len(), 0);
+
+        let err = execute(deps.as_mut(), mock_env(), message_info(&bidder, &[]), ExecuteMsg::Reindex { limit: None }).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized { sender: bidder.clone() });
+
+        let done: Vec<String> = (0..2)
+            .map(|_| {
+                let resp = execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), ExecuteMsg::Reindex { limit: Some(2) }).unwrap();
+                resp.attributes.iter().find(|attr| attr.key == "done").unwrap().value.clone()
+            })
+            .collect();
+        assert_eq!(done, vec!["false", "true"]);
+
+        let err = execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), ExecuteMsg::Reindex { limit: None }).unwrap_err();
+        assert_eq!(err, ContractError::NothingToReindex);
+
+        let auction: Auction = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Auction { id: auction_id }).unwrap()).unwrap();
+        assert_eq!(auction.owner, admin);
+
+        let auctions: PagedResp<AuctionId, Auction> = from_json(query(deps.as_ref(), mock_env(), active_auctions).unwrap()).unwrap();
+        assert_eq!(auctions.items.len(), 1);
+
+        let bids: PagedResp<BidKey, BidderBidResp> = from_json(query(deps.as_ref(), mock_env(), QueryMsg::BidsByBidder { bidder: bidder.into(), start_after: None, start_before: None, limit: None, order: None }).unwrap()).unwrap();
+        assert_eq!(bids.items.len(), 1);
+
+        // Contracts instantiated by this version have nothing to reindex
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), message_info(&admin, &[]), InstantiateMsg { admin: admin.clone() }).unwrap();
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), ExecuteMsg::Reindex { limit: None }).unwrap_err();
+        assert_eq!(err, ContractError::NothingToReindex);
     }
 
     #[test]
@@ -392,16 +1914,14 @@ mod tests {
         let mut auction_ids: Vec<u64> = vec![];
 
         for num in 0..total_auctions { // change it to get range
-            let bid_items= vec![ "My first bid item".to_string(), "My second bid item".to_string() ];
+            let bid_items= vec![ BidItemInput::new("My first bid item"), BidItemInput::new("My second bid item") ];
 
-            let auction_name = match num {
-                n => format!("TestAuction #{n}"),
-            };
+            let auction_name = format!("TestAuction #{num}");
 
             let resp = app.execute_contract(
                 Addr::unchecked("owner"),
                 addr.clone(),
-                &ExecuteMsg::CreateAuction { name: auction_name, bid_items: bid_items },
+                &ExecuteMsg::CreateAuction { name: auction_name, description: None, terms: None, ends_at: None, payout_mode: None, owner: None, bid_items },
                 &[],
             )
             .unwrap();
@@ -419,19 +1939,115 @@ mod tests {
             auction_ids.push(auction_id_u64);
         }
 
-        let resp: Vec<(u64, Auction)> = app
+        let resp: PagedResp<AuctionId, Auction> = app
+            .wrap()
+            .query_wasm_smart(&addr, &QueryMsg::Auctions { status: None, start_after: None, start_before: None, limit: None, order: None } )
+            .unwrap();
+
+        assert_eq!(resp.items.len(), 10);
+        assert_eq!(resp.next, Some(resp.items[9].0));
+
+        let resp: PagedResp<AuctionId, Auction> = app
+            .wrap()
+            .query_wasm_smart(&addr, &QueryMsg::Auctions { status: None, start_after: resp.next, start_before: None, limit: None, order: None } )
+            .unwrap();
+
+        assert_eq!(resp.items.len(), 5);
+        assert_eq!(resp.next, None);
+
+        // Descending pages walk back from the end with start_before
+        let resp: PagedResp<AuctionId, Auction> = app
+            .wrap()
+            .query_wasm_smart(&addr, &QueryMsg::Auctions { status: None, start_after: None, start_before: None, limit: Some(4), order: Some(Order::Descending) } )
+            .unwrap();
+
+        let ids: Vec<u64> = resp.items.iter().map(|item| item.0.0 as u64).collect();
+        assert_eq!(ids, auction_ids[11..].iter().rev().copied().collect::<Vec<u64>>());
+
+        let resp: PagedResp<AuctionId, Auction> = app
+            .wrap()
+            .query_wasm_smart(&addr, &QueryMsg::Auctions { status: None, start_after: None, start_before: resp.next, limit: Some(4), order: Some(Order::Descending) } )
+            .unwrap();
+
+        assert_eq!(resp.items[0].0.0 as u64, auction_ids[10]);
+
+        // The limit is capped by the config
+        app.execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &ExecuteMsg::UpdateConfig(ConfigUpdate { default_page_limit: Some(2), max_page_limit: Some(3), ..Default::default() }),
+            &[],
+        )
+        .unwrap();
+
+        let resp: PagedResp<AuctionId, Auction> = app
+            .wrap()
+            .query_wasm_smart(&addr, &QueryMsg::Auctions { status: None, start_after: None, start_before: None, limit: None, order: None } )
+            .unwrap();
+
+        assert_eq!(resp.items.len(), 2);
+
+        let resp: PagedResp<AuctionId, Auction> = app
             .wrap()
-            .query_wasm_smart(&addr, &QueryMsg::Auctions { start_after: None } )
+            .query_wasm_smart(&addr, &QueryMsg::Auctions { status: None, start_after: None, start_before: None, limit: Some(50), order: None } )
             .unwrap();
 
-        assert_eq!(resp.len(), 10);
+        assert_eq!(resp.items.len(), 3);
+
+        let err = app.execute_contract(
+            Addr::unchecked("owner"),
+            addr.clone(),
+            &ExecuteMsg::UpdateConfig(ConfigUpdate { default_page_limit: Some(5), ..Default::default() }),
+            &[],
+        )
+        .unwrap_err();
+
+        assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidConfig { .. }));
+    }
+
+    #[test]
+    fn filter_by_status() {
+        let mut suite = BiddingSuite::builder().build();
+
+        let mut auction_ids: Vec<AuctionId> = vec![];
+
+        for _ in 0..3 {
+            let (auction_id, _) = suite.create_auction(&["My first bid item", "My second bid item"]).unwrap();
+            auction_ids.push(auction_id);
+        }
+
+        suite.set_auction_state(auction_ids[1], AuctionStatus::Suspended).unwrap();
+        suite.close_auction(auction_ids[2]).unwrap();
+
+        let auctions_by_status = |suite: &BiddingSuite, status: AuctionStatus| -> Vec<AuctionId> {
+            suite
+                .query::<PagedResp<AuctionId, Auction>>(&QueryMsg::Auctions { status: Some(status), start_after: None, start_before: None, limit: None, order: None })
+                .items
+                .into_iter()
+                .map(|item| item.0)
+                .collect()
+        };
+
+        assert_eq!(auctions_by_status(&suite, AuctionStatus::Active), vec![auction_ids[0]]);
+        assert_eq!(auctions_by_status(&suite, AuctionStatus::Suspended), vec![auction_ids[1]]);
+        assert_eq!(auctions_by_status(&suite, AuctionStatus::PendingCompletion), vec![auction_ids[2]]);
+
+        suite.crank(None).unwrap();
+
+        assert_eq!(auctions_by_status(&suite, AuctionStatus::PendingCompletion), vec![]);
+        assert_eq!(auctions_by_status(&suite, AuctionStatus::Completed), vec![auction_ids[2]]);
+
+        let resp: PagedResp<BidItemKey, BidItem> = suite.query(&QueryMsg::BidItems { status: Some(BidItemStatus::Completed), start_after: None, start_before: None, limit: None, order: None });
 
-        // let resp: Vec<(AuctionId, Auction)> = app
-        //     .wrap()
-        //     .query_wasm_smart(&addr, &QueryMsg::Auctions { start_after: Some(resp[8].0) } )
-        //     .unwrap();
+        assert_eq!(resp.items.len(), 2);
+        assert!(resp.items.iter().all(|item| item.0.auction_id == auction_ids[2]));
 
-        // assert_eq!(resp.len(), 7);
+        let resp: PagedResp<BidItemKey, BidItem> = suite.query(&QueryMsg::BidItems { status: Some(BidItemStatus::Active), start_after: None, start_before: None, limit: Some(3), order: None });
+        assert_eq!(resp.items.len(), 3);
+
+        let resp: PagedResp<BidItemKey, BidItem> = suite.query(&QueryMsg::BidItems { status: Some(BidItemStatus::Active), start_after: resp.next, start_before: None, limit: Some(3), order: None });
+        assert_eq!(resp.items.len(), 1);
+        assert_eq!(resp.next, None);
     }
 
     #[test]
@@ -456,31 +2072,39 @@ mod tests {
             )
             .unwrap();
 
-        let bid_items= vec![ "My first bid item".to_string(), "My second bid item".to_string() ];
+        let bid_items= vec![ BidItemInput::new("My first bid item"), BidItemInput::new("My second bid item") ];
 
         app.execute_contract(
             Addr::unchecked("owner"),
             addr.clone(),
-            &ExecuteMsg::CreateAuction { name: "TestAuction #1".to_string(), bid_items: bid_items },
+            &ExecuteMsg::CreateAuction { name: "TestAuction #1".to_string(), description: None, terms: None, ends_at: None, payout_mode: None, owner: None, bid_items },
             &[],
         )
         .unwrap();
 
         let resp: Vec<(BidItemKey, BidItem)> = app
             .wrap()
-            .query_wasm_smart(&addr, &QueryMsg::BidItems { start_after: None })
+            .query_wasm_smart::<PagedResp<BidItemKey, BidItem>>(&addr, &QueryMsg::BidItems { status: None, start_after: None, start_before: None, limit: None, order: None })
+            .unwrap()
+            .items;
+
+        let bid_items_ids: Vec<BidItemId> = vec![resp[0].0.bid_item_id, resp[1].0.bid_item_id];
+
+        let resp: PagedResp<BidItemId, BidItem> = app
+            .wrap()
+            .query_wasm_smart(&addr, &QueryMsg::BidItemsById { bid_items_ids: bid_items_ids.clone(), start_after: None, limit: Some(1) } )
             .unwrap();
 
-        let mut bid_items_ids: Vec<BidItemId> = vec![];
-        bid_items_ids.push(resp[0].0.bid_item_id);
-        bid_items_ids.push(resp[1].0.bid_item_id);
+        assert_eq!(resp.items.len(), 1);
+        assert_eq!(resp.items[0].1.name, "My first bid item".to_string());
+        assert_eq!(resp.next, Some(bid_items_ids[0]));
 
-        let resp: Vec<(BidItemId, BidItem)> = app
+        let resp: PagedResp<BidItemId, BidItem> = app
             .wrap()
-            .query_wasm_smart(&addr, &QueryMsg::BidItemsById { bid_items_ids: bid_items_ids } )
+            .query_wasm_smart(&addr, &QueryMsg::BidItemsById { bid_items_ids, start_after: resp.next, limit: Some(1) } )
             .unwrap();
 
-        assert_eq!(resp[0].1.name, "My first bid item".to_string());
-        assert_eq!(resp[1].1.name, "My second bid item".to_string());
+        assert_eq!(resp.items[0].1.name, "My second bid item".to_string());
+        assert_eq!(resp.next, None);
     }
 }
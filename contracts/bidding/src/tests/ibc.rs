@@ -0,0 +1,349 @@
+use cosmwasm_std::{coin, from_json, to_json_string, Addr, Coin, IbcOrder, StdAck, Timestamp, Uint128};
+
+use crate::error::ContractError;
+use crate::msg::{BidMemo, ExecuteMsg, Ics20Packet, PagedResp, QueryMsg, RefundMemo, RemoteBid, VerifyStateResp};
+use crate::state::{AuctionId, Bid, BidId, BidItemId, BidItemStatus, Bidder, BidderAccess, PauseScope, RemoteBidder, RemoteSettlement};
+use crate::tests::suite::{event_attr, sent_packets, BiddingSuite, SentPacket};
+use crate::tests::_DENOM;
+
+const CHANNEL: &str = "channel-0";
+// Our channel's end on the other chain
+const COUNTERPARTY: &str = "channel-7";
+
+/// A suite with an auction and a channel taking remote bids, `remote1` and `remote2` hold 500 of
+/// the bidding denom on the other chain.
+fn setup() -> (BiddingSuite, AuctionId, BidItemId) {
+    let mut suite = BiddingSuite::builder().with_account("user", 1_000).with_account("sender", 1_000).build();
+    let admin = suite.admin.clone();
+
+    suite.open_channel(CHANNEL, COUNTERPARTY);
+    suite.execute(&admin, &ExecuteMsg::AddTransferChannel { channel_id: CHANNEL.to_string() }, &[]).unwrap();
+
+    let sender = suite.addr("sender");
+    send_remote(&mut suite, &sender, "remote1", 500);
+    send_remote(&mut suite, &sender, "remote2", 500);
+
+    let (auction_id, bid_items) = suite.create_auction(&["Bid item"]).unwrap();
+
+    (suite, auction_id, bid_items[0])
+}
+
+/// Sends the bidding denom to a remote address, the other chain takes it.
+fn send_remote(suite: &mut BiddingSuite, sender: &Addr, remote_address: &str, amount: u128) {
+    let msg = ExecuteMsg::Transfer { channel_id: CHANNEL.to_string(), remote_address: remote_address.to_string() };
+    let resp = suite.execute(sender, &msg, &[Coin::new(amount, _DENOM)]).unwrap();
+
+    suite.ack_packet(&sent_packets(&resp)[0], StdAck::success(b"\x01"));
+}
+
+/// A packet sending the bidding denom back from the other chain.
+fn returning(suite: &BiddingSuite, sender: &str, amount: u128, memo: String) -> Ics20Packet {
+    Ics20Packet {
+        denom: format!("transfer/{COUNTERPARTY}/{_DENOM}"),
+        amount: Uint128::new(amount),
+        sender: sender.to_string(),
+        receiver: suite.contract.to_string(),
+        memo,
+    }
+}
+
+fn bid_memo(bid_item_id: BidItemId) -> String {
+    to_json_string(&BidMemo { place_bid: RemoteBid { bid_item_id } }).unwrap()
+}
+
+fn remote(address: &str) -> Bidder {
+    Bidder::Remote(RemoteBidder { channel_id: CHANNEL.to_string(), address: address.to_string() })
+}
+
+fn undelivered(suite: &BiddingSuite) -> Vec<(BidId, RemoteSettlement)> {
+    let msg = QueryMsg::UndeliveredSettlements { start_after: None, start_before: None, limit: None, order: None };
+    suite.query::<PagedResp<BidId, RemoteSettlement>>(&msg).items
+}
+
+#[test]
+fn channel_handshake() {
+    let mut suite = BiddingSuite::builder().build();
+    let admin = suite.admin.clone();
+    let user = suite.addr("user");
+
+    let mut ordered = suite.ics20_channel(CHANNEL, COUNTERPARTY);
+    ordered.order = IbcOrder::Ordered;
+    let err = suite.open_channel_with(ordered).unwrap_err();
+    assert_eq!(err, ContractError::InvalidChannel { msg: "ICS-20 channels are unordered".to_string() });
+
+    let mut ics721 = suite.ics20_channel(CHANNEL, COUNTERPARTY);
+    ics721.version = "ics721-1".to_string();
+    let err = suite.open_channel_with(ics721).unwrap_err();
+    assert_eq!(err, ContractError::InvalidChannel { msg: "expecting version ics20-1, found ics721-1".to_string() });
+
+    // Bids are only taken from open channels an admin added
+    let msg = ExecuteMsg::AddTransferChannel { channel_id: CHANNEL.to_string() };
+    let err = suite.execute(&admin, &msg, &[]).unwrap_err();
+    assert_eq!(err, ContractError::ChannelNotOpen { channel_id: CHANNEL.to_string() });
+
+    let resp = suite.open_channel(CHANNEL, COUNTERPARTY);
+    let connected = resp.events.iter().find(|event| event.ty == "wasm").unwrap();
+    assert!(connected.attributes.iter().any(|attr| attr.key == "action" && attr.value == "ibc_channel_connect"));
+    suite.open_channel("channel-1", "channel-8");
+
+    let err = suite.execute(&user, &msg, &[]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { sender: user });
+    suite.execute(&admin, &msg, &[]).unwrap();
+
+    let err = suite.execute(&admin, &msg, &[]).unwrap_err();
+    assert_eq!(err, ContractError::TransferChannelExists { channel_id: CHANNEL.to_string() });
+
+    suite.execute(&admin, &ExecuteMsg::AddTransferChannel { channel_id: "channel-1".to_string() }, &[]).unwrap();
+
+    let query = QueryMsg::TransferChannels { start_after: None, start_before: None, limit: None, order: None };
+    let channels: PagedResp<String, Timestamp> = suite.query(&query);
+    let added = suite.app.block_info().time;
+    assert_eq!(channels.items, vec![(CHANNEL.to_string(), added), ("channel-1".to_string(), added)]);
+
+    suite.execute(&admin, &ExecuteMsg::RemoveTransferChannel { channel_id: "channel-1".to_string() }, &[]).unwrap();
+
+    let err = suite.execute(&admin, &ExecuteMsg::RemoveTransferChannel { channel_id: "channel-1".to_string() }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::UnknownTransferChannel { channel_id: "channel-1".to_string() });
+
+    // A closed channel stops taking bids for good
+    suite.close_channel(CHANNEL);
+    assert!(!suite.ibc_channel(CHANNEL).open);
+
+    let channels: PagedResp<String, Timestamp> = suite.query(&query);
+    assert_eq!(channels.items, vec![]);
+
+    let err = suite.execute(&admin, &msg, &[]).unwrap_err();
+    assert_eq!(err, ContractError::ChannelNotOpen { channel_id: CHANNEL.to_string() });
+}
+
+#[test]
+fn packets_bid_with_their_funds() {
+    let (mut suite, _, bid_item_id) = setup();
+    assert_eq!(suite.ibc_channel(CHANNEL).outstanding, Uint128::new(1_000));
+
+    let (ack, resp) = suite.receive_packet(CHANNEL, &returning(&suite, "remote1", 100, bid_memo(bid_item_id)));
+    assert_eq!(ack, StdAck::success(b"\x01"));
+    assert_eq!(event_attr(&resp, "bid_placed", "bidder"), vec![format!("{CHANNEL}/remote1")]);
+
+    let (_, bid): (BidId, Bid) = suite.query::<Option<_>>(&QueryMsg::WinningBid { bid_item_id }).unwrap();
+    assert_eq!((bid.bidder, bid.amount), (remote("remote1"), Uint128::new(100)));
+
+    // The funds were in the contract all along, they're not outstanding on the channel anymore
+    assert_eq!(suite.ibc_channel(CHANNEL).outstanding, Uint128::new(900));
+    suite.assert_balance(&suite.contract.clone(), 1_000);
+
+    let pages: Vec<VerifyStateResp> = suite.verify_state(None, None);
+    assert!(pages.iter().all(|page| page.issues.is_empty()), "{pages:?}");
+}
+
+#[test]
+fn rejected_packets_are_acknowledged_with_errors() {
+    let (mut suite, auction_id, bid_item_id) = setup();
+    let admin = suite.admin.clone();
+
+    suite.open_channel("channel-1", "channel-8");
+
+    let mut elsewhere = returning(&suite, "remote1", 100, bid_memo(bid_item_id));
+    elsewhere.receiver = suite.addr("user").to_string();
+
+    // Another asset, an unknown bid item, a memo without a bid, more than was sent out, funds sent
+    // to somebody else and a channel that doesn't take bids
+    let cases = [
+        (CHANNEL, Ics20Packet { denom: "uatom".to_string(), ..returning(&suite, "remote1", 100, bid_memo(bid_item_id)) }),
+        (CHANNEL, returning(&suite, "remote2", 200, bid_memo(BidItemId(99)))),
+        (CHANNEL, returning(&suite, "remote1", 300, String::new())),
+        (CHANNEL, returning(&suite, "remote2", 1_001, bid_memo(bid_item_id))),
+        (CHANNEL, elsewhere),
+        ("channel-1", Ics20Packet { denom: format!("transfer/channel-8/{_DENOM}"), ..returning(&suite, "remote1", 100, bid_memo(bid_item_id)) }),
+    ];
+
+    for (channel_id, transfer) in cases {
+        let (ack, resp) = suite.receive_packet(channel_id, &transfer);
+        assert!(matches!(ack, StdAck::Error(_)), "{transfer:?}");
+
+        assert_eq!(event_attr(&resp, "transfer_rejected", "channel_id"), vec![channel_id]);
+        assert_eq!(event_attr(&resp, "transfer_rejected", "sender"), vec![transfer.sender.clone()]);
+        assert!(sent_packets(&resp).is_empty());
+    }
+
+    // Allowlists only know local accounts, and a paused contract takes no bids
+    let msg = ExecuteMsg::SetBidderAccess { auction_id, access: BidderAccess::Allowlist };
+    suite.execute(&admin, &msg, &[]).unwrap();
+
+    let (ack, resp) = suite.receive_packet(CHANNEL, &returning(&suite, "remote1", 100, bid_memo(bid_item_id)));
+    let reason = ContractError::BidderNotAllowed { bidder: remote("remote1") }.to_string();
+    assert_eq!(ack, StdAck::error(reason.clone()));
+    assert_eq!(event_attr(&resp, "transfer_rejected", "reason"), vec![reason]);
+
+    suite.execute(&admin, &ExecuteMsg::SetBidderAccess { auction_id, access: BidderAccess::Open }, &[]).unwrap();
+    suite.execute(&admin, &ExecuteMsg::Pause { scope: Some(PauseScope::Bidding) }, &[]).unwrap();
+
+    let (ack, _) = suite.receive_packet(CHANNEL, &returning(&suite, "remote1", 100, bid_memo(bid_item_id)));
+    assert_eq!(ack, StdAck::error(ContractError::Paused { scope: "bidding".to_string() }.to_string()));
+
+    // Nothing was taken
+    let msg = QueryMsg::BidsByBidItem { bid_item_id, start_after: None, start_before: None, limit: None, order: None };
+    assert_eq!(suite.query::<PagedResp<BidId, Bid>>(&msg).items, vec![]);
+    assert_eq!(suite.ibc_channel(CHANNEL).outstanding, Uint128::new(1_000));
+    assert_eq!(suite.ibc_channel("channel-1").outstanding, Uint128::zero());
+}
+
+#[test]
+fn refused_transfers_come_back() {
+    let (mut suite, _, _) = setup();
+    let user = suite.addr("user");
+
+    let msg = ExecuteMsg::Transfer { channel_id: "channel-1".to_string(), remote_address: "remote1".to_string() };
+    let err = suite.execute(&user, &msg, &[Coin::new(100u128, _DENOM)]).unwrap_err();
+    assert_eq!(err, ContractError::UnknownTransferChannel { channel_id: "channel-1".to_string() });
+
+    let msg = ExecuteMsg::Transfer { channel_id: CHANNEL.to_string(), remote_address: "remote1".to_string() };
+    let refused = sent_packets(&suite.execute(&user, &msg, &[Coin::new(100u128, _DENOM)]).unwrap()).remove(0);
+    let timed_out = sent_packets(&suite.execute(&user, &msg, &[Coin::new(200u128, _DENOM)]).unwrap()).remove(0);
+
+    assert_eq!(refused.transfer, Ics20Packet {
+        denom: _DENOM.to_string(),
+        amount: Uint128::new(100),
+        sender: user.to_string(),
+        receiver: "remote1".to_string(),
+        memo: String::new(),
+    });
+    assert_eq!(suite.ibc_channel(CHANNEL).outstanding, Uint128::new(1_300));
+    suite.assert_balance(&user, 700);
+
+    suite.ack_packet(&refused, StdAck::error("invalid receiver"));
+    suite.timeout_packet(&timed_out);
+
+    suite.assert_balance(&user, 1_000);
+    assert_eq!(suite.ibc_channel(CHANNEL).outstanding, Uint128::new(1_000));
+}
+
+#[test]
+fn remote_refunds_are_held_and_retried() {
+    let (mut suite, auction_id, bid_item_id) = setup();
+    let admin = suite.admin.clone();
+    let user = suite.addr("user");
+
+    suite.receive_packet(CHANNEL, &returning(&suite, "remote1", 100, bid_memo(bid_item_id)));
+    suite.bid(&user, bid_item_id, 150).unwrap();
+    suite.receive_packet(CHANNEL, &returning(&suite, "remote2", 200, bid_memo(bid_item_id)));
+
+    suite.close_auction(auction_id).unwrap();
+    let resp = suite.crank(None).unwrap();
+
+    // The remote winner pays the owner here, the losing remote bid goes back over the channel
+    suite.assert_balance(&user, 1_000);
+    suite.assert_balance(&admin, 200);
+
+    let sent = sent_packets(&resp);
+    assert_eq!(sent.len(), 1);
+    let refund = sent[0].clone();
+    assert_eq!(
+        (refund.channel_id.as_str(), refund.transfer.receiver.as_str(), refund.transfer.amount, refund.transfer.denom.as_str()),
+        (CHANNEL, "remote1", Uint128::new(100), _DENOM),
+    );
+    assert_eq!(suite.ibc_channel(CHANNEL).outstanding, Uint128::new(800));
+
+    let bid_item = suite.bid_item(bid_item_id);
+    assert_eq!((bid_item.current_state, bid_item.winner), (BidItemStatus::Completed, Some(remote("remote2"))));
+
+    let losing_bid = from_json::<RefundMemo>(refund.transfer.memo.as_bytes()).unwrap().refund;
+    let held = RemoteSettlement { channel_id: CHANNEL.to_string(), receiver: "remote1".to_string(), amount: coin(100, _DENOM) };
+
+    // A refund the other chain refuses is held, its funds aren't outstanding anymore
+    let resp = suite.ack_packet(&refund, StdAck::error("invalid receiver"));
+    assert_eq!(event_attr(&resp, "settlement_undelivered", "reason"), vec!["invalid receiver"]);
+    assert_eq!(undelivered(&suite), vec![(losing_bid, held.clone())]);
+    assert_eq!(suite.ibc_channel(CHANNEL).outstanding, Uint128::new(700));
+
+    let retry = ExecuteMsg::RetrySettlement { bid_id: losing_bid, channel_id: None };
+    let err = suite.execute(&user, &retry, &[]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { sender: user.clone() });
+
+    let resp = suite.execute(&admin, &retry, &[]).unwrap();
+    let resent = sent_packets(&resp).remove(0);
+    assert_eq!(resent.transfer, refund.transfer);
+    assert_eq!(undelivered(&suite), vec![]);
+
+    // Timeouts are held too
+    suite.timeout_packet(&resent);
+    assert_eq!(undelivered(&suite), vec![(losing_bid, held.clone())]);
+
+    let err = suite.execute(&admin, &ExecuteMsg::RetrySettlement { bid_id: losing_bid, channel_id: Some("channel-5".to_string()) }, &[]).unwrap_err();
+    assert_eq!(err, ContractError::ChannelNotOpen { channel_id: "channel-5".to_string() });
+
+    // A refund that can't even be sent replies and is held again
+    suite.close_channel(CHANNEL);
+
+    let resp = suite.execute(&admin, &retry, &[]).unwrap();
+    assert!(sent_packets(&resp).is_empty());
+    assert_eq!(event_attr(&resp, "settlement_undelivered", "bid_id"), vec![losing_bid.to_string()]);
+    assert_eq!(undelivered(&suite), vec![(losing_bid, held.clone())]);
+    assert_eq!(suite.ibc_channel(CHANNEL).outstanding, Uint128::new(700));
+
+    // Once acknowledged there's nothing left to retry
+    suite.open_channel("channel-1", "channel-8");
+
+    let resp = suite.execute(&admin, &ExecuteMsg::RetrySettlement { bid_id: losing_bid, channel_id: Some("channel-1".to_string()) }, &[]).unwrap();
+    let resent = sent_packets(&resp).remove(0);
+    assert_eq!(resent.channel_id, "channel-1");
+
+    suite.ack_packet(&resent, StdAck::success(b"\x01"));
+    assert_eq!(suite.ibc_channel("channel-1").outstanding, Uint128::new(100));
+
+    let err = suite.execute(&admin, &retry, &[]).unwrap_err();
+    assert_eq!(err, ContractError::NoUndeliveredSettlement { bid_id: losing_bid });
+
+    let pages: Vec<VerifyStateResp> = suite.verify_state(None, None);
+    assert!(pages.iter().all(|page| page.issues.is_empty()), "{pages:?}");
+}
+
+#[test]
+fn remote_bid_end_to_end() {
+    let (mut suite, auction_id, bid_item_id) = setup();
+    let (admin, user, sender) = (suite.admin.clone(), suite.addr("user"), suite.addr("sender"));
+
+    let (low, high, stray) = (
+        returning(&suite, "remote1", 100, bid_memo(bid_item_id)),
+        returning(&suite, "remote2", 200, bid_memo(bid_item_id)),
+        returning(&suite, "remote3", 50, bid_memo(BidItemId(99))),
+    );
+
+    let (_, resp) = suite.receive_packet(CHANNEL, &low);
+    assert_eq!(event_attr(&resp, "bid_placed", "bidder"), vec![format!("{CHANNEL}/remote1")]);
+
+    suite.bid(&user, bid_item_id, 150).unwrap();
+
+    let (_, resp) = suite.receive_packet(CHANNEL, &high);
+    assert_eq!(event_attr(&resp, "bid_outbid", "bidder"), vec![user.to_string()]);
+
+    // A packet that can't bid goes straight back, the other chain refunds it
+    let (ack, resp) = suite.receive_packet(CHANNEL, &stray);
+    assert!(matches!(ack, StdAck::Error(_)));
+    assert_eq!(event_attr(&resp, "transfer_rejected", "sender"), vec!["remote3"]);
+
+    suite.assert_balance(&suite.contract.clone(), 1_150);
+
+    suite.close_auction(auction_id).unwrap();
+    let resp = suite.crank(None).unwrap();
+
+    let refunds: Vec<SentPacket> = sent_packets(&resp);
+    assert_eq!(refunds.iter().map(|sent| (sent.transfer.receiver.as_str(), sent.transfer.amount.u128())).collect::<Vec<_>>(), vec![("remote1", 100)]);
+    assert_eq!(event_attr(&resp, "refund_issued", "destination"), vec!["remote", "wallet"]);
+
+    suite.ack_packet(&refunds[0], StdAck::success(b"\x01"));
+
+    let bid_item = suite.bid_item(bid_item_id);
+    assert_eq!(bid_item.current_state, BidItemStatus::Completed);
+    assert_eq!(bid_item.winner, Some(remote("remote2")));
+
+    // What remote1 and remote2 still hold on the other chain is all the contract keeps
+    suite.assert_balance(&user, 1_000);
+    suite.assert_balance(&admin, 200);
+    suite.assert_balance(&sender, 0);
+    suite.assert_balance(&suite.contract.clone(), 800);
+    assert_eq!(suite.ibc_channel(CHANNEL).outstanding, Uint128::new(800));
+
+    let pages: Vec<VerifyStateResp> = suite.verify_state(None, None);
+    assert!(pages.iter().all(|page| page.issues.is_empty()), "{pages:?}");
+}
@@ -0,0 +1,305 @@
+use cosmwasm_std::{Addr, Coin, Uint128};
+use proptest::prelude::*;
+
+use crate::msg::{BidItemInput, ExecuteMsg, PagedResp, QueryMsg, StateIssue};
+use crate::state::{Auction, AuctionId, AuctionStatus, Bid, BidId, BidItem, BidItemId, BidItemStatus, Bidder, PayoutMode, Price, REMOVED_BID_ITEMS};
+use crate::tests::suite::{event_attr, BiddingSuite};
+use crate::tests::{_DENOM, _INITIAL_BALANCE};
+
+const BIDDERS: usize = 3;
+const VERIFY_PAGE_LIMIT: u32 = 3;
+
+/// A step of a random scenario. Auctions and bid items are picked by index among the ones created
+/// so far, so most steps hit something that exists, invalid steps are expected to be rejected.
+#[derive(Clone, Debug)]
+enum Op {
+    CreateAuction { bid_items: usize, payout_mode: PayoutMode, reserve: Option<u128> },
+    AddBidItems { auction: usize, bid_items: usize },
+    SetAuctionState { auction: usize, status: AuctionStatus },
+    CancelAuction { auction: usize },
+    RemoveBidItem { bid_item: usize },
+    PlaceBid { bidder: usize, bid_item: usize, amount: u128, from_deposit: bool },
+    Deposit { bidder: usize, amount: u128 },
+    Withdraw { bidder: usize, amount: Option<u128> },
+    /// `account` picks a bidder, or the seller past the last bidder.
+    Claim { account: usize },
+    AdvanceCrank { max_items: Option<u32> },
+}
+
+fn auction_status() -> impl Strategy<Value = AuctionStatus> {
+    prop_oneof![
+        Just(AuctionStatus::Active),
+        Just(AuctionStatus::Suspended),
+        Just(AuctionStatus::PendingCompletion),
+        Just(AuctionStatus::Completed),
+        Just(AuctionStatus::Cancelled),
+    ]
+}
+
+fn payout_mode() -> impl Strategy<Value = PayoutMode> {
+    prop_oneof![
+        Just(PayoutMode::Push),
+        Just(PayoutMode::Pull),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        1 => (0..4usize, payout_mode(), proptest::option::of(1..60u128)).prop_map(|(bid_items, payout_mode, reserve)| Op::CreateAuction { bid_items, payout_mode, reserve }),
+        1 => (any::<usize>(), 1..3usize).prop_map(|(auction, bid_items)| Op::AddBidItems { auction, bid_items }),
+        2 => (any::<usize>(), auction_status()).prop_map(|(auction, status)| Op::SetAuctionState { auction, status }),
+        1 => any::<usize>().prop_map(|auction| Op::CancelAuction { auction }),
+        1 => any::<usize>().prop_map(|bid_item| Op::RemoveBidItem { bid_item }),
+        5 => (0..BIDDERS, any::<usize>(), 1..50u128, any::<bool>()).prop_map(|(bidder, bid_item, amount, from_deposit)| Op::PlaceBid { bidder, bid_item, amount, from_deposit }),
+        1 => (0..BIDDERS, 1..100u128).prop_map(|(bidder, amount)| Op::Deposit { bidder, amount }),
+        1 => (0..BIDDERS, proptest::option::of(1..100u128)).prop_map(|(bidder, amount)| Op::Withdraw { bidder, amount }),
+        1 => (0..=BIDDERS).prop_map(|account| Op::Claim { account }),
+        2 => proptest::option::of(1..4u32).prop_map(|max_items| Op::AdvanceCrank { max_items }),
+    ]
+}
+
+struct Scenario {
+    suite: BiddingSuite,
+    bidders: Vec<Addr>,
+    auctions: Vec<AuctionId>,
+    bid_items: Vec<BidItemId>,
+}
+
+impl Scenario {
+    fn new() -> Self {
+        let mut builder = BiddingSuite::builder();
+
+        for num in 0..BIDDERS {
+            builder = builder.with_account(&format!("user{num}"), _INITIAL_BALANCE);
+        }
+
+        let suite = builder.build();
+        let bidders = (0..BIDDERS).map(|num| suite.addr(&format!("user{num}"))).collect();
+
+        Scenario { suite, bidders, auctions: vec![], bid_items: vec![] }
+    }
+
+    fn apply(&mut self, op: &Op) {
+        let admin = self.suite.admin.clone();
+
+        // Rejected steps leave the state untouched, the invariants are checked either way
+        match *op {
+            Op::CreateAuction { bid_items, payout_mode, reserve } => {
+                let msg = ExecuteMsg::CreateAuction {
+                    name: "TestAuction".to_string(),
+                    description: None,
+                    terms: None,
+                    ends_at: None,
+                    payout_mode: Some(payout_mode),
+                    owner: None,
+                    bid_items: (0..bid_items)
+                        .map(|num| BidItemInput {
+                            reserve_price: reserve.map(|reserve| Price::Native(reserve.into())),
+                            ..BidItemInput::new(format!("Bid item #{num}"))
+                        })
+                        .collect(),
+                };
+
+                if let Ok((auction_id, bid_item_ids)) = self.suite.create_auction_from(&admin, &msg) {
+                    self.auctions.push(auction_id);
+                    self.bid_items.extend(bid_item_ids);
+                }
+            },
+            Op::AddBidItems { auction, bid_items } => {
+                let Some(&auction_id) = pick(&self.auctions, auction) else { return };
+
+                let msg = ExecuteMsg::AddBidItems {
+                    auction_id,
+                    bid_items: (0..bid_items).map(|num| BidItemInput::new(format!("Added bid item #{num}"))).collect(),
+                };
+
+                if let Ok(resp) = self.suite.execute(&admin, &msg, &[]) {
+                    self.bid_items.extend(
+                        event_attr(&resp, "bid_item_added", "bid_item_id")
+                            .iter()
+                            .map(|id| BidItemId(id.parse().unwrap())),
+                    );
+                }
+            },
+            Op::SetAuctionState { auction, status } => {
+                if let Some(&auction_id) = pick(&self.auctions, auction) {
+                    let _ = self.suite.set_auction_state(auction_id, status);
+                }
+            },
+            Op::CancelAuction { auction } => {
+                if let Some(&auction_id) = pick(&self.auctions, auction) {
+                    let _ = self.suite.execute(&admin, &ExecuteMsg::CancelAuction { id: auction_id }, &[]);
+                }
+            },
+            Op::RemoveBidItem { bid_item } => {
+                if let Some(&bid_item_id) = pick(&self.bid_items, bid_item) {
+                    let _ = self.suite.execute(&admin, &ExecuteMsg::RemoveBidItem { bid_item_id }, &[]);
+                }
+            },
+            Op::PlaceBid { bidder, bid_item, amount, from_deposit } => {
+                if let Some(&bid_item_id) = pick(&self.bid_items, bid_item) {
+                    let bidder = self.bidders[bidder].clone();
+
+                    let _ = if from_deposit {
+                        self.suite.bid_from_deposit(&bidder, bid_item_id, amount)
+                    } else {
+                        self.suite.bid(&bidder, bid_item_id, amount)
+                    };
+                }
+            },
+            Op::Deposit { bidder, amount } => {
+                let bidder = self.bidders[bidder].clone();
+                let _ = self.suite.execute(&bidder, &ExecuteMsg::Deposit {}, &[Coin::new(amount, _DENOM)]);
+            },
+            Op::Withdraw { bidder, amount } => {
+                let bidder = self.bidders[bidder].clone();
+                let _ = self.suite.execute(&bidder, &ExecuteMsg::Withdraw { amount: amount.map(Uint128::new) }, &[]);
+            },
+            Op::Claim { account } => {
+                let account = self.accounts()[account].clone();
+                let _ = self.suite.execute(&account, &ExecuteMsg::Claim {}, &[]);
+            },
+            Op::AdvanceCrank { max_items } => {
+                let _ = self.suite.crank(max_items);
+            },
+        }
+    }
+
+    fn check_invariants(&self) {
+        let mut unsettled = Uint128::zero();
+
+        for (auction_id, auction) in self.all_auctions() {
+            for (bid_item_id, bid_item) in self.all_bid_items(auction_id) {
+                let bids = self.all_bids(bid_item_id);
+
+                match bid_item.current_state {
+                    BidItemStatus::Active => {
+                        assert_ne!(auction.current_state, AuctionStatus::Completed, "bid item {bid_item_id} is still active in completed auction {auction_id}");
+                        unsettled += bids.iter().map(|(_, bid)| bid.amount).sum::<Uint128>();
+                    },
+                    BidItemStatus::Completed => {
+                        assert_eq!(bid_item.winner, highest_bidder(&bids, bid_item.reserve_price), "winner of bid item {bid_item_id}");
+                    },
+                    // Removed bid items hold their bids until the crank refunded them
+                    BidItemStatus::Cancelled => {
+                        if REMOVED_BID_ITEMS.query(&self.suite.app.wrap(), self.suite.contract.clone(), bid_item_id).unwrap().is_some() {
+                            unsettled += bids.iter().map(|(_, bid)| bid.amount).sum::<Uint128>();
+                        }
+                    },
+                }
+            }
+        }
+
+        let crank_status = self.suite.crank_status();
+
+        // Crank rewards are off, so whatever the contract holds belongs to bids that aren't settled
+        // yet, to deposits or to payouts that weren't claimed
+        let mut held = unsettled;
+
+        for account in self.accounts() {
+            let deposit: Uint128 = self.suite.query(&QueryMsg::DepositBalance { address: account.clone() });
+            let claimable: Vec<Coin> = self.suite.query(&QueryMsg::Claimable { address: account });
+
+            held += deposit + claimable.iter().map(|coin| coin.amount).sum::<Uint128>();
+        }
+
+        assert_eq!(crank_status.reward_pool, Uint128::zero());
+        assert_eq!(self.suite.balance(&self.suite.contract), held.u128(), "contract balance");
+
+        // The counter matches the queue walked in small pages
+        let mut queued_auctions = 0;
+        let mut start_after = None;
+
+        loop {
+            let page = self.suite.crank_status_page(start_after, Some(VERIFY_PAGE_LIMIT));
+            queued_auctions += page.queued_auctions.len() as u64;
+
+            match page.next {
+                Some(next) => start_after = Some(next),
+                None => break,
+            }
+        }
+
+        assert_eq!(crank_status.queue_length, queued_auctions, "crank queue count");
+
+        // A small page makes the walk stop halfway through auctions and bid items
+        let issues: Vec<StateIssue> = self.suite.verify_state(None, Some(VERIFY_PAGE_LIMIT)).into_iter().flat_map(|page| page.issues).collect();
+        assert_eq!(issues, vec![], "state issues");
+    }
+
+    /// The bidders, then the seller of every auction.
+    fn accounts(&self) -> Vec<Addr> {
+        self.bidders.iter().cloned().chain([self.suite.admin.clone()]).collect()
+    }
+
+    fn all_auctions(&self) -> Vec<(AuctionId, Auction)> {
+        collect_pages(|start_after| QueryMsg::Auctions { status: None, start_after, start_before: None, limit: None, order: None }, &self.suite)
+    }
+
+    fn all_bid_items(&self, auction_id: AuctionId) -> Vec<(BidItemId, BidItem)> {
+        collect_pages(|start_after| QueryMsg::BidItemsByAuctionId { auction_id, start_after, start_before: None, limit: None, order: None }, &self.suite)
+    }
+
+    fn all_bids(&self, bid_item_id: BidItemId) -> Vec<(BidId, Bid)> {
+        collect_pages(|start_after| QueryMsg::BidsByBidItem { bid_item_id, start_after, start_before: None, limit: None, order: None }, &self.suite)
+    }
+}
+
+fn pick<T>(items: &[T], index: usize) -> Option<&T> {
+    if items.is_empty() {
+        None
+    } else {
+        items.get(index % items.len())
+    }
+}
+
+/// Follows the `next` cursor until the last page.
+fn collect_pages<K, T>(msg: impl Fn(Option<K>) -> QueryMsg, suite: &BiddingSuite) -> Vec<(K, T)>
+where
+    K: serde::de::DeserializeOwned,
+    T: serde::de::DeserializeOwned,
+{
+    let mut items = vec![];
+    let mut start_after = None;
+
+    loop {
+        let page: PagedResp<K, T> = suite.query(&msg(start_after));
+        items.extend(page.items);
+
+        match page.next {
+            Some(next) => start_after = Some(next),
+            None => return items,
+        }
+    }
+}
+
+/// The highest bid wins, ties go to the earliest one. Nobody wins below the reserve.
+fn highest_bidder(bids: &[(BidId, Bid)], reserve_price: Option<Price>) -> Option<Bidder> {
+    let reserve_price = match reserve_price {
+        Some(Price::Native(amount)) => amount,
+        Some(Price::Quote(_)) => unreachable!("scenarios have no oracle"),
+        None => Uint128::zero(),
+    };
+
+    bids.iter()
+        .fold(None, |best: Option<&Bid>, (_, bid)| match best {
+            Some(best) if best.amount >= bid.amount => Some(best),
+            _ => Some(bid),
+        })
+        .filter(|bid| bid.amount >= reserve_price)
+        .map(|bid| bid.bidder.clone())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(48))]
+
+    #[test]
+    fn auction_state_machine_invariants(ops in proptest::collection::vec(op(), 1..40)) {
+        let mut scenario = Scenario::new();
+
+        for op in &ops {
+            scenario.apply(op);
+            scenario.check_invariants();
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_binary, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Timestamp};
+use cw_multi_test::{Contract, ContractWrapper};
+use cw_storage_plus::Item;
+
+use crate::msg::{OraclePriceResp, OracleQueryMsg};
+
+// A price oracle answering `OracleQueryMsg::Price` with whatever price it was last given, for any
+// pair of assets.
+
+const PRICE: Item<OraclePriceResp> = Item::new("price");
+
+#[cw_serde]
+pub enum MockOracleMsg {
+    SetPrice {
+        price: Decimal,
+        twap: Decimal,
+        updated_at: Timestamp,
+    },
+}
+
+fn set_price(deps: DepsMut, _env: Env, _info: MessageInfo, msg: MockOracleMsg) -> StdResult<Response> {
+    let MockOracleMsg::SetPrice { price, twap, updated_at } = msg;
+    PRICE.save(deps.storage, &OraclePriceResp { price, twap, updated_at })?;
+
+    Ok(Response::new())
+}
+
+fn query(deps: Deps, _env: Env, msg: OracleQueryMsg) -> StdResult<Binary> {
+    match msg {
+        OracleQueryMsg::Price { .. } => to_json_binary(&PRICE.load(deps.storage)?),
+    }
+}
+
+pub fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(set_price, set_price, query))
+}
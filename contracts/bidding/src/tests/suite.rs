@@ -0,0 +1,408 @@
+use cosmwasm_std::testing::{mock_env, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Attribute, Coin, DepsMut, Empty, Env, Event, IbcAcknowledgement, IbcChannel, IbcChannelCloseMsg,
+    IbcChannelConnectMsg, IbcChannelOpenMsg, IbcEndpoint, IbcOrder, IbcPacket, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
+    IbcTimeout, QuerierWrapper, Reply, ReplyOn, StdAck, StdError, SubMsg, SubMsgResult, Timestamp,
+};
+use cw_multi_test::{App, AppBuilder, AppResponse, BankKeeper, ContractWrapper, DistributionKeeper, Executor, FailingModule, StakeKeeper, WasmKeeper};
+use serde::de::DeserializeOwned;
+
+use crate::contract::{execute, instantiate, query, reply, sudo};
+use crate::error::ContractError;
+use crate::ibc::{ibc_channel_close, ibc_channel_connect, ibc_channel_open, ibc_packet_ack, ibc_packet_receive, ibc_packet_timeout, ICS20_VERSION};
+use crate::msg::{BidItemInput, ConfigUpdate, CrankStatusResp, ExecuteMsg, Ics20Packet, InstantiateMsg, QueryMsg, SudoMsg, VerifyStateResp};
+use crate::state::{Auction, AuctionId, AuctionStatus, BidId, BidItem, BidItemId, BidItemStatus, Bidder, IbcChannelState, PayoutMode, IBC_CHANNELS};
+use crate::tests::transfer::MockIbc;
+use crate::tests::_DENOM;
+
+/// The default app with the contract's packets going through `MockIbc`.
+pub type BiddingApp = App<BankKeeper, MockApi, MockStorage, FailingModule<Empty, Empty, Empty>, WasmKeeper<Empty, Empty>, StakeKeeper, DistributionKeeper, MockIbc>;
+
+// Crank calls before crank_until_idle gives up, so a crank that never drains fails the test
+const MAX_CRANK_CALLS: usize = 100;
+
+/// Sets up the app, the contract and funded accounts for a test.
+#[derive(Default)]
+pub struct BiddingSuiteBuilder {
+    accounts: Vec<(String, u128)>,
+    config: Option<ConfigUpdate>,
+}
+
+impl BiddingSuiteBuilder {
+    /// Adds an account funded with `balance` of the contract denom, its address is `addr_make(name)`.
+    pub fn with_account(mut self, name: &str, balance: u128) -> Self {
+        self.accounts.push((name.to_string(), balance));
+        self
+    }
+
+    pub fn with_config(mut self, config: ConfigUpdate) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn build(self) -> BiddingSuite {
+        let mut app = AppBuilder::new().with_ibc(MockIbc).build(|_, _, _| {});
+
+        let code = ContractWrapper::new(execute, instantiate, query).with_sudo(sudo).with_reply(reply);
+        let code_id = app.store_code(Box::new(code));
+
+        let admin = app.api().addr_make("owner");
+
+        let contract = app
+            .instantiate_contract(
+                code_id,
+                admin.clone(),
+                &InstantiateMsg {
+                    admin: admin.clone(),
+                },
+                &[],
+                "Contract",
+                None,
+            )
+            .unwrap();
+
+        let accounts: Vec<(Addr, u128)> = self.accounts
+            .iter()
+            .map(|(name, balance)| (app.api().addr_make(name), *balance))
+            .collect();
+
+        app.init_modules(|router, _, storage| {
+            for (account, balance) in &accounts {
+                router
+                    .bank
+                    .init_balance(storage, account, vec![Coin::new(*balance, _DENOM)])
+                    .unwrap();
+            }
+        });
+
+        let mut suite = BiddingSuite { app, contract, admin, received_packets: 0 };
+
+        if let Some(config) = self.config {
+            let admin = suite.admin.clone();
+            suite.execute(&admin, &ExecuteMsg::UpdateConfig(config), &[]).unwrap();
+        }
+
+        suite
+    }
+}
+
+pub struct BiddingSuite {
+    pub app: BiddingApp,
+    pub contract: Addr,
+    pub admin: Addr,
+    received_packets: u64,
+}
+
+/// A packet the contract sent, as recorded by `MockIbc`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentPacket {
+    pub channel_id: String,
+    pub sequence: u64,
+    pub transfer: Ics20Packet,
+}
+
+impl BiddingSuite {
+    pub fn builder() -> BiddingSuiteBuilder {
+        BiddingSuiteBuilder::default()
+    }
+
+    /// Address of an account, the same one the builder funded.
+    pub fn addr(&self, name: &str) -> Addr {
+        self.app.api().addr_make(name)
+    }
+
+    /// Executes a message, errors other than contract errors are wrapped into `ContractError::Std`.
+    pub fn execute(&mut self, sender: &Addr, msg: &ExecuteMsg, funds: &[Coin]) -> Result<AppResponse, ContractError> {
+        self.app
+            .execute_contract(sender.clone(), self.contract.clone(), msg, funds)
+            .map_err(|err| match err.downcast::<ContractError>() {
+                Ok(err) => err,
+                Err(err) => ContractError::Std(StdError::generic_err(err.to_string())),
+            })
+    }
+
+    pub fn sudo(&mut self, msg: &SudoMsg) -> Result<AppResponse, ContractError> {
+        self.app
+            .wasm_sudo(self.contract.clone(), msg)
+            .map_err(|err| match err.downcast::<ContractError>() {
+                Ok(err) => err,
+                Err(err) => ContractError::Std(StdError::generic_err(err.to_string())),
+            })
+    }
+
+    pub fn query<T: DeserializeOwned>(&self, msg: &QueryMsg) -> T {
+        self.app.wrap().query_wasm_smart(&self.contract, msg).unwrap()
+    }
+
+    /// Creates an auction with the given bid items as the admin.
+    pub fn create_auction(&mut self, bid_items: &[&str]) -> Result<(AuctionId, Vec<BidItemId>), ContractError> {
+        self.create_auction_with(bid_items, None, None)
+    }
+
+    pub fn create_auction_with(&mut self, bid_items: &[&str], ends_at: Option<Timestamp>, payout_mode: Option<PayoutMode>) -> Result<(AuctionId, Vec<BidItemId>), ContractError> {
+        let msg = ExecuteMsg::CreateAuction {
+            name: "TestAuction".to_string(),
+            description: None,
+            terms: None,
+            ends_at,
+            payout_mode,
+            owner: None,
+            bid_items: bid_items.iter().map(|name| BidItemInput::new(*name)).collect(),
+        };
+
+        let admin = self.admin.clone();
+        self.create_auction_from(&admin, &msg)
+    }
+
+    /// Sends a `CreateAuction` message, returns the ids of the auction and of its bid items.
+    pub fn create_auction_from(&mut self, sender: &Addr, msg: &ExecuteMsg) -> Result<(AuctionId, Vec<BidItemId>), ContractError> {
+        let resp = self.execute(sender, msg, &[])?;
+
+        let auction_id = AuctionId(event_attr(&resp, "auction_created", "auction_id")[0].parse().unwrap());
+        let bid_item_ids = event_attr(&resp, "bid_item_added", "bid_item_id")
+            .iter()
+            .map(|id| BidItemId(id.parse().unwrap()))
+            .collect();
+
+        Ok((auction_id, bid_item_ids))
+    }
+
+    /// Bids with attached funds.
+    pub fn bid(&mut self, bidder: &Addr, bid_item_id: BidItemId, amount: u128) -> Result<BidId, ContractError> {
+        let resp = self.execute(bidder, &ExecuteMsg::PlaceBid { bid_item_id, amount: None }, &[Coin::new(amount, _DENOM)])?;
+        Ok(BidId(event_attr(&resp, "bid_placed", "bid_id")[0].parse().unwrap()))
+    }
+
+    pub fn bid_from_deposit(&mut self, bidder: &Addr, bid_item_id: BidItemId, amount: u128) -> Result<BidId, ContractError> {
+        let resp = self.execute(bidder, &ExecuteMsg::PlaceBid { bid_item_id, amount: Some(amount.into()) }, &[])?;
+        Ok(BidId(event_attr(&resp, "bid_placed", "bid_id")[0].parse().unwrap()))
+    }
+
+    // Multi-test doesn't route IBC entry points, so the suite calls them on the contract's storage
+    // the way the chain would: the handshake of a channel the other chain opens from its transfer
+    // port, the packets it sends and the acknowledgements and timeouts of the packets `MockIbc`
+    // sent. The messages of their responses are sent by the contract, replying to it when they
+    // fail and it asked for it, their events come back prefixed like wasm events.
+
+    /// The ICS-20 channel from the transfer port of the other chain, `counterparty_channel_id` is
+    /// its end.
+    pub fn ics20_channel(&self, channel_id: &str, counterparty_channel_id: &str) -> IbcChannel {
+        IbcChannel::new(
+            IbcEndpoint { port_id: format!("wasm.{}", self.contract), channel_id: channel_id.to_string() },
+            IbcEndpoint { port_id: "transfer".to_string(), channel_id: counterparty_channel_id.to_string() },
+            IbcOrder::Unordered,
+            ICS20_VERSION,
+            "connection-0",
+        )
+    }
+
+    pub fn open_channel(&mut self, channel_id: &str, counterparty_channel_id: &str) -> AppResponse {
+        let channel = self.ics20_channel(channel_id, counterparty_channel_id);
+        self.open_channel_with(channel).unwrap()
+    }
+
+    /// Runs the handshake of a channel opened by the other chain.
+    pub fn open_channel_with(&mut self, channel: IbcChannel) -> Result<AppResponse, ContractError> {
+        self.call_ibc(|deps, env| ibc_channel_open(deps, env, IbcChannelOpenMsg::new_try(channel.clone(), channel.version.clone())))?;
+        let resp = self.call_ibc(|deps, env| ibc_channel_connect(deps, env, IbcChannelConnectMsg::new_confirm(channel.clone())))?;
+
+        self.app.init_modules(|router, _, storage| router.ibc.open_channel(storage, &channel.endpoint.channel_id)).unwrap();
+
+        Ok(self.dispatch(resp.attributes, resp.events, resp.messages))
+    }
+
+    /// Closes a channel from the other chain's end.
+    pub fn close_channel(&mut self, channel_id: &str) -> AppResponse {
+        let counterparty = self.ibc_channel(channel_id).counterparty;
+        let channel = self.ics20_channel(channel_id, &counterparty.channel_id);
+
+        let resp = self.call_ibc(|deps, env| ibc_channel_close(deps, env, IbcChannelCloseMsg::new_confirm(channel))).unwrap();
+        self.app.init_modules(|router, _, storage| router.ibc.close_channel(storage, channel_id));
+
+        self.dispatch(resp.attributes, resp.events, resp.messages)
+    }
+
+    pub fn ibc_channel(&self, channel_id: &str) -> IbcChannelState {
+        IBC_CHANNELS.query(&self.app.wrap(), self.contract.clone(), channel_id).unwrap().unwrap()
+    }
+
+    /// Delivers a packet the other chain sent over `channel_id`, along with its acknowledgement.
+    pub fn receive_packet(&mut self, channel_id: &str, transfer: &Ics20Packet) -> (StdAck, AppResponse) {
+        self.received_packets += 1;
+
+        let counterparty = self.ibc_channel(channel_id).counterparty;
+        let dest = IbcEndpoint { port_id: format!("wasm.{}", self.contract), channel_id: channel_id.to_string() };
+        let timeout = IbcTimeout::with_timestamp(self.app.block_info().time.plus_seconds(600));
+        let packet = IbcPacket::new(to_json_binary(transfer).unwrap(), counterparty, dest, self.received_packets, timeout);
+        let msg = IbcPacketReceiveMsg::new(packet, self.addr("relayer"));
+
+        let resp = self.call_ibc(|deps, env| ibc_packet_receive(deps, env, msg)).unwrap();
+        let ack = from_json(resp.acknowledgement.unwrap()).unwrap();
+
+        (ack, self.dispatch(resp.attributes, resp.events, resp.messages))
+    }
+
+    pub fn ack_packet(&mut self, sent: &SentPacket, ack: StdAck) -> AppResponse {
+        let msg = IbcPacketAckMsg::new(IbcAcknowledgement::new(ack), self.sent_ibc_packet(sent), self.addr("relayer"));
+
+        let resp = self.call_ibc(|deps, env| ibc_packet_ack(deps, env, msg)).unwrap();
+        self.dispatch(resp.attributes, resp.events, resp.messages)
+    }
+
+    pub fn timeout_packet(&mut self, sent: &SentPacket) -> AppResponse {
+        let msg = IbcPacketTimeoutMsg::new(self.sent_ibc_packet(sent), self.addr("relayer"));
+
+        let resp = self.call_ibc(|deps, env| ibc_packet_timeout(deps, env, msg)).unwrap();
+        self.dispatch(resp.attributes, resp.events, resp.messages)
+    }
+
+    fn sent_ibc_packet(&self, sent: &SentPacket) -> IbcPacket {
+        let counterparty = self.ibc_channel(&sent.channel_id).counterparty;
+        let src = IbcEndpoint { port_id: format!("wasm.{}", self.contract), channel_id: sent.channel_id.clone() };
+        let timeout = IbcTimeout::with_timestamp(self.app.block_info().time.plus_seconds(600));
+
+        IbcPacket::new(to_json_binary(&sent.transfer).unwrap(), src, counterparty, sent.sequence, timeout)
+    }
+
+    fn call_ibc<T>(&mut self, entry_point: impl FnOnce(DepsMut, Env) -> Result<T, ContractError>) -> Result<T, ContractError> {
+        let mut env = mock_env();
+        env.block = self.app.block_info();
+        env.contract.address = self.contract.clone();
+
+        let api = MockApi::default();
+        let querier: MockQuerier = MockQuerier::new(&[]);
+
+        let mut storage = self.app.contract_storage_mut(&self.contract);
+        let deps = DepsMut { storage: storage.as_mut(), api: &api, querier: QuerierWrapper::new(&querier) };
+
+        entry_point(deps, env)
+    }
+
+    fn dispatch(&mut self, attributes: Vec<Attribute>, events: Vec<Event>, messages: Vec<SubMsg>) -> AppResponse {
+        let mut events: Vec<Event> = std::iter::once(Event::new("wasm").add_attributes(attributes))
+            .chain(events.into_iter().map(|event| Event::new(format!("wasm-{}", event.ty)).add_attributes(event.attributes)))
+            .collect();
+
+        for submsg in messages {
+            match self.app.execute(self.contract.clone(), submsg.msg) {
+                Ok(resp) => events.extend(resp.events),
+                Err(error) if matches!(submsg.reply_on, ReplyOn::Error | ReplyOn::Always) => {
+                    #[allow(deprecated)]
+                    let msg = Reply { id: submsg.id, payload: submsg.payload, gas_used: 0, result: SubMsgResult::Err(error.to_string()) };
+
+                    let resp = self.call_ibc(|deps, env| reply(deps, env, msg)).unwrap();
+                    events.extend(self.dispatch(resp.attributes, resp.events, resp.messages).events);
+                },
+                Err(error) => panic!("{error}"),
+            }
+        }
+
+        AppResponse { events, ..AppResponse::default() }
+    }
+
+    pub fn set_auction_state(&mut self, id: AuctionId, status: AuctionStatus) -> Result<AppResponse, ContractError> {
+        let admin = self.admin.clone();
+        self.execute(&admin, &ExecuteMsg::SetAuctionState { id, status }, &[])
+    }
+
+    /// Moves the auction to pending completion so the crank picks it up.
+    pub fn close_auction(&mut self, id: AuctionId) -> Result<AppResponse, ContractError> {
+        self.set_auction_state(id, AuctionStatus::PendingCompletion)
+    }
+
+    pub fn crank(&mut self, max_items: Option<u32>) -> Result<AppResponse, ContractError> {
+        let admin = self.admin.clone();
+        self.execute(&admin, &ExecuteMsg::AdvanceCrank { max_items }, &[])
+    }
+
+    /// Cranks until there's nothing left to do, returns the number of successful calls.
+    pub fn crank_until_idle(&mut self) -> usize {
+        for calls in 0..MAX_CRANK_CALLS {
+            match self.crank(None) {
+                Ok(_) => {},
+                Err(ContractError::NothingToCrank) => return calls,
+                Err(err) => panic!("Crank failed: {err}"),
+            }
+        }
+
+        panic!("Crank didn't go idle after {MAX_CRANK_CALLS} calls");
+    }
+
+    pub fn auction(&self, id: AuctionId) -> Auction {
+        self.query(&QueryMsg::Auction { id })
+    }
+
+    pub fn bid_item(&self, id: BidItemId) -> BidItem {
+        self.query(&QueryMsg::BidItem { id })
+    }
+
+    pub fn crank_status(&self) -> CrankStatusResp {
+        self.crank_status_page(None, None)
+    }
+
+    pub fn crank_status_page(&self, start_after: Option<AuctionId>, limit: Option<u32>) -> CrankStatusResp {
+        self.query(&QueryMsg::CrankStatus { start_after, limit })
+    }
+
+    /// Follows the `VerifyState` cursor to the end, returns every page.
+    pub fn verify_state(&self, auction_id: Option<AuctionId>, limit: Option<u32>) -> Vec<VerifyStateResp> {
+        let mut pages: Vec<VerifyStateResp> = vec![];
+        let mut start_after = None;
+
+        loop {
+            let page: VerifyStateResp = self.query(&QueryMsg::VerifyState { auction_id, start_after, limit });
+            start_after = page.next.clone();
+            pages.push(page);
+
+            if start_after.is_none() {
+                return pages;
+            }
+        }
+    }
+
+    pub fn balance(&self, addr: &Addr) -> u128 {
+        self.app.wrap().query_balance(addr, _DENOM).unwrap().amount.u128()
+    }
+
+    pub fn assert_balance(&self, addr: &Addr, expected: u128) {
+        assert_eq!(self.balance(addr), expected, "balance of {addr}");
+    }
+
+    pub fn assert_auction_state(&self, id: AuctionId, expected: AuctionStatus) {
+        assert_eq!(self.auction(id).current_state, expected, "state of auction {id}");
+    }
+
+    pub fn assert_bid_item(&self, id: BidItemId, expected: BidItemStatus, winner: Option<&Addr>) {
+        let bid_item = self.bid_item(id);
+
+        assert_eq!(bid_item.current_state, expected, "state of bid item {id}");
+        assert_eq!(bid_item.winner, winner.cloned().map(Bidder::Local), "winner of bid item {id}");
+    }
+}
+
+/// The packets `MockIbc` sent in a response.
+pub fn sent_packets(resp: &AppResponse) -> Vec<SentPacket> {
+    resp.events
+        .iter()
+        .filter(|event| event.ty == "send_packet")
+        .map(|event| {
+            let attr = |key: &str| event.attributes.iter().find(|attr| attr.key == key).unwrap().value.clone();
+
+            SentPacket {
+                channel_id: attr("channel_id"),
+                sequence: attr("sequence").parse().unwrap(),
+                transfer: from_json(attr("data").as_bytes()).unwrap(),
+            }
+        })
+        .collect()
+}
+
+/// Values of an attribute over every `wasm-{ty}` event of a response.
+pub fn event_attr(resp: &AppResponse, ty: &str, key: &str) -> Vec<String> {
+    resp.events
+        .iter()
+        .filter(|ev| ev.ty == format!("wasm-{ty}"))
+        .flat_map(|ev| ev.attributes.iter().filter(|attr| attr.key == key))
+        .map(|attr| attr.value.clone())
+        .collect()
+}